@@ -0,0 +1,306 @@
+//! 防止保存时丢失数据：原子写入、滚动备份和自动保存日志
+//!
+//! - 原子写入：先写同目录下的临时文件并 fsync，再重命名覆盖目标文件，
+//!   写入中途崩溃或磁盘写满时原文件保持完整。
+//! - 滚动备份：覆盖已有文件前把旧内容复制到应用数据目录，每个文件保留最近 N 份。
+//! - 自动保存日志：定期记录未保存标签页的内容，下次启动时可以恢复。
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::srt_parser::{SubtitleEntry, SubtitleFileFormat};
+
+/// 原子写入文件：写入同目录下的临时文件、fsync 后重命名为目标文件
+///
+/// 目标是符号链接时写入链接指向的文件；目录不允许创建文件时退回为直接写入。
+pub fn write_atomic(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    let contents = contents.as_ref();
+    let target = match fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_symlink() => fs::canonicalize(path)?,
+        _ => path.to_path_buf(),
+    };
+    let existing = fs::metadata(&target).ok();
+    if existing.as_ref().is_some_and(|meta| meta.permissions().readonly()) {
+        return Err(io::Error::new(io::ErrorKind::PermissionDenied, "Permission denied: file is read-only"));
+    }
+
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid file path"))?;
+    let dir = match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let temp = dir.join(format!(".{}.{}.tmp", file_name.to_string_lossy(), std::process::id()));
+
+    let mut file = match File::create(&temp) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+            // 目录只读但文件可写（如部分网络盘），只能覆盖写入
+            let mut file = File::create(&target)?;
+            file.write_all(contents)?;
+            return file.sync_all();
+        }
+        Err(e) => return Err(e),
+    };
+
+    let result = (|| {
+        file.write_all(contents)?;
+        file.sync_all()?;
+        drop(file);
+        if let Some(meta) = &existing {
+            fs::set_permissions(&temp, meta.permissions())?;
+        }
+        fs::rename(&temp, &target)?;
+        // 重命名本身也要落盘，否则断电后目录中可能仍是旧文件
+        #[cfg(unix)]
+        if let Ok(dir) = File::open(&dir) {
+            let _ = dir.sync_all();
+        }
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// FNV-1a 哈希，用于区分同名但路径不同的文件
+fn path_hash(path: &Path) -> u64 {
+    path.to_string_lossy().bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// 文件名中不适合用作目录名的字符替换为下划线
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect()
+}
+
+/// 文件的备份目录：<备份根目录>/<文件名>-<路径哈希>
+pub fn backup_dir_for(backup_root: &Path, file_path: &Path) -> PathBuf {
+    let stem = file_path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    backup_root.join(format!("{}-{:016x}", sanitize(&stem), path_hash(file_path)))
+}
+
+/// UTC 时间戳，格式为 20260101-120000-000，按字符串排序即按时间排序
+fn timestamp(time: SystemTime) -> String {
+    let ms = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+    let (days, ms_of_day) = (ms / 86_400_000, ms % 86_400_000);
+
+    // 由 1970-01-01 起的天数换算公历日期
+    let z = days + 719_468;
+    let (era, doe) = (z / 146_097, z % 146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}-{:03}",
+        year,
+        month,
+        day,
+        ms_of_day / 3_600_000,
+        ms_of_day / 60_000 % 60,
+        ms_of_day / 1000 % 60,
+        ms_of_day % 1000
+    )
+}
+
+/// 覆盖文件前备份旧内容，每个文件保留最近 keep 份
+///
+/// 最近一份备份不足 min_interval 时不再备份，避免频繁的自动保存把较早的版本挤掉。
+/// 文件不存在或 keep 为 0 时不备份，返回 None。
+pub fn backup_file(
+    file_path: &Path,
+    backup_root: &Path,
+    keep: usize,
+    min_interval: Duration,
+) -> Result<Option<PathBuf>, String> {
+    if keep == 0 || !file_path.is_file() {
+        return Ok(None);
+    }
+    let dir = backup_dir_for(backup_root, file_path);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backup directory: {}", e))?;
+
+    let mut backups = list_backup_files(&dir);
+    let now = SystemTime::now();
+    let recent = backups
+        .last()
+        .and_then(|latest| fs::metadata(latest).and_then(|m| m.modified()).ok())
+        .is_some_and(|modified| now.duration_since(modified).unwrap_or_default() < min_interval);
+
+    let mut created = None;
+    if !recent {
+        let stem = file_path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let mut name = format!("{}.{}", sanitize(&stem), timestamp(now));
+        if let Some(ext) = file_path.extension() {
+            name = format!("{}.{}", name, ext.to_string_lossy());
+        }
+        let backup = dir.join(name);
+        fs::copy(file_path, &backup).map_err(|e| format!("Failed to back up file: {}", e))?;
+        backups.push(backup.clone());
+        created = Some(backup);
+    }
+
+    let excess = backups.len().saturating_sub(keep);
+    for old in &backups[..excess] {
+        let _ = fs::remove_file(old);
+    }
+    Ok(created)
+}
+
+/// 备份目录中的备份文件，按时间从旧到新排列
+fn list_backup_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.is_file())
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// 自动保存日志中的一个未保存标签页
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalTab {
+    pub file_path: String,
+    pub entries: Vec<SubtitleEntry>,
+    /// 保存时沿用的原文件信息（编码、BOM、文件头、帧率）
+    #[serde(default)]
+    pub format: SubtitleFileFormat,
+    #[serde(default)]
+    pub audio_path: Option<String>,
+}
+
+/// 自动保存日志
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutosaveJournal {
+    pub tabs: Vec<JournalTab>,
+    /// 写入时间（毫秒时间戳）
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+/// 写入自动保存日志；没有未保存的标签页时删除日志文件
+pub fn write_journal(path: &Path, journal: &AutosaveJournal) -> Result<(), String> {
+    if journal.tabs.is_empty() {
+        return match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(format!("Failed to clear autosave journal: {}", e)),
+            _ => Ok(()),
+        };
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create journal directory: {}", e))?;
+    }
+    let json = serde_json::to_vec(journal).map_err(|e| format!("Failed to serialize autosave journal: {}", e))?;
+    write_atomic(path, json).map_err(|e| format!("Failed to write autosave journal: {}", e))
+}
+
+/// 读取自动保存日志，日志不存在或为空时返回 None
+pub fn read_journal(path: &Path) -> Result<Option<AutosaveJournal>, String> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read autosave journal: {}", e)),
+    };
+    let journal: AutosaveJournal =
+        serde_json::from_slice(&data).map_err(|e| format!("Failed to parse autosave journal: {}", e))?;
+    Ok(Some(journal).filter(|j| !j.tabs.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vosub-file-safety-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_without_leftovers() {
+        let dir = temp_dir("atomic");
+        let path = dir.join("a.srt");
+        write_atomic(&path, "old").unwrap();
+        write_atomic(&path, "new content").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new content");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        let mut perms = fs::metadata(&path).unwrap().permissions();
+        perms.set_readonly(true);
+        fs::set_permissions(&path, perms).unwrap();
+        assert!(write_atomic(&path, "blocked").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new content");
+        let mut perms = fs::metadata(&path).unwrap().permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        perms.set_readonly(false);
+        fs::set_permissions(&path, perms).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rolling_backups() {
+        let dir = temp_dir("backup");
+        let root = dir.join("backups");
+        let path = dir.join("movie.srt");
+        assert_eq!(backup_file(&path, &root, 3, Duration::ZERO).unwrap(), None);
+
+        for i in 0..5 {
+            fs::write(&path, format!("version {}", i)).unwrap();
+            assert!(backup_file(&path, &root, 3, Duration::ZERO).unwrap().is_some());
+            std::thread::sleep(Duration::from_millis(2));
+        }
+        let backups = list_backup_files(&backup_dir_for(&root, &path));
+        assert_eq!(backups.len(), 3);
+        assert_eq!(fs::read_to_string(&backups[0]).unwrap(), "version 2");
+        assert_eq!(fs::read_to_string(&backups[2]).unwrap(), "version 4");
+        assert!(backups[2].to_string_lossy().ends_with(".srt"));
+
+        // 间隔内不再备份
+        assert_eq!(backup_file(&path, &root, 3, Duration::from_secs(60)).unwrap(), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_timestamp_and_journal_roundtrip() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_792_240_380_123);
+        assert_eq!(timestamp(time), "20261017-123300-123");
+
+        let dir = temp_dir("journal");
+        let path = dir.join("journal").join("autosave.json");
+        assert!(read_journal(&path).unwrap().is_none());
+        let journal = AutosaveJournal {
+            tabs: vec![JournalTab {
+                file_path: "/tmp/a.srt".to_string(),
+                entries: vec![SubtitleEntry { id: 1, text: "hello".to_string(), ..Default::default() }],
+                format: SubtitleFileFormat::default(),
+                audio_path: None,
+            }],
+            saved_at: 1,
+        };
+        write_journal(&path, &journal).unwrap();
+        let restored = read_journal(&path).unwrap().unwrap();
+        assert_eq!(restored.tabs[0].entries[0].text, "hello");
+
+        write_journal(&path, &AutosaveJournal::default()).unwrap();
+        assert!(!path.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,241 @@
+//! 监视已打开的字幕和音频文件在外部的修改、删除和重命名
+//!
+//! 监视文件所在目录而不是文件本身：其他程序（包括本应用）常以“写临时文件再重命名”的方式保存，
+//! 直接监视文件会在第一次替换后失效。同一批文件系统事件静默一段时间后才汇总判断，
+//! 并与上次记录的修改时间和大小比较，本应用自己的保存（见 acknowledge）不会被报告。
+
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// 事件静默多久后汇总判断（编辑器保存时常连续产生多个事件）
+const SETTLE_DELAY: Duration = Duration::from_millis(300);
+
+/// 变化类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileChangeKind {
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+/// 文件变化
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChange {
+    pub path: String,
+    pub kind: FileChangeKind,
+    /// 重命名后的路径（仅 Renamed）
+    pub new_path: Option<String>,
+}
+
+/// 用于判断文件是否变化：修改时间和大小
+type Fingerprint = Option<(SystemTime, u64)>;
+
+fn fingerprint(path: &Path) -> Fingerprint {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+#[derive(Default)]
+struct WatchState {
+    /// 被监视的文件及最近一次确认的状态
+    files: HashMap<PathBuf, Fingerprint>,
+    /// 被监视的目录及其中被监视的文件数
+    dirs: HashMap<PathBuf, usize>,
+}
+
+pub struct FileWatcher {
+    watcher: Mutex<RecommendedWatcher>,
+    state: Arc<Mutex<WatchState>>,
+}
+
+fn parent_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+impl FileWatcher {
+    /// 创建监视器，文件变化时在后台线程调用 on_change
+    pub fn new(on_change: impl Fn(FileChange) + Send + 'static) -> Result<Self, String> {
+        let (tx, rx) = mpsc::channel::<Event>();
+        let watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+            if let Ok(event) = result {
+                let _ = tx.send(event);
+            }
+        })
+        .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+
+        let state = Arc::new(Mutex::new(WatchState::default()));
+        let worker_state = Arc::clone(&state);
+        std::thread::spawn(move || {
+            // 待判断的文件，以及事件中看到的重命名目标
+            let mut pending: HashMap<PathBuf, Option<PathBuf>> = HashMap::new();
+            loop {
+                match rx.recv_timeout(SETTLE_DELAY) {
+                    Ok(event) => collect(&worker_state, &event, &mut pending),
+                    Err(RecvTimeoutError::Timeout) => {
+                        for change in settle(&worker_state, pending.drain()) {
+                            on_change(change);
+                        }
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+
+        Ok(FileWatcher { watcher: Mutex::new(watcher), state })
+    }
+
+    /// 开始监视文件；已在监视时更新记录的状态
+    pub fn watch(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref().to_path_buf();
+        let dir = parent_dir(&path);
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        if state.files.insert(path.clone(), fingerprint(&path)).is_some() {
+            return Ok(());
+        }
+        if !state.dirs.contains_key(&dir) {
+            let mut watcher = self.watcher.lock().map_err(|e| e.to_string())?;
+            if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                state.files.remove(&path);
+                return Err(format!("Failed to watch {}: {}", dir.display(), e));
+            }
+        }
+        *state.dirs.entry(dir).or_default() += 1;
+        Ok(())
+    }
+
+    /// 停止监视文件
+    pub fn unwatch(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let Ok(mut state) = self.state.lock() else { return };
+        if state.files.remove(path).is_none() {
+            return;
+        }
+        let dir = parent_dir(path);
+        if let Some(count) = state.dirs.get_mut(&dir) {
+            *count -= 1;
+            if *count == 0 {
+                state.dirs.remove(&dir);
+                if let Ok(mut watcher) = self.watcher.lock() {
+                    let _ = watcher.unwatch(&dir);
+                }
+            }
+        }
+    }
+
+    /// 记录文件当前状态（本应用写入文件后调用），此前的变化不再报告
+    pub fn acknowledge(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(known) = state.files.get_mut(path) {
+                *known = fingerprint(path);
+            }
+        }
+    }
+}
+
+/// 记录事件涉及的被监视文件
+fn collect(state: &Mutex<WatchState>, event: &Event, pending: &mut HashMap<PathBuf, Option<PathBuf>>) {
+    let Ok(state) = state.lock() else { return };
+    if let (EventKind::Modify(ModifyKind::Name(RenameMode::Both)), [from, to]) = (&event.kind, event.paths.as_slice()) {
+        if state.files.contains_key(from) {
+            pending.insert(from.clone(), Some(to.clone()));
+        }
+        if state.files.contains_key(to) {
+            pending.entry(to.clone()).or_default();
+        }
+        return;
+    }
+    for path in &event.paths {
+        if state.files.contains_key(path) {
+            pending.entry(path.clone()).or_default();
+        }
+    }
+}
+
+/// 按文件当前状态判断变化类型，并更新记录的状态
+fn settle(
+    state: &Mutex<WatchState>,
+    pending: impl Iterator<Item = (PathBuf, Option<PathBuf>)>,
+) -> Vec<FileChange> {
+    let Ok(mut state) = state.lock() else { return Vec::new() };
+    let mut changes = Vec::new();
+    for (path, renamed_to) in pending {
+        let current = fingerprint(&path);
+        let Some(known) = state.files.get_mut(&path) else { continue };
+        if current == *known {
+            continue;
+        }
+        *known = current;
+        let change = match (current, renamed_to) {
+            (Some(_), _) => FileChange { path: path_string(&path), kind: FileChangeKind::Modified, new_path: None },
+            (None, Some(to)) => FileChange {
+                path: path_string(&path),
+                kind: FileChangeKind::Renamed,
+                new_path: Some(path_string(&to)),
+            },
+            (None, None) => FileChange { path: path_string(&path), kind: FileChangeKind::Deleted, new_path: None },
+        };
+        changes.push(change);
+    }
+    changes
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recv(rx: &mpsc::Receiver<FileChange>) -> FileChange {
+        rx.recv_timeout(Duration::from_secs(5)).expect("no file change reported")
+    }
+
+    #[test]
+    fn test_reports_external_changes_only() {
+        let dir = std::env::temp_dir().join(format!("vosub-watcher-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("a.srt");
+        fs::write(&path, "1").unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let watcher = FileWatcher::new(move |change| tx.send(change).unwrap()).unwrap();
+        watcher.watch(&path).unwrap();
+
+        // 本应用的保存不报告
+        fs::write(&path, "22").unwrap();
+        watcher.acknowledge(&path);
+        assert!(rx.recv_timeout(SETTLE_DELAY * 3).is_err());
+
+        // 外部以替换方式保存
+        fs::write(dir.join("tmp"), "333").unwrap();
+        fs::rename(dir.join("tmp"), &path).unwrap();
+        assert_eq!(recv(&rx).kind, FileChangeKind::Modified);
+
+        let moved = dir.join("b.srt");
+        fs::rename(&path, &moved).unwrap();
+        let change = recv(&rx);
+        assert_eq!(change.kind, FileChangeKind::Renamed);
+        assert_eq!(change.new_path.as_deref(), Some(moved.to_string_lossy().as_ref()));
+
+        watcher.watch(&moved).unwrap();
+        fs::remove_file(&moved).unwrap();
+        assert_eq!(recv(&rx).kind, FileChangeKind::Deleted);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1432,16 +1432,6 @@ if __name__ == "__main__":
 }
 
 
-/// 毫秒转 TimeStamp
-fn ms_to_timestamp(ms: u32) -> TimeStamp {
-    TimeStamp {
-        hours: ms / 3600000,
-        minutes: (ms % 3600000) / 60000,
-        seconds: (ms % 60000) / 1000,
-        milliseconds: ms % 1000,
-    }
-}
-
 /// 使用 FireRedASR 校正字幕
 pub async fn correct_with_firered(
     srt_path: String,
@@ -1619,8 +1609,8 @@ pub async fn correct_with_firered(
     let entries: Vec<CorrectionEntry> = result.entries.into_iter().map(|e| {
         CorrectionEntry {
             id: e.id,
-            start_time: ms_to_timestamp(e.start_ms),
-            end_time: ms_to_timestamp(e.end_ms),
+            start_time: TimeStamp::from_ms(e.start_ms as u64),
+            end_time: TimeStamp::from_ms(e.end_ms as u64),
             original: e.original,
             corrected: e.corrected,
             has_diff: e.has_diff,
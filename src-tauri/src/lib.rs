@@ -1,12 +1,25 @@
+mod file_safety;
+mod file_watcher;
+mod project;
 mod srt_parser;
+mod text_encoding;
 mod waveform_generator;
 mod whisper_python_transcriber;
 mod sensevoice_transcriber;
 mod firered_corrector;
 
 use srt_parser::{
-    read_srt_file, write_srt_file, SRTFile, SubtitleEntry,
+    read_subtitle_file, write_subtitle_file, without_ass_comments, SRTFile, SubtitleEntry, TimeStamp,
     export_to_txt, export_to_vtt, export_to_markdown, export_to_fcpxml,
+    export_to_ass, export_to_ttml, validate_imsc1_text,
+    export_to_microdvd, export_to_sbv, export_to_spruce_stl, export_to_lrc, export_to_ebu_stl,
+    export_to_scc, validate_cea608, CaptionViolation, export_to_edl, export_to_xmeml, MarkerExportOptions,
+    shift_entries, scale_entries, ripple_insert, ripple_delete, snap_to_frames,
+    builtin_lint_profiles, LintProfile, LintReport, auto_fix_timing, AudioEnergy, AutoFixOptions, TimingFix,
+    break_lines, LineBreakOptions, split_long_entries, merge_fragments, SegmentOptions,
+    diff_subtitles, merge_subtitles, MergeResult, SubtitleDiff,
+    AssHeader, AssStyle, VttHeader, TtmlHeader, TtmlExportOptions, LrcHeader, LrcExportOptions,
+    EbuStlOptions, FcpxmlExportOptions, FcpxmlTitleStyle, builtin_title_styles, SubtitleFileFormat, SrtWriteOptions,
     check_file_permission, unlock_file, FilePermissionCheck,
 };
 use whisper_python_transcriber::{
@@ -32,8 +45,13 @@ use firered_corrector::{
     get_firered_models, download_firered_model, delete_firered_model, open_firered_model_dir,
     FireRedEnvStatus, CorrectionEntry, SingleCorrectionResult, FireRedModelInfo,
 };
-use waveform_generator::{generate_waveform_with_progress, ProgressCallback};
+use file_safety::{backup_dir_for, backup_file, read_journal, write_journal, AutosaveJournal};
+use file_watcher::FileWatcher;
+use project::{read_project, write_project, LoadedProject, VosubProject};
+use waveform_generator::{generate_waveform_minmax_with_progress, generate_waveform_with_progress, ProgressCallback};
+use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use tauri::menu::{MenuBuilder, MenuItem, PredefinedMenuItem, SubmenuBuilder};
 use tauri::tray::TrayIconBuilder;
@@ -42,11 +60,17 @@ use tauri::{Emitter, Manager};
 use tauri_plugin_prevent_default::Flags;
 use tauri_plugin_log::{Target, TargetKind, TimezoneStrategy, RotationStrategy};
 use log::info;
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 
 // 全局状态：存储通过文件关联打开的待处理文件路径
 static PENDING_FILE_OPEN: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
 
+// 已打开的字幕和音频文件的监视器（在 setup 中创建），外部修改时发送 file-changed 事件
+static FILE_WATCHER: OnceCell<FileWatcher> = OnceCell::new();
+
+// 已生成的波形，按 (文件路径, 采样数) 缓存，音频文件变化或关闭时清除
+static WAVEFORM_CACHE: Lazy<Mutex<HashMap<(String, usize), Vec<f32>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
 // 全局状态：tray icon ID
 static PROGRESS_TRAY_ID: &str = "progress-tray";
 
@@ -57,16 +81,123 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-/// Read and parse an SRT file
+/// Read and parse a subtitle file (SRT, ASS/SSA, WebVTT, TTML/DFXP, MicroDVD, SBV, Spruce STL)
+/// fps: 以帧计时的格式在文件未声明帧率时使用；缺少时返回 FPS_REQUIRED 错误，前端询问后重试
 #[tauri::command]
-fn read_srt(file_path: String) -> Result<SRTFile, String> {
-    read_srt_file(&file_path)
+fn read_srt(file_path: String, fps: Option<f64>) -> Result<SRTFile, String> {
+    let file = read_subtitle_file(&file_path, fps)?;
+    watch_opened_file(&file_path);
+    Ok(file)
 }
 
-/// Write SRT file
+/// 开始监视已打开的文件，监视失败只记录日志
+fn watch_opened_file(file_path: &str) {
+    if let Some(watcher) = FILE_WATCHER.get() {
+        if let Err(e) = watcher.watch(file_path) {
+            log::warn!("{}", e);
+        }
+    }
+}
+
+/// 清除音频文件的波形缓存
+fn invalidate_waveform_cache(file_path: &str) {
+    if let Ok(mut cache) = WAVEFORM_CACHE.lock() {
+        cache.retain(|(path, _), _| path != file_path);
+    }
+}
+
+/// 开始监视文件（另存为新路径后调用）
+#[tauri::command]
+fn watch_file(file_path: String) {
+    watch_opened_file(&file_path);
+}
+
+/// 停止监视文件（关闭标签页或卸载音频后调用），同时清除其波形缓存
 #[tauri::command]
-fn write_srt(file_path: String, entries: Vec<SubtitleEntry>) -> Result<(), String> {
-    write_srt_file(&file_path, &entries)
+fn unwatch_file(file_path: String) {
+    if let Some(watcher) = FILE_WATCHER.get() {
+        watcher.unwatch(&file_path);
+    }
+    invalidate_waveform_cache(&file_path);
+}
+
+/// 每个文件默认保留的备份数量
+const DEFAULT_BACKUP_COUNT: usize = 10;
+/// 同一文件两次备份的最短间隔，避免自动保存时每次都备份
+const BACKUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// 备份根目录（应用数据目录下的 backups）
+fn backup_root_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(data_dir.join("backups"))
+}
+
+/// 自动保存日志路径
+fn autosave_journal_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(data_dir.join("autosave").join("journal.json"))
+}
+
+/// Write subtitle file (SRT, or another supported format by extension)
+/// format: 原文件的编码、BOM、文件头和帧率，缺省为不带 BOM 的 UTF-8
+/// srt_options: 写为 SRT 时的编号、换行符、BOM 和结尾换行，缺省为重新编号、LF、沿用原 BOM、无结尾换行
+/// backup_count: 覆盖已有文件前保留的备份数量，缺省为 10，0 表示不备份
+#[tauri::command]
+fn write_srt(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+    entries: Vec<SubtitleEntry>,
+    format: Option<SubtitleFileFormat>,
+    srt_options: Option<SrtWriteOptions>,
+    backup_count: Option<usize>,
+) -> Result<(), String> {
+    let keep = backup_count.unwrap_or(DEFAULT_BACKUP_COUNT);
+    // 备份失败不影响保存
+    let backup = backup_root_dir(&app_handle)
+        .and_then(|root| backup_file(Path::new(&file_path), &root, keep, BACKUP_INTERVAL));
+    match backup {
+        Ok(Some(backup)) => info!("已备份 {} 到 {}", file_path, backup.display()),
+        Ok(None) => {}
+        Err(e) => log::warn!("备份 {} 失败: {}", file_path, e),
+    }
+    write_subtitle_file(&file_path, &entries, &format.unwrap_or_default(), &srt_options.unwrap_or_default())?;
+    // 自己保存的修改不作为外部修改报告
+    if let Some(watcher) = FILE_WATCHER.get() {
+        watcher.acknowledge(&file_path);
+    }
+    Ok(())
+}
+
+/// 写入自动保存日志（未保存的标签页），没有未保存的标签页时清除日志
+#[tauri::command]
+fn write_autosave_journal(app_handle: tauri::AppHandle, journal: AutosaveJournal) -> Result<(), String> {
+    write_journal(&autosave_journal_path(&app_handle)?, &journal)
+}
+
+/// 读取上次退出时未保存的标签页
+#[tauri::command]
+fn read_autosave_journal(app_handle: tauri::AppHandle) -> Result<Option<AutosaveJournal>, String> {
+    read_journal(&autosave_journal_path(&app_handle)?)
+}
+
+/// 读取 VoSub 项目文件，并开始监视其中的字幕和音频文件
+#[tauri::command]
+fn read_vosub_project(project_path: String) -> Result<LoadedProject, String> {
+    let loaded = read_project(Path::new(&project_path))?;
+    for path in loaded.project.tracks.iter().filter_map(|track| track.path.as_deref()) {
+        watch_opened_file(path);
+    }
+    if let Some(audio) = &loaded.project.audio {
+        watch_opened_file(&audio.path);
+    }
+    info!("打开项目 {}（{} 条轨道）", project_path, loaded.project.tracks.len());
+    Ok(loaded)
+}
+
+/// 写入 VoSub 项目文件
+#[tauri::command]
+fn write_vosub_project(project_path: String, project: VosubProject) -> Result<(), String> {
+    write_project(Path::new(&project_path), project)
 }
 
 /// 检查文件写入权限
@@ -95,6 +226,7 @@ fn read_audio_file(file_path: String) -> Result<String, String> {
 /// Generate waveform data from an audio file
 /// Returns a vector of normalized amplitude values (0.0 to 1.0)
 /// target_samples: number of data points to generate (default: 2000)
+/// 结果按路径和采样数缓存，音频文件在外部被修改时失效
 #[tauri::command]
 async fn generate_audio_waveform(
     app_handle: tauri::AppHandle,
@@ -102,6 +234,12 @@ async fn generate_audio_waveform(
     target_samples: Option<usize>,
 ) -> Result<Vec<f32>, String> {
     let samples = target_samples.unwrap_or(2000);
+    watch_opened_file(&file_path);
+    let cache_key = (file_path.clone(), samples);
+    if let Some(cached) = WAVEFORM_CACHE.lock().ok().and_then(|cache| cache.get(&cache_key).cloned()) {
+        let _ = app_handle.emit("waveform-progress", 1.0);
+        return Ok(cached);
+    }
     
     let (tx, rx) = std::sync::mpsc::channel();
     let app_handle_clone = app_handle.clone();
@@ -125,6 +263,9 @@ async fn generate_audio_waveform(
     .await
     .map_err(|e| format!("Task error: {:?}", e))??;
     
+    if let Ok(mut cache) = WAVEFORM_CACHE.lock() {
+        cache.insert(cache_key, result.clone());
+    }
     Ok(result)
 }
 
@@ -354,11 +495,27 @@ fn hide_tray_progress(app: tauri::AppHandle) -> Result<(), String> {
 #[tauri::command]
 fn show_log_in_folder(app_handle: tauri::AppHandle) -> Result<(), String> {
     let log_dir = app_handle.path().app_log_dir().map_err(|e| e.to_string())?;
+    open_in_file_manager(&log_dir)
+}
 
+/// 在系统文件管理器中打开备份目录；指定文件且已有备份时打开该文件的备份目录
+#[tauri::command]
+fn show_backups_in_folder(app_handle: tauri::AppHandle, file_path: Option<String>) -> Result<(), String> {
+    let backup_root = backup_root_dir(&app_handle)?;
+    let dir = file_path
+        .map(|path| backup_dir_for(&backup_root, Path::new(&path)))
+        .filter(|dir| dir.is_dir())
+        .unwrap_or(backup_root);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    open_in_file_manager(&dir)
+}
+
+/// 在系统文件管理器中打开目录
+fn open_in_file_manager(dir: &Path) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open")
-            .arg(&log_dir)
+            .arg(dir)
             .spawn()
             .map_err(|e| e.to_string())?;
     }
@@ -366,7 +523,7 @@ fn show_log_in_folder(app_handle: tauri::AppHandle) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("explorer")
-            .arg(&log_dir)
+            .arg(dir)
             .spawn()
             .map_err(|e| e.to_string())?;
     }
@@ -374,7 +531,7 @@ fn show_log_in_folder(app_handle: tauri::AppHandle) -> Result<(), String> {
     #[cfg(target_os = "linux")]
     {
         std::process::Command::new("xdg-open")
-            .arg(&log_dir)
+            .arg(dir)
             .spawn()
             .map_err(|e| e.to_string())?;
     }
@@ -409,8 +566,14 @@ async fn transcribe_audio_to_subtitles(
     audio_path: String,
     model_size: String,
     language: String,
+    line_break: Option<LineBreakOptions>,
 ) -> Result<Vec<SubtitleEntry>, String> {
-    transcribe_with_whisper(audio_path, model_size, language, window).await
+    let mut entries = transcribe_with_whisper(audio_path, model_size, language, window).await?;
+    // 可选：转录结果按显示宽度自动换行
+    if let Some(options) = line_break {
+        break_lines(&mut entries, &options);
+    }
+    Ok(entries)
 }
 
 /// 下载 Whisper 模型
@@ -481,8 +644,13 @@ async fn transcribe_with_sensevoice_model(
     window: tauri::Window,
     audio_path: String,
     language: String,
+    line_break: Option<LineBreakOptions>,
 ) -> Result<Vec<SubtitleEntry>, String> {
-    transcribe_with_sensevoice(audio_path, language, window).await
+    let mut entries = transcribe_with_sensevoice(audio_path, language, window).await?;
+    if let Some(options) = line_break {
+        break_lines(&mut entries, &options);
+    }
+    Ok(entries)
 }
 
 /// 卸载 SenseVoice 环境
@@ -698,37 +866,301 @@ async fn correct_single_subtitle(
 /// 导出为 TXT 格式（纯文本）
 #[tauri::command]
 fn export_txt(file_path: String, entries: Vec<SubtitleEntry>) -> Result<(), String> {
-    export_to_txt(&file_path, &entries)
+    export_to_txt(&file_path, &without_ass_comments(&entries))
 }
 
 /// 导出为 VTT 格式（WebVTT）
+/// header: 原 VTT 文件头，传入时保留 STYLE / REGION / NOTE 块
 #[tauri::command]
-fn export_vtt(file_path: String, entries: Vec<SubtitleEntry>) -> Result<(), String> {
-    export_to_vtt(&file_path, &entries)
+fn export_vtt(
+    file_path: String,
+    entries: Vec<SubtitleEntry>,
+    header: Option<VttHeader>,
+) -> Result<(), String> {
+    export_to_vtt(&file_path, &without_ass_comments(&entries), header.as_ref())
 }
 
 /// 导出为 Markdown 格式
 #[tauri::command]
 fn export_markdown(file_path: String, entries: Vec<SubtitleEntry>) -> Result<(), String> {
-    export_to_markdown(&file_path, &entries)
+    export_to_markdown(&file_path, &without_ass_comments(&entries))
+}
+
+/// 导出为 ASS 格式（Advanced SubStation Alpha）
+/// header: 原 ASS 文件头，styles: 额外配置的样式（与 Default 样式一起写入）
+#[tauri::command]
+fn export_ass(
+    file_path: String,
+    entries: Vec<SubtitleEntry>,
+    header: Option<AssHeader>,
+    styles: Option<Vec<AssStyle>>,
+) -> Result<(), String> {
+    export_to_ass(&file_path, &entries, header.as_ref(), &styles.unwrap_or_default())
+}
+
+/// 导出为 TTML 格式（IMSC1 Text Profile）
+/// header: 原 TTML 文件的样式和区域，为空时按 options 生成
+#[tauri::command]
+fn export_ttml(
+    file_path: String,
+    entries: Vec<SubtitleEntry>,
+    options: Option<TtmlExportOptions>,
+    header: Option<TtmlHeader>,
+) -> Result<(), String> {
+    export_to_ttml(&file_path, &without_ass_comments(&entries), &options.unwrap_or_default(), header.as_ref())
+}
+
+/// 导出为 MicroDVD 格式（.sub，以帧计时）
+#[tauri::command]
+fn export_microdvd(file_path: String, entries: Vec<SubtitleEntry>, fps: f64) -> Result<(), String> {
+    export_to_microdvd(&file_path, &without_ass_comments(&entries), fps)
+}
+
+/// 导出为 YouTube SBV 格式
+#[tauri::command]
+fn export_sbv(file_path: String, entries: Vec<SubtitleEntry>) -> Result<(), String> {
+    export_to_sbv(&file_path, &without_ass_comments(&entries))
+}
+
+/// 导出为 Spruce STL 格式（DVD Studio Pro，HH:MM:SS:FF 时间码）
+#[tauri::command]
+fn export_spruce_stl(file_path: String, entries: Vec<SubtitleEntry>, fps: f64) -> Result<(), String> {
+    export_to_spruce_stl(&file_path, &without_ass_comments(&entries), fps)
+}
+
+/// 导出为 LRC 歌词
+/// options: 行级或逐字（增强格式）输出，header: 原 LRC 文件的元数据标签
+#[tauri::command]
+fn export_lrc(
+    file_path: String,
+    entries: Vec<SubtitleEntry>,
+    options: Option<LrcExportOptions>,
+    header: Option<LrcHeader>,
+) -> Result<(), String> {
+    export_to_lrc(&file_path, &without_ass_comments(&entries), &options.unwrap_or_default(), header.as_ref())
+}
+
+/// 按 IMSC1 Text Profile 校验 TTML 文件，返回违反的约束列表
+#[tauri::command]
+fn validate_ttml(file_path: String) -> Result<Vec<String>, String> {
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read TTML file: {}", e))?;
+    Ok(validate_imsc1_text(&content))
+}
+
+/// 导出为 EBU STL 格式（Tech 3264 二进制，欧洲广播交付）
+/// options: 帧率、显示标准、字符表、每行最多字符数等；有字幕违反约束时返回全部问题
+#[tauri::command]
+fn export_ebu_stl(
+    file_path: String,
+    entries: Vec<SubtitleEntry>,
+    options: Option<EbuStlOptions>,
+) -> Result<(), String> {
+    export_to_ebu_stl(&file_path, &without_ass_comments(&entries), &options.unwrap_or_default())
+}
+
+/// 导出为 Scenarist SCC 格式（CEA-608 pop-on，29.97 丢帧时间码，美国广播交付）
+/// 有字幕超出 32 列 / 4 行或包含 608 字符集以外的字符时返回全部问题
+#[tauri::command]
+fn export_scc(file_path: String, entries: Vec<SubtitleEntry>) -> Result<(), String> {
+    export_to_scc(&file_path, &without_ass_comments(&entries))
+}
+
+/// 检查字幕是否满足 CEA-608 限制，返回每条违规字幕的 ID 和问题
+#[tauri::command]
+fn validate_scc(entries: Vec<SubtitleEntry>) -> Vec<CaptionViolation> {
+    validate_cea608(&without_ass_comments(&entries))
 }
 
 /// 导出为 FCPXML 格式（Final Cut Pro）
+/// options: 帧率（NTSC 帧率使用 1001 时基）、DF/NDF、分辨率、版本、字幕位置和标题样式
 #[tauri::command]
 fn export_fcpxml(
     file_path: String,
     entries: Vec<SubtitleEntry>,
-    fps: f64,
-    position_x: Option<i32>,
-    position_y: Option<i32>,
+    options: Option<FcpxmlExportOptions>,
+) -> Result<(), String> {
+    export_to_fcpxml(&file_path, &without_ass_comments(&entries), &options.unwrap_or_default())
+}
+
+/// 时间戳转换为 SMPTE 时间码 HH:MM:SS:FF（drop_frame 时为 HH:MM:SS;FF，仅 29.97 / 59.94）
+#[tauri::command]
+fn timestamp_to_smpte(time: TimeStamp, fps: f64, drop_frame: bool) -> Result<String, String> {
+    time.to_smpte(fps, drop_frame)
+}
+
+/// 解析 SMPTE 时间码为时间戳（帧之前为 `;` 时按丢帧时间码解析）
+#[tauri::command]
+fn timestamp_from_smpte(timecode: String, fps: f64) -> Result<TimeStamp, String> {
+    TimeStamp::from_smpte(&timecode, fps)
+}
+
+/// 平移 ID 在 [from_id, to_id] 内的字幕（不传表示不限），offset_ms 可为负
+#[tauri::command]
+fn shift_subtitles(
+    mut entries: Vec<SubtitleEntry>,
+    offset_ms: i64,
+    from_id: Option<u32>,
+    to_id: Option<u32>,
+) -> Vec<SubtitleEntry> {
+    shift_entries(&mut entries, offset_ms, from_id, to_id);
+    entries
+}
+
+/// 以 anchor_ms 为不动点线性缩放全部字幕时间（如 25 → 23.976 fps 变速时 factor 为 25 / 23.976）
+#[tauri::command]
+fn scale_subtitles(mut entries: Vec<SubtitleEntry>, factor: f64, anchor_ms: u64) -> Result<Vec<SubtitleEntry>, String> {
+    scale_entries(&mut entries, factor, anchor_ms)?;
+    Ok(entries)
+}
+
+/// 在 at_ms 处插入 duration_ms 的空白，之后的字幕整体推后
+#[tauri::command]
+fn ripple_insert_time(mut entries: Vec<SubtitleEntry>, at_ms: u64, duration_ms: u64) -> Vec<SubtitleEntry> {
+    ripple_insert(&mut entries, at_ms, duration_ms);
+    entries
+}
+
+/// 删除 [start_ms, end_ms) 这段时间，之后的字幕整体提前，区间内的字幕被移除
+#[tauri::command]
+fn ripple_delete_time(mut entries: Vec<SubtitleEntry>, start_ms: u64, end_ms: u64) -> Result<Vec<SubtitleEntry>, String> {
+    ripple_delete(&mut entries, start_ms, end_ms)?;
+    Ok(entries)
+}
+
+/// 把全部字幕的开始和结束时间对齐到帧边界
+#[tauri::command]
+fn snap_subtitles_to_frames(mut entries: Vec<SubtitleEntry>, fps: f64) -> Result<Vec<SubtitleEntry>, String> {
+    snap_to_frames(&mut entries, fps)?;
+    Ok(entries)
+}
+
+/// 按检查配置（默认 Netflix）检查字幕的时间轴和文本，时间重叠同时以 TimeConflict 返回
+#[tauri::command]
+fn lint_subtitles(entries: Vec<SubtitleEntry>, profile: Option<LintProfile>) -> LintReport {
+    srt_parser::lint_subtitles(&entries, &profile.unwrap_or_default())
+}
+
+/// 获取内置的检查配置（用户自定义的配置保存在前端配置中）
+#[tauri::command]
+fn get_lint_profiles() -> Vec<LintProfile> {
+    builtin_lint_profiles()
+}
+
+/// 按显示宽度（CJK 按双宽）重新换行全部字幕
+#[tauri::command]
+fn break_subtitle_lines(mut entries: Vec<SubtitleEntry>, options: Option<LineBreakOptions>) -> Vec<SubtitleEntry> {
+    break_lines(&mut entries, &options.unwrap_or_default());
+    entries
+}
+
+/// 分割 / 合并后重新编号的字幕，changed 为被分割的条数或合并的次数
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SegmentResult {
+    entries: Vec<SubtitleEntry>,
+    changed: usize,
+}
+
+/// 按句末标点和最大宽度分割长字幕（ids 为空时处理全部），有逐字时间时按词分配时间
+#[tauri::command]
+fn split_long_subtitles(
+    entries: Vec<SubtitleEntry>,
+    ids: Option<Vec<u32>>,
+    options: Option<SegmentOptions>,
+) -> SegmentResult {
+    let (entries, changed) = split_long_entries(entries, ids.as_deref(), &options.unwrap_or_default());
+    SegmentResult { entries, changed }
+}
+
+/// 把字符过少的碎片与相邻字幕合并（ids 为空时处理全部）
+#[tauri::command]
+fn merge_subtitle_fragments(
+    entries: Vec<SubtitleEntry>,
+    ids: Option<Vec<u32>>,
+    options: Option<SegmentOptions>,
+) -> SegmentResult {
+    let (entries, changed) = merge_fragments(entries, ids.as_deref(), &options.unwrap_or_default());
+    SegmentResult { entries, changed }
+}
+
+/// 按时间重叠和文本相似度对齐比较两个版本的字幕
+#[tauri::command]
+fn diff_subtitle_versions(old_entries: Vec<SubtitleEntry>, new_entries: Vec<SubtitleEntry>) -> SubtitleDiff {
+    diff_subtitles(&old_entries, &new_entries)
+}
+
+/// 以共同版本为基准三方合并字幕：不冲突的修改自动合并，两方都修改了的字幕作为冲突返回
+#[tauri::command]
+fn merge_subtitle_versions(
+    base: Vec<SubtitleEntry>,
+    ours: Vec<SubtitleEntry>,
+    theirs: Vec<SubtitleEntry>,
+) -> MergeResult {
+    let result = merge_subtitles(&base, &ours, &theirs);
+    info!("合并字幕版本：自动合并 {} 处，冲突 {} 处", result.applied, result.conflicts.len());
+    result
+}
+
+/// 自动修复后的字幕和修改报告
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AutoFixResult {
+    entries: Vec<SubtitleEntry>,
+    fixes: Vec<TimingFix>,
+}
+
+/// 自动修复重叠、间隔过短和时长问题；提供音频路径时按音频能量选择分界点和截断点
+#[tauri::command]
+async fn auto_fix_subtitle_timing(
+    entries: Vec<SubtitleEntry>,
+    options: Option<AutoFixOptions>,
+    audio_path: Option<String>,
+) -> Result<AutoFixResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut entries = entries;
+        let energy = match audio_path {
+            Some(path) => {
+                // 约 10 ms 一个点
+                let last_end = entries.iter().map(|e| e.end_time.to_ms()).max().unwrap_or(0);
+                let points = (last_end / 10).clamp(1000, 1_000_000) as usize;
+                let data = generate_waveform_minmax_with_progress(&path, points, None)?;
+                AudioEnergy::from_min_max(&data.peaks, data.duration)
+            }
+            None => None,
+        };
+        let fixes = auto_fix_timing(&mut entries, &options.unwrap_or_default(), energy.as_ref());
+        Ok(AutoFixResult { entries, fixes })
+    })
+    .await
+    .map_err(|e| format!("Task error: {:?}", e))?
+}
+
+/// 导出为 CMX3600 EDL 标记（DaVinci Resolve 时间线标记）
+/// options: 帧率、录制起始时间码（如 01:00:00:00）、标题和标记颜色
+#[tauri::command]
+fn export_edl_markers(
+    file_path: String,
+    entries: Vec<SubtitleEntry>,
+    options: Option<MarkerExportOptions>,
 ) -> Result<(), String> {
-    export_to_fcpxml(
-        &file_path,
-        &entries,
-        fps,
-        position_x.unwrap_or(0),
-        position_y.unwrap_or(-415),
-    )
+    export_to_edl(&file_path, &without_ass_comments(&entries), &options.unwrap_or_default())
+}
+
+/// 导出为 xmeml 序列标记（Premiere Pro / DaVinci Resolve 可导入的 XML）
+#[tauri::command]
+fn export_xmeml_markers(
+    file_path: String,
+    entries: Vec<SubtitleEntry>,
+    options: Option<MarkerExportOptions>,
+) -> Result<(), String> {
+    export_to_xmeml(&file_path, &without_ass_comments(&entries), &options.unwrap_or_default())
+}
+
+/// 获取内置的 FCPXML 标题样式预设（用户自定义的预设保存在前端配置中）
+#[tauri::command]
+fn get_fcpxml_title_presets() -> Vec<FcpxmlTitleStyle> {
+    builtin_title_styles()
 }
 
 /// 最近文件信息
@@ -811,6 +1243,9 @@ fn update_recent_files_menu(app_handle: tauri::AppHandle, files: Vec<RecentFileI
                 .text("batch-add-cjk-spaces", "批量添加中英文空格")
                 .text("batch-remove-html", "批量移除HTML标签")
                 .text("batch-remove-punctuation", "批量删除标点符号")
+                .text("batch-break-lines", "批量自动换行")
+                .text("batch-split-long", "批量分割长字幕")
+                .text("batch-merge-fragments", "批量合并碎片字幕")
                 .separator()
                 .text("batch-to-uppercase", "批量转换为大写")
                 .text("batch-to-lowercase", "批量转换为小写")
@@ -867,6 +1302,9 @@ fn update_recent_files_menu(app_handle: tauri::AppHandle, files: Vec<RecentFileI
                 .text("batch-add-cjk-spaces", "批量添加中英文空格")
                 .text("batch-remove-html", "批量移除HTML标签")
                 .text("batch-remove-punctuation", "批量删除标点符号")
+                .text("batch-break-lines", "批量自动换行")
+                .text("batch-split-long", "批量分割长字幕")
+                .text("batch-merge-fragments", "批量合并碎片字幕")
                 .separator()
                 .text("batch-to-uppercase", "批量转换为大写")
                 .text("batch-to-lowercase", "批量转换为小写")
@@ -923,6 +1361,9 @@ fn update_recent_files_menu(app_handle: tauri::AppHandle, files: Vec<RecentFileI
                 .text("batch-add-cjk-spaces", "批量添加中英文空格")
                 .text("batch-remove-html", "批量移除HTML标签")
                 .text("batch-remove-punctuation", "批量删除标点符号")
+                .text("batch-break-lines", "批量自动换行")
+                .text("batch-split-long", "批量分割长字幕")
+                .text("batch-merge-fragments", "批量合并碎片字幕")
                 .separator()
                 .text("batch-to-uppercase", "批量转换为大写")
                 .text("batch-to-lowercase", "批量转换为小写")
@@ -978,14 +1419,28 @@ fn base64_encode(data: &[u8]) -> String {
 pub fn run() {
     tauri::Builder::default()
         .setup(|app| {
+            // 监视已打开的文件，外部修改时清除波形缓存并通知前端
+            let watcher_handle = app.handle().clone();
+            match FileWatcher::new(move |change| {
+                invalidate_waveform_cache(&change.path);
+                info!("文件在外部发生变化: {:?}", change);
+                let _ = watcher_handle.emit("file-changed", &change);
+            }) {
+                Ok(watcher) => {
+                    let _ = FILE_WATCHER.set(watcher);
+                }
+                Err(e) => log::warn!("{}", e),
+            }
+
             // Windows: 处理命令行参数中的文件路径
             #[cfg(target_os = "windows")]
             {
                 let args: Vec<String> = std::env::args().collect();
                 // 第一个参数是程序路径，从第二个开始检查
                 for arg in args.iter().skip(1) {
-                    if arg.to_lowercase().ends_with(".srt") && std::path::Path::new(arg).exists() {
-                        info!("Windows: 通过命令行参数打开 SRT 文件: {}", arg);
+                    let lower = arg.to_lowercase();
+                    if (lower.ends_with(".srt") || lower.ends_with(".vosub")) && std::path::Path::new(arg).exists() {
+                        info!("Windows: 通过命令行参数打开文件: {}", arg);
                         if let Ok(mut pending) = PENDING_FILE_OPEN.lock() {
                             *pending = Some(arg.clone());
                         }
@@ -1016,8 +1471,20 @@ pub fn run() {
                     .text("export-vtt", "导出为 VTT")
                     .text("export-srt", "导出为 SRT")
                     .text("export-markdown", "导出为 Markdown")
+                    .text("export-ass", "导出为 ASS")
+                    .text("export-ttml", "导出为 TTML (IMSC1)")
+                    .text("export-sbv", "导出为 YouTube SBV")
+                    .text("export-microdvd", "导出为 MicroDVD...")
+                    .text("export-stl", "导出为 Spruce STL...")
+                    .text("export-lrc", "导出为 LRC 歌词")
+                    .text("export-lrc-word", "导出为 LRC 歌词（逐字）")
                     .separator()
                     .text("export-fcpxml", "导出为 FCPXML...")
+                    .text("export-ebu-stl", "导出为 EBU STL...")
+                    .text("export-scc", "导出为 SCC (CEA-608)")
+                    .separator()
+                    .text("export-edl", "导出为 EDL 标记...")
+                    .text("export-xmeml", "导出为 XML 标记 (Premiere/Resolve)...")
                     .build()?;
 
                 // 创建 文件 菜单（macOS 使用 Cmd）
@@ -1057,6 +1524,9 @@ pub fn run() {
                     .text("batch-add-cjk-spaces", "批量添加中英文空格")
                     .text("batch-remove-html", "批量移除HTML标签")
                     .text("batch-remove-punctuation", "批量删除标点符号")
+                    .text("batch-break-lines", "批量自动换行")
+                    .text("batch-split-long", "批量分割长字幕")
+                    .text("batch-merge-fragments", "批量合并碎片字幕")
                     .separator()
                     .text("batch-to-uppercase", "批量转换为大写")
                     .text("batch-to-lowercase", "批量转换为小写")
@@ -1089,8 +1559,20 @@ pub fn run() {
                     .text("export-vtt", "导出为 VTT")
                     .text("export-srt", "导出为 SRT")
                     .text("export-markdown", "导出为 Markdown")
+                    .text("export-ass", "导出为 ASS")
+                    .text("export-ttml", "导出为 TTML (IMSC1)")
+                    .text("export-sbv", "导出为 YouTube SBV")
+                    .text("export-microdvd", "导出为 MicroDVD...")
+                    .text("export-stl", "导出为 Spruce STL...")
+                    .text("export-lrc", "导出为 LRC 歌词")
+                    .text("export-lrc-word", "导出为 LRC 歌词（逐字）")
                     .separator()
                     .text("export-fcpxml", "导出为 FCPXML...")
+                    .text("export-ebu-stl", "导出为 EBU STL...")
+                    .text("export-scc", "导出为 SCC (CEA-608)")
+                    .separator()
+                    .text("export-edl", "导出为 EDL 标记...")
+                    .text("export-xmeml", "导出为 XML 标记 (Premiere/Resolve)...")
                     .build()?;
 
                 // 创建 文件 菜单（Windows 使用 Ctrl）
@@ -1130,6 +1612,9 @@ pub fn run() {
                     .text("batch-add-cjk-spaces", "批量添加中英文空格")
                     .text("batch-remove-html", "批量移除HTML标签")
                     .text("batch-remove-punctuation", "批量删除标点符号")
+                    .text("batch-break-lines", "批量自动换行")
+                    .text("batch-split-long", "批量分割长字幕")
+                    .text("batch-merge-fragments", "批量合并碎片字幕")
                     .separator()
                     .text("batch-to-uppercase", "批量转换为大写")
                     .text("batch-to-lowercase", "批量转换为小写")
@@ -1161,8 +1646,20 @@ pub fn run() {
                     .text("export-vtt", "导出为 VTT")
                     .text("export-srt", "导出为 SRT")
                     .text("export-markdown", "导出为 Markdown")
+                    .text("export-ass", "导出为 ASS")
+                    .text("export-ttml", "导出为 TTML (IMSC1)")
+                    .text("export-sbv", "导出为 YouTube SBV")
+                    .text("export-microdvd", "导出为 MicroDVD...")
+                    .text("export-stl", "导出为 Spruce STL...")
+                    .text("export-lrc", "导出为 LRC 歌词")
+                    .text("export-lrc-word", "导出为 LRC 歌词（逐字）")
                     .separator()
                     .text("export-fcpxml", "导出为 FCPXML...")
+                    .text("export-ebu-stl", "导出为 EBU STL...")
+                    .text("export-scc", "导出为 SCC (CEA-608)")
+                    .separator()
+                    .text("export-edl", "导出为 EDL 标记...")
+                    .text("export-xmeml", "导出为 XML 标记 (Premiere/Resolve)...")
                     .build()?;
 
                 // 创建 文件 菜单（Linux 使用 Ctrl）
@@ -1202,6 +1699,9 @@ pub fn run() {
                     .text("batch-add-cjk-spaces", "批量添加中英文空格")
                     .text("batch-remove-html", "批量移除HTML标签")
                     .text("batch-remove-punctuation", "批量删除标点符号")
+                    .text("batch-break-lines", "批量自动换行")
+                    .text("batch-split-long", "批量分割长字幕")
+                    .text("batch-merge-fragments", "批量合并碎片字幕")
                     .separator()
                     .text("batch-to-uppercase", "批量转换为大写")
                     .text("batch-to-lowercase", "批量转换为小写")
@@ -1323,6 +1823,42 @@ pub fn run() {
                             let _ = window.eval(js_code);
                         }
                     }
+                    "batch-break-lines" => {
+                        if let Some(window) = app_handle.get_webview_window("main") {
+                            let js_code = r#"
+                                (async () => {
+                                    if (window.__globalBatchBreakLines && typeof window.__globalBatchBreakLines === 'function') {
+                                        await window.__globalBatchBreakLines();
+                                    }
+                                })();
+                            "#;
+                            let _ = window.eval(js_code);
+                        }
+                    }
+                    "batch-split-long" => {
+                        if let Some(window) = app_handle.get_webview_window("main") {
+                            let js_code = r#"
+                                (async () => {
+                                    if (window.__globalSplitLongSubtitles && typeof window.__globalSplitLongSubtitles === 'function') {
+                                        await window.__globalSplitLongSubtitles();
+                                    }
+                                })();
+                            "#;
+                            let _ = window.eval(js_code);
+                        }
+                    }
+                    "batch-merge-fragments" => {
+                        if let Some(window) = app_handle.get_webview_window("main") {
+                            let js_code = r#"
+                                (async () => {
+                                    if (window.__globalMergeFragments && typeof window.__globalMergeFragments === 'function') {
+                                        await window.__globalMergeFragments();
+                                    }
+                                })();
+                            "#;
+                            let _ = window.eval(js_code);
+                        }
+                    }
                     "batch-to-uppercase" => {
                         if let Some(window) = app_handle.get_webview_window("main") {
                             let js_code = r#"
@@ -1489,6 +2025,13 @@ pub fn run() {
             update_recent_files_menu,
             get_log_path,
             show_log_in_folder,
+            show_backups_in_folder,
+            watch_file,
+            unwatch_file,
+            write_autosave_journal,
+            read_autosave_journal,
+            read_vosub_project,
+            write_vosub_project,
             // Whisper 相关
             check_whisper_env_status,
             install_whisper,
@@ -1537,7 +2080,38 @@ pub fn run() {
             export_txt,
             export_vtt,
             export_markdown,
+            export_ass,
+            export_ttml,
+            validate_ttml,
+            export_microdvd,
+            export_sbv,
+            export_spruce_stl,
+            export_lrc,
             export_fcpxml,
+            get_fcpxml_title_presets,
+            export_ebu_stl,
+            export_scc,
+            validate_scc,
+            export_edl_markers,
+            export_xmeml_markers,
+            timestamp_to_smpte,
+            timestamp_from_smpte,
+            // 批量调整时间
+            shift_subtitles,
+            scale_subtitles,
+            ripple_insert_time,
+            ripple_delete_time,
+            snap_subtitles_to_frames,
+            // 质量检查
+            lint_subtitles,
+            get_lint_profiles,
+            auto_fix_subtitle_timing,
+            break_subtitle_lines,
+            split_long_subtitles,
+            merge_subtitle_fragments,
+            // 版本比较
+            diff_subtitle_versions,
+            merge_subtitle_versions,
             // 版本信息
             get_app_version,
             // 更新下载
@@ -1558,9 +2132,10 @@ pub fn run() {
                     // 将 file:// URL 转换为路径
                     if let Ok(path) = url.to_file_path() {
                         if let Some(path_str) = path.to_str() {
-                            // 检查是否是 .srt 文件
-                            if path_str.to_lowercase().ends_with(".srt") {
-                                info!("通过文件关联打开 SRT 文件: {}", path_str);
+                            // 检查是否是 .srt 字幕或 .vosub 项目文件
+                            let lower = path_str.to_lowercase();
+                            if lower.ends_with(".srt") || lower.ends_with(".vosub") {
+                                info!("通过文件关联打开文件: {}", path_str);
                                 let path_string = path_str.to_string();
                                 
                                 // 存储到全局状态（供前端启动后查询）
@@ -0,0 +1,355 @@
+//! VoSub 项目文件（.vosub）
+//!
+//! 项目文件是一个 JSON 文件，把一次编辑工作需要的状态放在一起：字幕轨道（含条目和原文件信息）、
+//! 关联的音频、FireRed 校正结果及处理情况、逐条审校状态和备注、编辑器视图状态。
+//! 字幕和音频同时记录绝对路径和相对项目文件的路径，项目目录整体移动后仍能找到；
+//! 音频另记录指纹，打开时可以发现音频已被替换。
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::file_safety::write_atomic;
+use crate::firered_corrector::CorrectionEntry;
+use crate::srt_parser::{SubtitleEntry, SubtitleFileFormat};
+
+/// 当前项目文件版本，读取更高版本的文件时报错
+pub const PROJECT_VERSION: u32 = 1;
+
+/// 计算音频指纹时读取文件开头和结尾各多少字节
+const FINGERPRINT_SAMPLE: u64 = 1024 * 1024;
+
+/// 校正建议的处理情况
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CorrectionStatus {
+    /// 尚未确认
+    #[default]
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+/// 一条 FireRed 校正结果及用户的处理
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrectionDecision {
+    pub entry: CorrectionEntry,
+    #[serde(default)]
+    pub status: CorrectionStatus,
+    /// 用户逐字选择后的最终文本（与 corrected 不同时）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_text: Option<String>,
+}
+
+/// 审校状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReviewStatus {
+    #[default]
+    Unreviewed,
+    Approved,
+    NeedsWork,
+}
+
+/// 单条字幕的审校状态和备注
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EntryReview {
+    pub entry_id: u32,
+    pub status: ReviewStatus,
+    pub note: String,
+}
+
+/// 字幕轨道
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProjectTrack {
+    /// 显示名称
+    pub name: String,
+    /// 字幕文件的绝对路径（打开时已解析为实际位置）
+    pub path: Option<String>,
+    /// 相对项目文件所在目录的路径（无法表示为相对路径时为空）
+    pub relative_path: Option<String>,
+    pub entries: Vec<SubtitleEntry>,
+    /// 保存时沿用的原文件信息（编码、BOM、文件头、帧率）
+    pub format: SubtitleFileFormat,
+    pub corrections: Vec<CorrectionDecision>,
+    pub reviews: Vec<EntryReview>,
+}
+
+/// 音频指纹：文件大小和首尾各 1 MiB 内容的哈希
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioFingerprint {
+    pub size: u64,
+    /// FNV-1a 哈希（十六进制），JSON 中的数字在前端会丢失精度
+    pub hash: String,
+}
+
+/// 关联的音频文件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProjectAudio {
+    /// 绝对路径（打开时已解析为实际位置）
+    pub path: String,
+    pub relative_path: Option<String>,
+    pub fingerprint: Option<AudioFingerprint>,
+}
+
+/// 编辑器视图状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EditorViewState {
+    /// 当前显示的轨道
+    pub active_track: usize,
+    pub current_entry_id: Option<u32>,
+    /// 播放位置（秒）
+    pub current_time: f64,
+    pub volume: f64,
+    pub playback_rate: f64,
+}
+
+impl Default for EditorViewState {
+    fn default() -> Self {
+        EditorViewState {
+            active_track: 0,
+            current_entry_id: None,
+            current_time: 0.0,
+            volume: 1.0,
+            playback_rate: 1.0,
+        }
+    }
+}
+
+/// 项目文件内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VosubProject {
+    pub version: u32,
+    /// 保存时间（毫秒时间戳）
+    #[serde(default)]
+    pub saved_at: u64,
+    pub tracks: Vec<ProjectTrack>,
+    #[serde(default)]
+    pub audio: Option<ProjectAudio>,
+    #[serde(default)]
+    pub view: EditorViewState,
+}
+
+/// 打开项目时音频文件的情况
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AudioLinkStatus {
+    /// 找到且指纹一致（或项目未记录指纹）
+    Found,
+    /// 找到但内容与保存项目时不同
+    Changed,
+    Missing,
+}
+
+/// 打开的项目
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedProject {
+    pub project: VosubProject,
+    /// 项目没有关联音频时为空
+    pub audio_status: Option<AudioLinkStatus>,
+}
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// 计算音频指纹，只读取首尾两段，大文件也能很快完成
+pub fn audio_fingerprint(path: &Path) -> Result<AudioFingerprint, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open audio file: {}", e))?;
+    let size = file.metadata().map_err(|e| e.to_string())?.len();
+
+    let mut hash = fnv1a(0xcbf2_9ce4_8422_2325, &size.to_le_bytes());
+    let mut buf = Vec::new();
+    (&mut file)
+        .take(FINGERPRINT_SAMPLE)
+        .read_to_end(&mut buf)
+        .map_err(|e| format!("Failed to read audio file: {}", e))?;
+    hash = fnv1a(hash, &buf);
+    if size > FINGERPRINT_SAMPLE {
+        let tail_start = size.saturating_sub(FINGERPRINT_SAMPLE).max(FINGERPRINT_SAMPLE);
+        buf.clear();
+        file.seek(SeekFrom::Start(tail_start)).map_err(|e| e.to_string())?;
+        file.read_to_end(&mut buf).map_err(|e| format!("Failed to read audio file: {}", e))?;
+        hash = fnv1a(hash, &buf);
+    }
+    Ok(AudioFingerprint { size, hash: format!("{:016x}", hash) })
+}
+
+/// path 相对 base_dir 的路径（用 / 分隔），两者不在同一根目录（如 Windows 不同盘符）时为 None
+fn relative_path(base_dir: &Path, path: &Path) -> Option<String> {
+    let base: Vec<Component> = base_dir.components().collect();
+    let target: Vec<Component> = path.components().collect();
+    let common = base.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return None;
+    }
+    let parts: Vec<String> = std::iter::repeat_n("..".to_string(), base.len() - common)
+        .chain(target[common..].iter().map(|c| c.as_os_str().to_string_lossy().to_string()))
+        .collect();
+    Some(parts.join("/"))
+}
+
+/// 解析项目中记录的文件位置：优先使用相对路径（项目目录整体移动过），其次绝对路径
+fn resolve_link(project_dir: &Path, path: &str, relative: Option<&str>) -> Option<PathBuf> {
+    let by_relative = relative.map(|rel| project_dir.join(rel));
+    by_relative
+        .into_iter()
+        .chain((!path.is_empty()).then(|| PathBuf::from(path)))
+        .find(|candidate| candidate.exists())
+}
+
+fn project_dir(project_path: &Path) -> PathBuf {
+    match project_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// 写入项目文件：补全相对路径和音频指纹后原子写入
+///
+/// 音频文件已不存在时保留原有指纹，以便之后找回音频时仍能比对。
+pub fn write_project(project_path: &Path, mut project: VosubProject) -> Result<(), String> {
+    let dir = project_dir(project_path);
+    project.version = PROJECT_VERSION;
+    project.saved_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+
+    for track in &mut project.tracks {
+        if let Some(path) = track.path.as_deref() {
+            track.relative_path = relative_path(&dir, Path::new(path));
+        }
+    }
+    if let Some(audio) = &mut project.audio {
+        let path = PathBuf::from(&audio.path);
+        audio.relative_path = relative_path(&dir, &path);
+        if path.is_file() {
+            audio.fingerprint = Some(audio_fingerprint(&path)?);
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&project).map_err(|e| format!("Failed to serialize project: {}", e))?;
+    write_atomic(project_path, json).map_err(|e| format!("Failed to write project file: {}", e))
+}
+
+/// 读取项目文件，把字幕和音频路径解析为实际位置，并检查音频是否被替换
+pub fn read_project(project_path: &Path) -> Result<LoadedProject, String> {
+    let json = fs::read_to_string(project_path).map_err(|e| format!("Failed to read project file: {}", e))?;
+    let mut project: VosubProject =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid project file: {}", e))?;
+    if project.version > PROJECT_VERSION {
+        return Err(format!(
+            "Project file version {} is newer than supported version {}",
+            project.version, PROJECT_VERSION
+        ));
+    }
+
+    let dir = project_dir(project_path);
+    for track in &mut project.tracks {
+        let path = track.path.clone().unwrap_or_default();
+        if let Some(found) = resolve_link(&dir, &path, track.relative_path.as_deref()) {
+            track.path = Some(found.to_string_lossy().to_string());
+        }
+    }
+
+    let audio_status = project.audio.as_mut().map(|audio| {
+        let Some(found) = resolve_link(&dir, &audio.path, audio.relative_path.as_deref()) else {
+            return AudioLinkStatus::Missing;
+        };
+        audio.path = found.to_string_lossy().to_string();
+        match &audio.fingerprint {
+            Some(expected) if audio_fingerprint(&found).ok().as_ref() != Some(expected) => AudioLinkStatus::Changed,
+            _ => AudioLinkStatus::Found,
+        }
+    });
+
+    Ok(LoadedProject { project, audio_status })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_path() {
+        let base = Path::new("/work/show/project");
+        assert_eq!(relative_path(base, Path::new("/work/show/project/ep1.srt")).as_deref(), Some("ep1.srt"));
+        assert_eq!(
+            relative_path(base, Path::new("/work/show/audio/ep1.wav")).as_deref(),
+            Some("../audio/ep1.wav")
+        );
+        assert_eq!(relative_path(base, Path::new("relative.srt")), None);
+    }
+
+    #[test]
+    fn test_project_survives_moving_folder() {
+        let root = std::env::temp_dir().join(format!("vosub-project-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let old_dir = root.join("old");
+        fs::create_dir_all(old_dir.join("audio")).unwrap();
+        let audio_path = old_dir.join("audio").join("ep1.wav");
+        fs::write(&audio_path, vec![7u8; 3 * FINGERPRINT_SAMPLE as usize / 2]).unwrap();
+
+        let project = VosubProject {
+            version: 0,
+            saved_at: 0,
+            tracks: vec![ProjectTrack {
+                name: "ep1.srt".to_string(),
+                path: Some(old_dir.join("ep1.srt").to_string_lossy().to_string()),
+                corrections: vec![CorrectionDecision {
+                    entry: CorrectionEntry {
+                        id: 1,
+                        start_time: Default::default(),
+                        end_time: Default::default(),
+                        original: "在见".to_string(),
+                        corrected: "再见".to_string(),
+                        has_diff: true,
+                    },
+                    status: CorrectionStatus::Accepted,
+                    final_text: None,
+                }],
+                reviews: vec![EntryReview { entry_id: 1, status: ReviewStatus::NeedsWork, note: "语气".to_string() }],
+                ..Default::default()
+            }],
+            audio: Some(ProjectAudio { path: audio_path.to_string_lossy().to_string(), ..Default::default() }),
+            view: EditorViewState { current_time: 12.5, ..Default::default() },
+        };
+        write_project(&old_dir.join("ep1.vosub"), project).unwrap();
+        fs::write(old_dir.join("ep1.srt"), "").unwrap();
+
+        // 整个目录移动后按相对路径找到文件
+        let new_dir = root.join("new");
+        fs::rename(&old_dir, &new_dir).unwrap();
+        let loaded = read_project(&new_dir.join("ep1.vosub")).unwrap();
+        assert_eq!(loaded.audio_status, Some(AudioLinkStatus::Found));
+        let audio = loaded.project.audio.as_ref().unwrap();
+        assert_eq!(PathBuf::from(&audio.path), new_dir.join("audio").join("ep1.wav"));
+        let track = &loaded.project.tracks[0];
+        assert_eq!(track.path.as_deref().map(PathBuf::from), Some(new_dir.join("ep1.srt")));
+        assert_eq!(track.corrections[0].status, CorrectionStatus::Accepted);
+        assert_eq!(track.reviews[0].status, ReviewStatus::NeedsWork);
+        assert_eq!(loaded.project.view.current_time, 12.5);
+        assert_eq!(loaded.project.version, PROJECT_VERSION);
+
+        // 音频末尾内容变化
+        let mut data = fs::read(new_dir.join("audio").join("ep1.wav")).unwrap();
+        *data.last_mut().unwrap() = 8;
+        fs::write(new_dir.join("audio").join("ep1.wav"), data).unwrap();
+        let loaded = read_project(&new_dir.join("ep1.vosub")).unwrap();
+        assert_eq!(loaded.audio_status, Some(AudioLinkStatus::Changed));
+
+        fs::remove_file(new_dir.join("audio").join("ep1.wav")).unwrap();
+        let loaded = read_project(&new_dir.join("ep1.vosub")).unwrap();
+        assert_eq!(loaded.audio_status, Some(AudioLinkStatus::Missing));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1481,28 +1481,15 @@ pub async fn transcribe_with_sensevoice(
     // 转换为字幕条目
     let mut entries = Vec::new();
     for (i, segment) in result.segments.iter().enumerate() {
-        let start_ms = (segment.start * 1000.0) as u32;
-        let end_ms = (segment.end * 1000.0) as u32;
-        
-        let start_time = TimeStamp {
-            hours: start_ms / 3600000,
-            minutes: (start_ms % 3600000) / 60000,
-            seconds: (start_ms % 60000) / 1000,
-            milliseconds: start_ms % 1000,
-        };
-        
-        let end_time = TimeStamp {
-            hours: end_ms / 3600000,
-            minutes: (end_ms % 3600000) / 60000,
-            seconds: (end_ms % 60000) / 1000,
-            milliseconds: end_ms % 1000,
-        };
+        let start_time = TimeStamp::from_seconds(segment.start);
+        let end_time = TimeStamp::from_seconds(segment.end);
         
         entries.push(SubtitleEntry {
             id: (i + 1) as u32,
             start_time,
             end_time,
             text: segment.text.trim().to_string(),
+            ..Default::default()
         });
     }
     
@@ -1,9 +1,52 @@
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::fs;
 use std::fs::OpenOptions;
+use std::ops::{Add, Sub};
 use std::path::Path;
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use std::time::Duration;
+
+use crate::file_safety::write_atomic;
+use crate::text_encoding::{decode_text, write_encoded_file, DecodedText};
+use timecode::FrameRate;
+
+pub mod ass;
+pub mod autofix;
+pub mod diff;
+pub mod ebu_stl;
+pub mod fcpxml;
+pub mod frame_formats;
+pub mod linebreak;
+pub mod lint;
+pub mod lrc;
+pub mod markers;
+pub mod scc;
+pub mod segment;
+pub mod timecode;
+pub mod timing;
+pub mod ttml;
+pub mod vtt;
+
+pub use ass::{export_to_ass, parse_ass, without_ass_comments, AssEventMeta, AssHeader, AssStyle};
+pub use autofix::{auto_fix_timing, AudioEnergy, AutoFixOptions, TimingFix};
+pub use diff::{diff_subtitles, merge_subtitles, MergeResult, SubtitleDiff};
+pub use ebu_stl::{export_to_ebu_stl, parse_ebu_stl, EbuStlOptions};
+pub use fcpxml::{builtin_title_styles, export_to_fcpxml, parse_fcpxml, FcpxmlExportOptions, FcpxmlTitleStyle};
+pub use frame_formats::{
+    export_to_microdvd, export_to_sbv, export_to_spruce_stl, parse_microdvd, parse_sbv, parse_spruce_stl,
+    FPS_REQUIRED_ERROR,
+};
+pub use linebreak::{break_lines, LineBreakOptions};
+pub use lint::{builtin_lint_profiles, lint_subtitles, LintProfile, LintReport};
+pub use lrc::{export_to_lrc, parse_lrc, LrcExportOptions, LrcHeader, LrcWord};
+pub use markers::{export_to_edl, export_to_xmeml, MarkerExportOptions};
+pub use scc::{export_to_scc, parse_scc, validate_cea608, CaptionViolation};
+pub use segment::{merge_fragments, split_long_entries, SegmentOptions};
+pub use timing::{ripple_delete, ripple_insert, scale_entries, shift_entries, snap_to_frames};
+pub use ttml::{export_to_ttml, parse_ttml, validate_imsc1_text, TtmlCueMeta, TtmlExportOptions, TtmlHeader};
+pub use vtt::{export_to_vtt, parse_vtt, VttCueMeta, VttHeader};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TimeStamp {
     pub hours: u32,
     pub minutes: u32,
@@ -11,7 +54,7 @@ pub struct TimeStamp {
     pub milliseconds: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SubtitleEntry {
     pub id: u32,
     #[serde(rename = "startTime")]
@@ -19,18 +62,58 @@ pub struct SubtitleEntry {
     #[serde(rename = "endTime")]
     pub end_time: TimeStamp,
     pub text: String,
+    /// ASS/SSA 专有属性（样式、图层、边距、覆盖标签），仅从 ASS 文件加载时存在
+    #[serde(rename = "assEvent", default, skip_serializing_if = "Option::is_none")]
+    pub ass_event: Option<AssEventMeta>,
+    /// WebVTT 专有属性（标识符、cue 设置、NOTE），仅从 VTT 文件加载时存在
+    #[serde(rename = "vttCue", default, skip_serializing_if = "Option::is_none")]
+    pub vtt_cue: Option<VttCueMeta>,
+    /// TTML 专有属性（区域、样式引用、行内样式），仅从 TTML 文件加载时存在
+    #[serde(rename = "ttmlCue", default, skip_serializing_if = "Option::is_none")]
+    pub ttml_cue: Option<TtmlCueMeta>,
+    /// 增强 LRC 的逐字时间，仅从带 `<mm:ss.xx>` 标签的 LRC 文件加载时存在
+    #[serde(rename = "lrcWords", default, skip_serializing_if = "Option::is_none")]
+    pub lrc_words: Option<Vec<LrcWord>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct SRTFile {
     pub name: String,
     pub path: String,
     pub entries: Vec<SubtitleEntry>,
     pub encoding: Option<String>,
+    /// 原文件是否带 BOM（保存时据此保持一致）
+    #[serde(default)]
+    pub has_bom: bool,
+    /// ASS/SSA 文件头（脚本信息和样式表），仅从 ASS 文件加载时存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ass_header: Option<AssHeader>,
+    /// WebVTT 文件头（STYLE / REGION / NOTE 块），仅从 VTT 文件加载时存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vtt_header: Option<VttHeader>,
+    /// TTML 文件头（语言、时间参数、样式与区域），仅从 TTML 文件加载时存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttml_header: Option<TtmlHeader>,
+    /// LRC 元数据标签（歌手、标题等），仅从 LRC 文件加载时存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lrc_header: Option<LrcHeader>,
+    /// EBU STL 的 GSI 信息（帧率、字符表、显示标准等），仅从 EBU STL 文件加载时存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ebu_stl: Option<EbuStlOptions>,
+    /// FCPXML 的时间线设置和标题样式，仅从 FCPXML 文件加载时存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fcpxml: Option<FcpxmlExportOptions>,
+    /// 帧率，仅从 MicroDVD / Spruce STL 等以帧计时的文件加载时存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame_rate: Option<f64>,
+    /// 解析时发现并已处理的问题
+    #[serde(default)]
+    pub warnings: Vec<ParseDiagnostic>,
 }
 
 impl TimeStamp {
     /// Parse timestamp from SRT format: HH:MM:SS,mmm
+    /// 字段按原样保留，不进位（超出范围的字段由调用方决定是否报告或用 `normalized` 进位）
     pub fn parse(s: &str) -> Result<Self, String> {
         let parts: Vec<&str> = s.split(':').collect();
         if parts.len() != 3 {
@@ -71,76 +154,501 @@ impl TimeStamp {
     }
 }
 
-/// Parse SRT file content
-pub fn parse_srt(content: &str) -> Result<Vec<SubtitleEntry>, String> {
-    let mut entries = Vec::new();
-    let blocks: Vec<&str> = content.split("\n\n").collect();
+/// 解析过程中发现的问题（不会中断整个文件的加载）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseDiagnostic {
+    /// 问题所在的行号（从 1 开始）
+    pub line: usize,
+    /// 问题所在的字幕块序号（从 1 开始，0 表示不属于任何字幕块）
+    pub block: usize,
+    /// 问题描述
+    pub issue: String,
+    /// 已自动应用的修复（None 表示该内容被跳过）
+    pub auto_fix: Option<String>,
+}
+
+/// 宽松解析的结果：成功解析的字幕 + 诊断信息
+#[derive(Debug, Clone, Default)]
+pub struct ParseResult {
+    pub entries: Vec<SubtitleEntry>,
+    pub diagnostics: Vec<ParseDiagnostic>,
+}
 
-    for block in blocks {
-        let block = block.trim();
-        if block.is_empty() {
-            continue;
+/// 统一换行符并移除 UTF-8 BOM
+pub fn normalize_line_endings(content: &str) -> String {
+    content
+        .trim_start_matches('\u{feff}')
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+}
+
+/// 宽松解析时间戳，返回时间戳和已应用的修复说明
+/// 支持 HH:MM:SS,mmm / HH:MM:SS.mmm / MM:SS,mmm 以及 1-3 位毫秒
+fn parse_timestamp_lenient(s: &str) -> Result<(TimeStamp, Option<String>), String> {
+    if let Ok(ts) = TimeStamp::parse(s) {
+        let ms_digits = s.rsplit(',').next().map_or(0, |f| f.len());
+        if ms_digits == 3 && ts.seconds < 60 && ts.minutes < 60 {
+            return Ok((ts, None));
+        }
+    }
+
+    let mut fixes = Vec::new();
+    let (clock, fraction) = match s.rfind([',', '.']) {
+        Some(pos) => {
+            if &s[pos..pos + 1] == "." {
+                fixes.push("毫秒分隔符 '.' 已按 ',' 处理".to_string());
+            }
+            (&s[..pos], &s[pos + 1..])
+        }
+        None => {
+            fixes.push("缺少毫秒，已补为 000".to_string());
+            (s, "0")
         }
+    };
 
-        let lines: Vec<&str> = block.lines().collect();
-        if lines.len() < 3 {
-            continue; // Skip invalid blocks
+    let fields: Vec<&str> = clock.split(':').map(|p| p.trim()).collect();
+    let (hours, minutes, seconds) = match fields.as_slice() {
+        [h, m, sec] => (*h, *m, *sec),
+        [m, sec] => {
+            fixes.push("缺少小时字段，已补为 00".to_string());
+            ("0", *m, *sec)
         }
+        _ => return Err(format!("无法识别的时间戳: {}", s)),
+    };
+
+    let parse_field = |v: &str, name: &str| {
+        v.parse::<u32>()
+            .map_err(|_| format!("无效的{}: {}", name, s))
+    };
+    let hours = parse_field(hours, "小时")?;
+    let minutes = parse_field(minutes, "分钟")?;
+    let seconds = parse_field(seconds, "秒")?;
+
+    if fraction.is_empty() || fraction.len() > 3 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("无效的毫秒: {}", s));
+    }
+    if fraction.len() < 3 {
+        fixes.push(format!("毫秒 '{}' 已按小数补齐为 3 位", fraction));
+    }
+    let milliseconds = format!("{:0<3}", fraction).parse::<u32>().unwrap_or(0);
+
+    if minutes >= 60 || seconds >= 60 {
+        return Err(format!("时间字段超出范围: {}", s));
+    }
+
+    let fix = if fixes.is_empty() { None } else { Some(fixes.join("；")) };
+    Ok((
+        TimeStamp {
+            hours,
+            minutes,
+            seconds,
+            milliseconds,
+        },
+        fix,
+    ))
+}
+
+/// 宽松解析时间轴行 "start --> end"，忽略结束时间后的坐标等附加信息
+fn parse_timing_line_lenient(
+    line: &str,
+) -> Result<(TimeStamp, TimeStamp, Vec<String>), String> {
+    let (start, end) = line
+        .split_once("-->")
+        .ok_or_else(|| format!("无效的时间轴行: {}", line))?;
+    let end = end.split_whitespace().next().unwrap_or("");
+
+    let (start_time, start_fix) = parse_timestamp_lenient(start.trim())?;
+    let (end_time, end_fix) = parse_timestamp_lenient(end)?;
+
+    let fixes = start_fix.into_iter().chain(end_fix).collect();
+    Ok((start_time, end_time, fixes))
+}
 
-        // Parse ID
-        let id = lines[0].trim().parse::<u32>()
-            .map_err(|e| format!("Invalid subtitle ID: {}", e))?;
+/// 宽松解析 SRT 内容
+/// 以时间轴行作为字幕块的锚点，而不是依赖空行分隔，因此可以容忍 CRLF、BOM、
+/// 字幕内的多余空行以及缺失/错误的序号。单个块的错误只会产生诊断信息并跳过该块。
+pub fn parse_srt_lenient(content: &str) -> ParseResult {
+    let normalized = normalize_line_endings(content);
+    let lines: Vec<&str> = normalized.lines().collect();
+    let mut result = ParseResult::default();
+
+    let is_blank = |i: usize| lines[i].trim().is_empty();
+    let is_id = |i: usize| {
+        let t = lines[i].trim();
+        !t.is_empty() && t.chars().all(|c| c.is_ascii_digit())
+    };
 
-        // Parse timestamps
-        let timestamp_line = lines[1].trim();
-        let times: Vec<&str> = timestamp_line.split(" --> ").collect();
-        if times.len() != 2 {
-            return Err(format!("Invalid timestamp line: {}", timestamp_line));
+    // 找出所有时间轴行：只有两端时间戳都能解析的行才是时间轴行，其余含 "-->" 的行按文本保留
+    let timings: Vec<_> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.contains("-->"))
+        .filter_map(|(i, line)| parse_timing_line_lenient(line.trim()).ok().map(|timing| (i, timing)))
+        .collect();
+    let anchors: Vec<usize> = timings.iter().map(|(i, _)| *i).collect();
+
+    // 形似时间轴（以数字开头）但无法解析的行，返回解析错误
+    let timing_error = |i: usize| {
+        let line = lines[i].trim();
+        if !line.contains("-->") || !line.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
         }
+        parse_timing_line_lenient(line).err()
+    };
 
-        let start_time = TimeStamp::parse(times[0].trim())?;
-        let end_time = TimeStamp::parse(times[1].trim())?;
+    // 每个时间轴行前面的序号行（如果有）
+    let id_lines: Vec<Option<usize>> = anchors
+        .iter()
+        .enumerate()
+        .map(|(n, &a)| {
+            let prev_anchor = if n > 0 { Some(anchors[n - 1]) } else { None };
+            if a == 0 || Some(a - 1) == prev_anchor || is_blank(a - 1) {
+                return None;
+            }
+            // 序号行：纯数字，或者前面紧跟空行/文件开头的非空行（错误的序号）
+            let starts_block = a == 1 || is_blank(a - 2);
+            if is_id(a - 1) || starts_block {
+                Some(a - 1)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    // 第一个字幕块之前的内容
+    let first_block_start = id_lines
+        .first()
+        .map(|id| id.unwrap_or(anchors[0]))
+        .unwrap_or(lines.len());
+    for (i, line) in lines.iter().enumerate().take(first_block_start) {
+        if !line.trim().is_empty() {
+            result.diagnostics.push(ParseDiagnostic {
+                line: i + 1,
+                block: 0,
+                issue: timing_error(i).unwrap_or_else(|| format!("无法识别的内容: {}", line.trim())),
+                auto_fix: None,
+            });
+        }
+    }
 
-        // Parse text (all remaining lines)
-        let text = lines[2..].join("\n");
+    let mut last_id = 0u32;
+    for (n, (anchor, (start_time, end_time, fixes))) in timings.into_iter().enumerate() {
+        let block = n + 1;
+        let text_end = match anchors.get(n + 1) {
+            Some(&next) => id_lines[n + 1].unwrap_or(next),
+            None => lines.len(),
+        };
+
+        // 序号
+        let id = match id_lines[n] {
+            Some(i) => match lines[i].trim().parse::<u32>() {
+                Ok(id) => id,
+                Err(_) => {
+                    result.diagnostics.push(ParseDiagnostic {
+                        line: i + 1,
+                        block,
+                        issue: format!("无效的字幕序号: {}", lines[i].trim()),
+                        auto_fix: Some(format!("已重新编号为 {}", last_id + 1)),
+                    });
+                    last_id + 1
+                }
+            },
+            None => {
+                result.diagnostics.push(ParseDiagnostic {
+                    line: anchor + 1,
+                    block,
+                    issue: "缺少字幕序号".to_string(),
+                    auto_fix: Some(format!("已编号为 {}", last_id + 1)),
+                });
+                last_id + 1
+            }
+        };
+
+        // 时间轴
+        for fix in fixes {
+            result.diagnostics.push(ParseDiagnostic {
+                line: anchor + 1,
+                block,
+                issue: "时间戳格式不规范".to_string(),
+                auto_fix: Some(fix),
+            });
+        }
+        if end_time.to_ms() < start_time.to_ms() {
+            result.diagnostics.push(ParseDiagnostic {
+                line: anchor + 1,
+                block,
+                issue: "结束时间早于开始时间".to_string(),
+                auto_fix: None,
+            });
+        }
+
+        // 文本：去掉首尾空行，字幕内部的空行视为多余空行并移除
+        let mut text_lines: Vec<&str> = Vec::new();
+        let mut pending_blank: Option<usize> = None;
+        for (i, line) in lines.iter().enumerate().take(text_end).skip(anchor + 1) {
+            if line.trim().is_empty() {
+                if !text_lines.is_empty() && pending_blank.is_none() {
+                    pending_blank = Some(i);
+                }
+                continue;
+            }
+            if let Some(blank) = pending_blank.take() {
+                result.diagnostics.push(ParseDiagnostic {
+                    line: blank + 1,
+                    block,
+                    issue: "字幕文本中存在多余空行".to_string(),
+                    auto_fix: Some("已移除空行".to_string()),
+                });
+            }
+            if let Some(e) = timing_error(i) {
+                result.diagnostics.push(ParseDiagnostic {
+                    line: i + 1,
+                    block,
+                    issue: e,
+                    auto_fix: Some("已作为字幕文本保留".to_string()),
+                });
+            }
+            text_lines.push(line.trim_end());
+        }
+        if text_lines.is_empty() {
+            result.diagnostics.push(ParseDiagnostic {
+                line: anchor + 1,
+                block,
+                issue: "字幕文本为空".to_string(),
+                auto_fix: None,
+            });
+        }
 
-        entries.push(SubtitleEntry {
+        last_id = id;
+        result.entries.push(SubtitleEntry {
             id,
             start_time,
             end_time,
-            text,
+            text: text_lines.join("\n"),
+            ..Default::default()
         });
     }
 
-    Ok(entries)
+    result
+}
+
+/// 读取文本文件并自动检测编码
+fn read_text_file(file_path: &str) -> Result<DecodedText, String> {
+    if !Path::new(file_path).exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let bytes = fs::read(file_path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(decode_text(&bytes))
+}
+
+fn file_name_of(file_path: &str) -> String {
+    Path::new(file_path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// 文件扩展名（小写）
+fn extension_of(file_path: &str) -> String {
+    Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase()
 }
 
 /// Read and parse SRT file
 pub fn read_srt_file(file_path: &str) -> Result<SRTFile, String> {
-    let path = Path::new(file_path);
+    let decoded = read_text_file(file_path)?;
+    let content = decoded.text;
 
-    if !path.exists() {
-        return Err(format!("File not found: {}", file_path));
+    let ParseResult { entries, diagnostics } = parse_srt_lenient(&content);
+    if entries.is_empty() && !content.trim().is_empty() {
+        return Err(format!("Failed to parse SRT file: {}", file_path));
     }
 
-    let content = fs::read_to_string(file_path)
+    Ok(SRTFile {
+        name: file_name_of(file_path),
+        path: file_path.to_string(),
+        entries,
+        encoding: Some(decoded.encoding),
+        has_bom: decoded.has_bom,
+        warnings: diagnostics,
+        ..Default::default()
+    })
+}
+
+/// Read and parse ASS/SSA file
+/// 无法解析的 Dialogue/Comment 行会被跳过，问题放在 warnings 中
+pub fn read_ass_file(file_path: &str) -> Result<SRTFile, String> {
+    let decoded = read_text_file(file_path)?;
+    let (header, entries, diagnostics) = parse_ass(&decoded.text)?;
+
+    Ok(SRTFile {
+        name: file_name_of(file_path),
+        path: file_path.to_string(),
+        entries,
+        encoding: Some(decoded.encoding),
+        has_bom: decoded.has_bom,
+        ass_header: Some(header),
+        warnings: diagnostics,
+        ..Default::default()
+    })
+}
+
+/// Read and parse WebVTT file
+pub fn read_vtt_file(file_path: &str) -> Result<SRTFile, String> {
+    let decoded = read_text_file(file_path)?;
+    let (header, entries) = parse_vtt(&decoded.text)?;
+
+    Ok(SRTFile {
+        name: file_name_of(file_path),
+        path: file_path.to_string(),
+        entries,
+        encoding: Some(decoded.encoding),
+        has_bom: decoded.has_bom,
+        vtt_header: Some(header),
+        ..Default::default()
+    })
+}
+
+/// Read and parse TTML / DFXP file
+pub fn read_ttml_file(file_path: &str) -> Result<SRTFile, String> {
+    let decoded = read_text_file(file_path)?;
+    let (header, entries) = parse_ttml(&decoded.text)?;
+
+    Ok(SRTFile {
+        name: file_name_of(file_path),
+        path: file_path.to_string(),
+        entries,
+        encoding: Some(decoded.encoding),
+        has_bom: decoded.has_bom,
+        ttml_header: Some(header),
+        ..Default::default()
+    })
+}
+
+/// Read and parse LRC lyrics file
+pub fn read_lrc_file(file_path: &str) -> Result<SRTFile, String> {
+    let decoded = read_text_file(file_path)?;
+    let (header, entries) = parse_lrc(&decoded.text)?;
+
+    Ok(SRTFile {
+        name: file_name_of(file_path),
+        path: file_path.to_string(),
+        entries,
+        encoding: Some(decoded.encoding),
+        has_bom: decoded.has_bom,
+        lrc_header: Some(header),
+        ..Default::default()
+    })
+}
+
+/// Read and parse frame-based or YouTube subtitle file (.sub / .sbv / .stl)
+/// fps: 文件未声明帧率时使用，仍缺少时返回 `FPS_REQUIRED_ERROR` 开头的错误
+pub fn read_frame_based_file(file_path: &str, fps: Option<f64>) -> Result<SRTFile, String> {
+    let decoded = read_text_file(file_path)?;
+    let (entries, frame_rate) = match extension_of(file_path).as_str() {
+        "sub" => {
+            let (entries, fps) = parse_microdvd(&decoded.text, fps)?;
+            (entries, Some(fps))
+        }
+        "stl" => (parse_spruce_stl(&decoded.text, fps)?, fps),
+        _ => (parse_sbv(&decoded.text)?, None),
+    };
+
+    Ok(SRTFile {
+        name: file_name_of(file_path),
+        path: file_path.to_string(),
+        entries,
+        encoding: Some(decoded.encoding),
+        has_bom: decoded.has_bom,
+        frame_rate,
+        ..Default::default()
+    })
+}
+
+/// Read and parse EBU STL (Tech 3264) binary file
+/// GSI 与 TTI 中发现的问题放在 warnings 中
+pub fn read_ebu_stl_file(file_path: &str) -> Result<SRTFile, String> {
+    let bytes = fs::read(file_path)
         .map_err(|e| format!("Failed to read file: {}", e))?;
+    let (options, entries, diagnostics) = parse_ebu_stl(&bytes)?;
 
-    let entries = parse_srt(&content)?;
+    Ok(SRTFile {
+        name: file_name_of(file_path),
+        path: file_path.to_string(),
+        entries,
+        frame_rate: Some(options.frame_rate),
+        ebu_stl: Some(options),
+        warnings: diagnostics,
+        ..Default::default()
+    })
+}
 
-    let name = path.file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown")
-        .to_string();
+/// Read and parse Scenarist SCC (CEA-608) file
+/// 解码时发现的问题（校验位错误、超出 32 列等）放在 warnings 中
+pub fn read_scc_file(file_path: &str) -> Result<SRTFile, String> {
+    let decoded = read_text_file(file_path)?;
+    let (entries, diagnostics) = parse_scc(&decoded.text)?;
+
+    Ok(SRTFile {
+        name: file_name_of(file_path),
+        path: file_path.to_string(),
+        entries,
+        encoding: Some(decoded.encoding),
+        has_bom: decoded.has_bom,
+        frame_rate: Some(29.97),
+        warnings: diagnostics,
+        ..Default::default()
+    })
+}
+
+/// Read and parse FCPXML (Final Cut Pro XML) file
+/// 读取 title 和 caption，并保留时间线设置和第一个标题的样式供再次导出
+pub fn read_fcpxml_file(file_path: &str) -> Result<SRTFile, String> {
+    let decoded = read_text_file(file_path)?;
+    let (options, entries) = parse_fcpxml(&decoded.text)?;
 
     Ok(SRTFile {
-        name,
+        name: file_name_of(file_path),
         path: file_path.to_string(),
         entries,
-        encoding: Some("UTF-8".to_string()),
+        encoding: Some(decoded.encoding),
+        has_bom: decoded.has_bom,
+        frame_rate: Some(options.fps),
+        fcpxml: Some(options),
+        ..Default::default()
     })
 }
 
+/// .stl 可能是二进制的 EBU STL，也可能是文本的 Spruce STL
+fn is_ebu_stl_file(file_path: &str) -> bool {
+    let mut signature = [0u8; 11];
+    fs::File::open(file_path)
+        .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut signature))
+        .is_ok_and(|_| ebu_stl::is_ebu_stl(&signature))
+}
+
+/// 根据扩展名选择解析器读取字幕文件
+/// fps: 以帧计时的格式（.sub / .stl）在文件未声明帧率时使用
+pub fn read_subtitle_file(file_path: &str, fps: Option<f64>) -> Result<SRTFile, String> {
+    match extension_of(file_path).as_str() {
+        "ass" | "ssa" => read_ass_file(file_path),
+        "vtt" => read_vtt_file(file_path),
+        "ttml" | "dfxp" => read_ttml_file(file_path),
+        "stl" if is_ebu_stl_file(file_path) => read_ebu_stl_file(file_path),
+        "sub" | "sbv" | "stl" => read_frame_based_file(file_path, fps),
+        "lrc" => read_lrc_file(file_path),
+        "scc" => read_scc_file(file_path),
+        "fcpxml" => read_fcpxml_file(file_path),
+        _ => read_srt_file(file_path),
+    }
+}
+
 /// 文件权限检查结果
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FilePermissionCheck {
@@ -215,7 +723,7 @@ pub fn check_file_permission(file_path: &str) -> FilePermissionCheck {
     }
 
     // 检查读取权限
-    let readable = fs::read_to_string(file_path).is_ok();
+    let readable = fs::read(file_path).is_ok();
 
     // 检查写入权限 - 尝试以追加模式打开文件
     let write_result = OpenOptions::new().write(true).append(true).open(file_path);
@@ -250,38 +758,184 @@ pub fn check_file_permission(file_path: &str) -> FilePermissionCheck {
     }
 }
 
-/// Write SRT file
-pub fn write_srt_file(file_path: &str, entries: &[SubtitleEntry]) -> Result<(), String> {
-    let mut content = String::new();
-
-    for (index, entry) in entries.iter().enumerate() {
-        // Add subtitle ID (sequence number starting from 1)
-        // Always use sequential numbering regardless of original id
-        content.push_str(&format!("{}\n", index + 1));
+/// 换行符
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
 
-        // Add timestamp line
-        content.push_str(&format!(
-            "{} --> {}\n",
-            entry.start_time.to_string(),
-            entry.end_time.to_string()
-        ));
+impl LineEnding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+        }
+    }
+}
 
-        // Add subtitle text
-        content.push_str(&format!("{}", entry.text));
+/// SRT 写入选项，用于满足不同客户和播放器的交付规范
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SrtWriteOptions {
+    /// 从 1 开始重新编号；为 false 时保留原字幕的 id
+    pub renumber: bool,
+    pub line_ending: LineEnding,
+    /// 是否写入 BOM，None 时沿用原文件
+    pub with_bom: Option<bool>,
+    /// 最后一条字幕后是否以换行结尾
+    pub final_newline: bool,
+}
 
-        // Add blank line between entries (except for the last one)
-        if index < entries.len() - 1 {
-            content.push_str("\n\n");
+impl Default for SrtWriteOptions {
+    fn default() -> Self {
+        SrtWriteOptions {
+            renumber: true,
+            line_ending: LineEnding::Lf,
+            with_bom: None,
+            final_newline: false,
         }
     }
+}
 
-    fs::write(file_path, content)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
+/// 生成 SRT 文本
+pub fn build_srt(entries: &[SubtitleEntry], options: &SrtWriteOptions) -> String {
+    let newline = options.line_ending.as_str();
+    let blocks: Vec<String> = entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let id = if options.renumber { index as u32 + 1 } else { entry.id };
+            let text = entry.text.replace("\r\n", "\n").replace('\n', newline);
+            format!("{}{nl}{} --> {}{nl}{}", id, entry.start_time.to_string(), entry.end_time.to_string(), text, nl = newline)
+        })
+        .collect();
 
-    println!("Successfully wrote {} subtitles to {}", entries.len(), file_path);
+    let mut content = blocks.join(&newline.repeat(2));
+    if options.final_newline && !content.is_empty() {
+        content.push_str(newline);
+    }
+    content
+}
+
+/// Write SRT file
+/// encoding: 目标编码（None 为 UTF-8），用于把旧编码的文件按原编码写回
+/// with_bom: 原文件是否带 BOM，options.with_bom 未指定时沿用
+pub fn write_srt_file(
+    file_path: &str,
+    entries: &[SubtitleEntry],
+    encoding: Option<&str>,
+    with_bom: bool,
+    options: &SrtWriteOptions,
+) -> Result<(), String> {
+    let content = build_srt(entries, options);
+    write_encoded_file(file_path, &content, encoding, options.with_bom.unwrap_or(with_bom))?;
+
+    println!(
+        "Successfully wrote {} subtitles to {} ({})",
+        entries.len(),
+        file_path,
+        encoding.unwrap_or("UTF-8")
+    );
     Ok(())
 }
 
+/// 保存字幕文件时沿用的原文件信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SubtitleFileFormat {
+    /// 目标编码（None 为 UTF-8）
+    pub encoding: Option<String>,
+    /// 是否写入 BOM
+    pub with_bom: bool,
+    pub ass_header: Option<AssHeader>,
+    pub vtt_header: Option<VttHeader>,
+    pub ttml_header: Option<TtmlHeader>,
+    pub lrc_header: Option<LrcHeader>,
+    /// 原文件为 EBU STL 时的 GSI 信息，保存 .stl 时据此写为 EBU STL 而不是 Spruce STL
+    pub ebu_stl: Option<EbuStlOptions>,
+    /// 原文件为 FCPXML 时的时间线设置和标题样式
+    pub fcpxml: Option<FcpxmlExportOptions>,
+    /// MicroDVD / Spruce STL 使用的帧率
+    pub frame_rate: Option<f64>,
+}
+
+/// 根据扩展名写入字幕文件：.ass/.ssa 写为 ASS、.vtt 写为 WebVTT、.ttml/.dfxp 写为 TTML
+/// （均保留原文件头），.sub/.sbv 写为 MicroDVD/SBV，.stl 按原文件写为 EBU STL 或 Spruce STL，
+/// .lrc 写为 LRC 歌词，.scc 写为 CEA-608 SCC，.fcpxml 按原文件的时间线设置和样式写为 FCPXML，其余写为 SRT
+/// 写入为原子操作（先写临时文件再重命名），中途失败时原文件不受影响；非 ASS 格式不写出 ASS 的 Comment 事件
+/// srt_options: 写为 SRT 时的编号、换行符、BOM 和结尾换行
+pub fn write_subtitle_file(
+    file_path: &str,
+    entries: &[SubtitleEntry],
+    format: &SubtitleFileFormat,
+    srt_options: &SrtWriteOptions,
+) -> Result<(), String> {
+    let encoding = format.encoding.as_deref();
+    let with_bom = format.with_bom;
+    let frame_rate = || format.frame_rate.ok_or_else(|| FPS_REQUIRED_ERROR.to_string());
+
+    let extension = extension_of(file_path);
+    if matches!(extension.as_str(), "ass" | "ssa") {
+        let content = ass::build_ass(entries, format.ass_header.as_ref(), &[]);
+        return write_encoded_file(file_path, &content, encoding, with_bom);
+    }
+
+    // 其他格式没有注释事件，ASS 的 Comment 行不写出
+    let dialogue = without_ass_comments(entries);
+    let entries = &*dialogue;
+    match extension.as_str() {
+        "vtt" => {
+            let content = vtt::build_vtt(entries, format.vtt_header.as_ref());
+            write_encoded_file(file_path, &content, encoding, with_bom)
+        }
+        // XML 声明固定为 UTF-8，因此不使用原文件编码
+        "ttml" | "dfxp" => {
+            let content = ttml::build_ttml(entries, &TtmlExportOptions::default(), format.ttml_header.as_ref());
+            write_encoded_file(file_path, &content, None, false)
+        }
+        "sub" => {
+            let content = frame_formats::build_microdvd(entries, frame_rate()?)?;
+            write_encoded_file(file_path, &content, encoding, with_bom)
+        }
+        "sbv" => {
+            let content = frame_formats::build_sbv(entries);
+            write_encoded_file(file_path, &content, encoding, with_bom)
+        }
+        "stl" => match &format.ebu_stl {
+            Some(options) => {
+                let content = ebu_stl::build_ebu_stl(entries, options)?;
+                write_atomic(file_path, content).map_err(|e| format!("Failed to write file: {}", e))
+            }
+            None => {
+                let content = frame_formats::build_spruce_stl(entries, frame_rate()?)?;
+                write_encoded_file(file_path, &content, encoding, with_bom)
+            }
+        },
+        // 原文件带逐字时间时保存为增强格式
+        "lrc" => {
+            let options = LrcExportOptions {
+                word_level: entries.iter().any(|e| e.lrc_words.is_some()),
+                ..Default::default()
+            };
+            let content = lrc::build_lrc(entries, &options, format.lrc_header.as_ref());
+            write_encoded_file(file_path, &content, encoding, with_bom)
+        }
+        "scc" => {
+            let content = scc::build_scc(entries)?;
+            write_encoded_file(file_path, &content, None, false)
+        }
+        "fcpxml" => {
+            let options = format.fcpxml.clone().unwrap_or_default();
+            let content = fcpxml::build_fcpxml(entries, &options)?;
+            write_encoded_file(file_path, &content, None, false)
+        }
+        _ => write_srt_file(file_path, entries, encoding, with_bom, srt_options),
+    }
+}
+
 // ============ 导出功能 ============
 
 impl TimeStamp {
@@ -301,65 +955,171 @@ impl TimeStamp {
         )
     }
 
-    /// Convert to total milliseconds
+    /// Create from total milliseconds (hours saturate at u32::MAX)
+    pub fn from_ms(ms: u64) -> Self {
+        TimeStamp {
+            hours: (ms / 3_600_000).min(u32::MAX as u64) as u32,
+            minutes: ((ms % 3_600_000) / 60_000) as u32,
+            seconds: ((ms % 60_000) / 1000) as u32,
+            milliseconds: (ms % 1000) as u32,
+        }
+    }
+
+    /// Create from seconds (negative or NaN becomes 0), rounded to the nearest millisecond
+    pub fn from_seconds(seconds: f64) -> Self {
+        Self::from_ms((seconds.max(0.0) * 1000.0).round() as u64)
+    }
+
+    /// Convert to total milliseconds; out-of-range fields (e.g. 75 seconds) are carried over
     pub fn to_ms(&self) -> u64 {
         (self.hours as u64 * 3600 + self.minutes as u64 * 60 + self.seconds as u64) * 1000
             + self.milliseconds as u64
     }
 
-    /// Convert to frames at given frame rate
-    pub fn to_frames(&self, fps: f64) -> u64 {
-        let total_seconds = self.hours as f64 * 3600.0
-            + self.minutes as f64 * 60.0
-            + self.seconds as f64
-            + self.milliseconds as f64 / 1000.0;
-        (total_seconds * fps).round() as u64
+    /// 进位超出范围的字段，如 00:00:75,1500 → 00:01:16,500
+    pub fn normalized(&self) -> Self {
+        Self::from_ms(self.to_ms())
+    }
+
+    /// Create from frame count at given frame rate (NTSC rates use the exact 1001 timebase)
+    pub fn from_frames(frames: u64, rate: FrameRate) -> Self {
+        Self::from_ms(rate.ms_from_frames(frames))
+    }
+
+    /// Convert to frames at given frame rate (NTSC rates use the exact 1001 timebase)
+    pub fn to_frames(&self, rate: FrameRate) -> u64 {
+        rate.frames_from_ms(self.to_ms())
+    }
+
+    /// 解析 SMPTE 时间码 HH:MM:SS:FF（帧之前为 `;` 时按丢帧时间码解析）
+    pub fn from_smpte(timecode: &str, fps: f64) -> Result<Self, String> {
+        let rate = FrameRate::from_fps(fps)?;
+        Ok(Self::from_ms(rate.ms_from_frames(rate.parse_timecode(timecode)?)))
+    }
+
+    /// 格式化为 SMPTE 时间码 HH:MM:SS:FF，丢帧时间码为 HH:MM:SS;FF（仅 29.97 / 59.94）
+    pub fn to_smpte(&self, fps: f64, drop_frame: bool) -> Result<String, String> {
+        let rate = FrameRate::from_fps(fps)?;
+        rate.format_timecode(rate.frames_from_ms(self.to_ms()), drop_frame)
     }
 }
 
-/// Export to TXT (plain text, subtitles only)
-pub fn export_to_txt(file_path: &str, entries: &[SubtitleEntry]) -> Result<(), String> {
-    let content: String = entries
-        .iter()
-        .map(|e| e.text.clone())
-        .collect::<Vec<_>>()
-        .join("\n");
+// 比较按总毫秒数进行，字段未规范化的时间戳（如 75 秒）与规范化后的值相等
+impl PartialEq for TimeStamp {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_ms() == other.to_ms()
+    }
+}
 
-    fs::write(file_path, content)
-        .map_err(|e| format!("Failed to write TXT file: {}", e))?;
+impl Eq for TimeStamp {}
 
-    println!("Successfully exported {} subtitles to TXT: {}", entries.len(), file_path);
-    Ok(())
+impl PartialOrd for TimeStamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
-/// Export to VTT (WebVTT format)
-pub fn export_to_vtt(file_path: &str, entries: &[SubtitleEntry]) -> Result<(), String> {
-    let mut content = String::from("WEBVTT\n\n");
+impl Ord for TimeStamp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_ms().cmp(&other.to_ms())
+    }
+}
 
-    for (index, entry) in entries.iter().enumerate() {
-        // Cue identifier (optional but useful)
-        content.push_str(&format!("{}\n", index + 1));
+impl Add<Duration> for &TimeStamp {
+    type Output = TimeStamp;
 
-        // Timestamp line (VTT uses . instead of ,)
-        content.push_str(&format!(
-            "{} --> {}\n",
-            entry.start_time.to_vtt_string(),
-            entry.end_time.to_vtt_string()
-        ));
+    fn add(self, duration: Duration) -> TimeStamp {
+        let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        TimeStamp::from_ms(self.to_ms().saturating_add(ms))
+    }
+}
+
+impl Add<Duration> for TimeStamp {
+    type Output = TimeStamp;
+
+    fn add(self, duration: Duration) -> TimeStamp {
+        &self + duration
+    }
+}
+
+/// 减去时长，结果小于 0 时截断为 0
+impl Sub<Duration> for &TimeStamp {
+    type Output = TimeStamp;
+
+    fn sub(self, duration: Duration) -> TimeStamp {
+        let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        TimeStamp::from_ms(self.to_ms().saturating_sub(ms))
+    }
+}
+
+impl Sub<Duration> for TimeStamp {
+    type Output = TimeStamp;
 
-        // Subtitle text
-        content.push_str(&entry.text);
+    fn sub(self, duration: Duration) -> TimeStamp {
+        &self - duration
+    }
+}
+
+/// 两个时间戳之间的时长，`a - b` 在 a 早于 b 时为 0
+impl Sub for &TimeStamp {
+    type Output = Duration;
+
+    fn sub(self, other: &TimeStamp) -> Duration {
+        Duration::from_millis(self.to_ms().saturating_sub(other.to_ms()))
+    }
+}
 
-        // Blank line between cues
-        if index < entries.len() - 1 {
-            content.push_str("\n\n");
+impl Sub for TimeStamp {
+    type Output = Duration;
+
+    fn sub(self, other: TimeStamp) -> Duration {
+        &self - &other
+    }
+}
+
+/// Escape XML special characters
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// 去掉 `<i>`、`{\an8}` 等样式标签
+pub fn strip_tags(text: &str) -> String {
+    let mut result = String::new();
+    let mut closing: Option<char> = None;
+    for c in text.chars() {
+        match (closing, c) {
+            (None, '<') => closing = Some('>'),
+            (None, '{') if text.contains("{\\") => closing = Some('}'),
+            (None, _) => result.push(c),
+            (Some(end), _) if c == end => closing = None,
+            _ => {}
         }
     }
+    result
+}
+
+/// 是否为 CJK 字符（假名、汉字、谚文）
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}' | '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{AC00}'..='\u{D7AF}' | '\u{F900}'..='\u{FAFF}')
+}
 
-    fs::write(file_path, content)
-        .map_err(|e| format!("Failed to write VTT file: {}", e))?;
+/// Export to TXT (plain text, subtitles only)
+pub fn export_to_txt(file_path: &str, entries: &[SubtitleEntry]) -> Result<(), String> {
+    let content: String = entries
+        .iter()
+        .map(|e| e.text.clone())
+        .collect::<Vec<_>>()
+        .join("\n");
 
-    println!("Successfully exported {} subtitles to VTT: {}", entries.len(), file_path);
+    write_atomic(file_path, content)
+        .map_err(|e| format!("Failed to write TXT file: {}", e))?;
+
+    println!("Successfully exported {} subtitles to TXT: {}", entries.len(), file_path);
     Ok(())
 }
 
@@ -376,112 +1136,13 @@ pub fn export_to_markdown(file_path: &str, entries: &[SubtitleEntry]) -> Result<
         ));
     }
 
-    fs::write(file_path, content.trim_end())
+    write_atomic(file_path, content.trim_end())
         .map_err(|e| format!("Failed to write Markdown file: {}", e))?;
 
     println!("Successfully exported {} subtitles to Markdown: {}", entries.len(), file_path);
     Ok(())
 }
 
-/// Export to FCPXML (Final Cut Pro XML)
-/// fps: frame rate (e.g., 24.0, 25.0, 29.97, 30.0, 60.0)
-/// position_x: subtitle X position (default: 0)
-/// position_y: subtitle Y position (default: -415)
-pub fn export_to_fcpxml(
-    file_path: &str,
-    entries: &[SubtitleEntry],
-    fps: f64,
-    position_x: i32,
-    position_y: i32,
-) -> Result<(), String> {
-    // Calculate frame duration and format name based on fps
-    let (frame_duration, format_name) = match fps as u32 {
-        24 => ("100/2400s", "FFVideoFormat1080p24"),
-        25 => ("100/2500s", "FFVideoFormat1080p25"),
-        30 => ("100/3000s", "FFVideoFormat1080p30"),
-        60 => ("100/6000s", "FFVideoFormat1080p60"),
-        _ => ("100/2500s", "FFVideoFormat1080p25"), // Default to 25fps
-    };
-
-    // Find total duration from last subtitle (in the same time base)
-    let total_duration = entries
-        .last()
-        .map(|e| e.end_time.to_frames(fps) * 100)
-        .unwrap_or(0);
-    
-    let time_base = (fps * 100.0) as u64;
-    let gap_duration = total_duration + 10000;
-
-    let mut content = format!(r#"<?xml version="1.0" encoding="UTF-8"?>
-<!DOCTYPE fcpxml>
-
-<fcpxml version="1.8">
-  <resources>
-    <format id="r1" name="{}" frameDuration="{}" width="1920" height="1080" colorSpace="1-1-1 (Rec. 709)"/>
-    <effect id="r2" name="自定" uid=".../Titles.localized/Build In:Out.localized/Custom.localized/Custom.moti"/>
-  </resources>
-  <library>
-    <event name="Subtitles">
-      <project name="Subtitles">
-        <sequence duration="{}/{}s" format="r1" tcStart="0s" tcFormat="NDF" audioLayout="stereo" audioRate="48k">
-          <spine>
-            <gap name="空隙" offset="0s" duration="{}/{}s">
-"#, format_name, frame_duration, gap_duration, time_base, gap_duration, time_base);
-
-    for (index, entry) in entries.iter().enumerate() {
-        // Convert time to the same time base (fps * 100)
-        let start_units = entry.start_time.to_frames(fps) * 100;
-        let end_units = entry.end_time.to_frames(fps) * 100;
-        let duration_units = end_units - start_units;
-
-        // Escape XML special characters
-        let escaped_text = entry.text
-            .replace('&', "&amp;")
-            .replace('<', "&lt;")
-            .replace('>', "&gt;")
-            .replace('"', "&quot;")
-            .replace('\'', "&apos;")
-            .replace('\n', " ");
-
-        content.push_str(&format!(
-            r#"<title name="{} - 自定" lane="1" offset="{}/{}s" ref="r2" duration="{}/{}s">
-<param name="位置" key="9999/10199/10201/1/100/101" value="{} {}"/>
-<param name="对齐" key="9999/10199/10201/2/354/1002961760/401" value="1 (居中)"/>
-<param name="Out Sequencing" key="9999/10199/10201/4/10233/201/202" value="0 (到)"/>
-
-<text>
-  <text-style ref="ts{}">{}</text-style>
-</text>
-<text-style-def id="ts{}">
-  <text-style font="PingFang SC" fontSize="62" fontFace="Semibold" fontColor="1 1 1 1" bold="1" strokeColor="0.329705 0.329721 0.329713 1" strokeWidth="-1" shadowColor="0 0 0 0.75" shadowOffset="3 315" kerning="1.24" alignment="center"/>
-</text-style-def>
-</title>
-"#,
-            escaped_text.chars().take(20).collect::<String>(),
-            start_units, time_base,
-            duration_units, time_base,
-            position_x, position_y,
-            index + 1,
-            escaped_text,
-            index + 1
-        ));
-    }
-
-    content.push_str(r#"            </gap>
-          </spine>
-        </sequence>
-      </project>
-    </event>
-  </library>
-</fcpxml>"#);
-
-    fs::write(file_path, content)
-        .map_err(|e| format!("Failed to write FCPXML file: {}", e))?;
-
-    println!("Successfully exported {} subtitles to FCPXML ({}fps): {}", entries.len(), fps, file_path);
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -506,6 +1167,44 @@ mod tests {
         assert_eq!(ts.to_string(), "00:01:23,456");
     }
 
+    #[test]
+    fn test_timestamp_arithmetic() {
+        let unnormalized = TimeStamp { hours: 0, minutes: 0, seconds: 75, milliseconds: 1500 };
+        assert_eq!(unnormalized.normalized().to_string(), "00:01:16,500");
+        assert_eq!(unnormalized, TimeStamp::from_ms(76_500));
+        assert_eq!(TimeStamp::parse("00:00:75,000").unwrap().seconds, 75);
+        assert_eq!(TimeStamp::parse("00:00:75,000").unwrap().normalized().to_string(), "00:01:15,000");
+
+        let mut times = [TimeStamp::from_ms(3000), TimeStamp::from_ms(1000), TimeStamp::from_ms(2000)];
+        times.sort();
+        assert_eq!(times.iter().map(|t| t.to_ms()).collect::<Vec<_>>(), vec![1000, 2000, 3000]);
+
+        let start = TimeStamp::from_ms(1000);
+        assert_eq!((&start + Duration::from_millis(61_500)).to_string(), "00:01:02,500");
+        assert_eq!((&start - Duration::from_secs(5)).to_ms(), 0);
+        assert_eq!(TimeStamp::from_ms(4000) - start.clone(), Duration::from_secs(3));
+        assert_eq!(&start - &TimeStamp::from_ms(4000), Duration::ZERO);
+        assert_eq!(TimeStamp::from_ms(u64::MAX).hours, u32::MAX);
+        assert_eq!((TimeStamp::from_ms(u64::MAX - 1) + Duration::MAX).to_ms(), TimeStamp::from_ms(u64::MAX).to_ms());
+        assert_eq!(TimeStamp::from_seconds(-1.0).to_ms(), 0);
+        assert_eq!(TimeStamp::from_seconds(1.2345).to_ms(), 1235);
+    }
+
+    #[test]
+    fn test_timestamp_smpte() {
+        let hour = TimeStamp::from_ms(3_600_000);
+        assert_eq!(hour.to_smpte(25.0, false).unwrap(), "01:00:00:00");
+        assert_eq!(hour.to_smpte(29.97, true).unwrap(), "01:00:00;00");
+        assert_eq!(hour.to_smpte(29.97, false).unwrap(), "00:59:56:12");
+        assert!(hour.to_smpte(25.0, true).is_err());
+        assert_eq!(TimeStamp::from_smpte("01:00:00;00", 29.97).unwrap().to_ms(), 3_599_996);
+        assert_eq!(TimeStamp::from_smpte("00:00:01:12", 25.0).unwrap().to_ms(), 1480);
+        let ntsc = FrameRate::from_fps(29.97).unwrap();
+        assert_eq!(TimeStamp::from_ms(1001).to_frames(ntsc), 30);
+        assert_eq!(TimeStamp::from_frames(30, ntsc).to_ms(), 1001);
+        assert!(TimeStamp::from_smpte("00:00:01", 25.0).is_err());
+    }
+
     #[test]
     fn test_parse_srt() {
         let content = r#"1
@@ -516,9 +1215,80 @@ This is the first subtitle
 00:00:05,000 --> 00:00:08,000
 This is the second subtitle"#;
 
-        let entries = parse_srt(content).unwrap();
+        let entries = parse_srt_lenient(content).entries;
         assert_eq!(entries.len(), 2);
         assert_eq!(entries[0].id, 1);
         assert_eq!(entries[0].text, "This is the first subtitle");
     }
+
+    #[test]
+    fn test_parse_srt_crlf_and_bom() {
+        let content = "\u{feff}1\r\n00:00:01,000 --> 00:00:02,000\r\nHello\r\n\r\n2\r\n00:00:03,000 --> 00:00:04,000\r\nWorld\r\n";
+        let result = parse_srt_lenient(content);
+        assert_eq!(result.entries.len(), 2);
+        assert_eq!(result.entries[0].text, "Hello");
+        assert_eq!(result.entries[1].id, 2);
+        assert!(result.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_parse_srt_lenient_fixes() {
+        let content = "00:00:01.5 --> 00:00:02.000\nNo id\n\nfirst line\n\n3\n00:00:03,000 --> 00:00:04,000\nline one\n\nline two";
+        let result = parse_srt_lenient(content);
+        assert_eq!(result.entries.len(), 2);
+        assert_eq!(result.entries[0].id, 1);
+        assert_eq!(result.entries[0].start_time.milliseconds, 500);
+        assert_eq!(result.entries[0].text, "No id\nfirst line");
+        assert_eq!(result.entries[1].text, "line one\nline two");
+        assert!(result.diagnostics.iter().any(|d| d.issue == "缺少字幕序号"));
+        assert!(result.diagnostics.iter().all(|d| d.auto_fix.is_some()));
+    }
+
+    #[test]
+    fn test_parse_srt_keeps_unparsable_timing_line_as_text() {
+        let content = "1\n00:00:01,000 --> 00:00:02,000\nGood\n\n2\n00:xx:03,000 --> 00:00:04,000\nBad\n\n3\n00:00:05,000 --> 00:00:06,000\nAlso good";
+        let result = parse_srt_lenient(content);
+        assert_eq!(result.entries.len(), 2);
+        assert_eq!(result.entries[0].text, "Good\n2\n00:xx:03,000 --> 00:00:04,000\nBad");
+        assert_eq!(result.entries[1].id, 3);
+        let bad = result.diagnostics.iter().find(|d| d.line == 6).unwrap();
+        assert_eq!(bad.block, 1);
+        assert!(bad.issue.contains("无效的分钟"), "{}", bad.issue);
+    }
+
+    #[test]
+    fn test_parse_srt_arrow_in_dialogue() {
+        let content = "1\n00:00:01,000 --> 00:00:02,000\nHe said --> go\nnow";
+        let result = parse_srt_lenient(content);
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.entries[0].text, "He said --> go\nnow");
+        assert!(result.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_parse_srt_rejects_out_of_range_fields() {
+        let content = "1\n00:00:75,000 --> 00:01:20,000\nBad\n\n2\n00:01:30,000 --> 00:01:31,000\nGood";
+        let result = parse_srt_lenient(content);
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.entries[0].text, "Good");
+        let bad = result.diagnostics.iter().find(|d| d.line == 2).unwrap();
+        assert!(bad.auto_fix.is_none());
+        assert!(bad.issue.contains("时间字段超出范围"), "{}", bad.issue);
+    }
+
+    #[test]
+    fn test_build_srt_options() {
+        let entries = parse_srt_lenient("7\n00:00:01,000 --> 00:00:02,000\nA\nB\n\n9\n00:00:03,000 --> 00:00:04,000\nC").entries;
+        assert_eq!(
+            build_srt(&entries, &SrtWriteOptions::default()),
+            "1\n00:00:01,000 --> 00:00:02,000\nA\nB\n\n2\n00:00:03,000 --> 00:00:04,000\nC"
+        );
+
+        let options = SrtWriteOptions { renumber: false, line_ending: LineEnding::Crlf, final_newline: true, ..Default::default() };
+        let content = build_srt(&entries, &options);
+        assert_eq!(content, "7\r\n00:00:01,000 --> 00:00:02,000\r\nA\r\nB\r\n\r\n9\r\n00:00:03,000 --> 00:00:04,000\r\nC\r\n");
+        let reparsed = parse_srt_lenient(&content).entries;
+        assert_eq!(reparsed[1].id, 9);
+        assert_eq!(reparsed[0].text, "A\nB");
+    }
 }
@@ -0,0 +1,617 @@
+//! Advanced SubStation Alpha (.ass) / SubStation Alpha (.ssa) 读写
+//!
+//! `[Events]` 中的 Dialogue 和 Comment 行映射为 `SubtitleEntry`，样式名、图层、边距、特效以及
+//! 行首的覆盖标签（如 `{\an8}`）保存在 `AssEventMeta` 中；`[Script Info]`、样式表以及
+//! 其他无法识别的段落保存在 `AssHeader` 中，保证加载后再保存不会丢失信息。
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+use crate::file_safety::write_atomic;
+use super::{ParseDiagnostic, SubtitleEntry, TimeStamp};
+
+/// ASS 样式（对应 `[V4+ Styles]` 中的一行 Style）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AssStyle {
+    pub name: String,
+    pub fontname: String,
+    pub fontsize: f64,
+    /// 颜色使用 ASS 原始格式 `&HAABBGGRR`
+    pub primary_colour: String,
+    pub secondary_colour: String,
+    pub outline_colour: String,
+    pub back_colour: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strike_out: bool,
+    pub scale_x: f64,
+    pub scale_y: f64,
+    pub spacing: f64,
+    pub angle: f64,
+    pub border_style: u8,
+    pub outline: f64,
+    pub shadow: f64,
+    /// 小键盘布局的对齐方式（1-9）
+    pub alignment: u8,
+    pub margin_l: i32,
+    pub margin_r: i32,
+    pub margin_v: i32,
+    pub encoding: i32,
+}
+
+impl Default for AssStyle {
+    fn default() -> Self {
+        AssStyle {
+            name: "Default".to_string(),
+            fontname: "Arial".to_string(),
+            fontsize: 48.0,
+            primary_colour: "&H00FFFFFF".to_string(),
+            secondary_colour: "&H000000FF".to_string(),
+            outline_colour: "&H00000000".to_string(),
+            back_colour: "&H80000000".to_string(),
+            bold: false,
+            italic: false,
+            underline: false,
+            strike_out: false,
+            scale_x: 100.0,
+            scale_y: 100.0,
+            spacing: 0.0,
+            angle: 0.0,
+            border_style: 1,
+            outline: 2.0,
+            shadow: 1.0,
+            alignment: 2,
+            margin_l: 20,
+            margin_r: 20,
+            margin_v: 40,
+            encoding: 1,
+        }
+    }
+}
+
+/// 单条 Dialogue 的 ASS 专有属性
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AssEventMeta {
+    pub layer: i32,
+    pub style: String,
+    /// 说话人（Name/Actor 字段）
+    pub name: String,
+    pub margin_l: i32,
+    pub margin_r: i32,
+    pub margin_v: i32,
+    pub effect: String,
+    /// 文本开头的覆盖标签块，例如 `{\an8\fad(200,200)}`
+    pub override_tags: String,
+    /// 是否为 `Comment:` 事件（播放器不显示，保存时原样写回）
+    pub comment: bool,
+}
+
+impl Default for AssEventMeta {
+    fn default() -> Self {
+        AssEventMeta {
+            layer: 0,
+            style: "Default".to_string(),
+            name: String::new(),
+            margin_l: 0,
+            margin_r: 0,
+            margin_v: 0,
+            effect: String::new(),
+            override_tags: String::new(),
+            comment: false,
+        }
+    }
+}
+
+/// ASS 文件头：脚本信息、样式表以及其他原样保留的段落
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AssHeader {
+    /// `[Script Info]` 中的键值对（保持原顺序）
+    pub script_info: Vec<(String, String)>,
+    pub styles: Vec<AssStyle>,
+    /// `[Fonts]`、`[Graphics]` 等段落的原始文本
+    pub extra_sections: String,
+}
+
+const V4_PLUS_STYLE_FORMAT: &str = "Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding";
+const EVENT_FORMAT: &str = "Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text";
+
+/// 解析 ASS 时间：H:MM:SS.cc
+fn parse_ass_time(s: &str) -> Result<TimeStamp, String> {
+    let parts: Vec<&str> = s.trim().split(':').collect();
+    if parts.len() != 3 {
+        return Err(format!("Invalid ASS time: {}", s));
+    }
+    let (sec, frac) = parts[2].split_once('.').unwrap_or((parts[2], "0"));
+    let parse = |v: &str| v.trim().parse::<u32>().map_err(|_| format!("Invalid ASS time: {}", s));
+    // 小数部分只能是数字，下面按字节截取前 3 位
+    let frac = frac.trim();
+    if frac.is_empty() || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("Invalid ASS time: {}", s));
+    }
+
+    // 小数部分通常是百分之一秒，但也容忍 1 位或 3 位
+    let milliseconds = match frac.len() {
+        1 => parse(frac)? * 100,
+        2 => parse(frac)? * 10,
+        _ => parse(&frac[..3.min(frac.len())])?,
+    };
+
+    Ok(TimeStamp {
+        hours: parse(parts[0])?,
+        minutes: parse(parts[1])?,
+        seconds: parse(sec)?,
+        milliseconds,
+    })
+}
+
+/// 格式化为 ASS 时间：H:MM:SS.cc（四舍五入到百分之一秒）
+fn format_ass_time(ts: &TimeStamp) -> String {
+    let total_cs = (ts.to_ms() + 5) / 10;
+    format!(
+        "{}:{:02}:{:02}.{:02}",
+        total_cs / 360000,
+        (total_cs / 6000) % 60,
+        (total_cs / 100) % 60,
+        total_cs % 100
+    )
+}
+
+fn parse_bool(v: &str) -> bool {
+    v.trim().parse::<i32>().map(|n| n != 0).unwrap_or(false)
+}
+
+fn format_bool(v: bool) -> &'static str {
+    if v {
+        "-1"
+    } else {
+        "0"
+    }
+}
+
+/// SSA 的旧版对齐编号转换为小键盘布局
+fn legacy_alignment_to_numpad(a: u8) -> u8 {
+    match a {
+        5..=7 => a + 2,
+        9..=11 => a - 5,
+        _ => a,
+    }
+}
+
+fn parse_style(format: &[String], value: &str, is_ssa: bool) -> AssStyle {
+    let fields: Vec<&str> = value.splitn(format.len().max(1), ',').collect();
+    let mut style = AssStyle::default();
+
+    for (key, raw) in format.iter().zip(fields.iter()) {
+        let v = raw.trim();
+        let num = || v.parse::<f64>().unwrap_or(0.0);
+        let int = || v.parse::<i32>().unwrap_or(0);
+        match key.as_str() {
+            "name" => style.name = v.to_string(),
+            "fontname" => style.fontname = v.to_string(),
+            "fontsize" => style.fontsize = num(),
+            "primarycolour" => style.primary_colour = v.to_string(),
+            "secondarycolour" => style.secondary_colour = v.to_string(),
+            "outlinecolour" | "tertiarycolour" => style.outline_colour = v.to_string(),
+            "backcolour" => style.back_colour = v.to_string(),
+            "bold" => style.bold = parse_bool(v),
+            "italic" => style.italic = parse_bool(v),
+            "underline" => style.underline = parse_bool(v),
+            "strikeout" => style.strike_out = parse_bool(v),
+            "scalex" => style.scale_x = num(),
+            "scaley" => style.scale_y = num(),
+            "spacing" => style.spacing = num(),
+            "angle" => style.angle = num(),
+            "borderstyle" => style.border_style = int() as u8,
+            "outline" => style.outline = num(),
+            "shadow" => style.shadow = num(),
+            "alignment" => {
+                let a = int() as u8;
+                style.alignment = if is_ssa { legacy_alignment_to_numpad(a) } else { a };
+            }
+            "marginl" => style.margin_l = int(),
+            "marginr" => style.margin_r = int(),
+            "marginv" => style.margin_v = int(),
+            "encoding" => style.encoding = int(),
+            _ => {}
+        }
+    }
+
+    style
+}
+
+fn format_style(style: &AssStyle) -> String {
+    format!(
+        "Style: {},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+        style.name,
+        style.fontname,
+        style.fontsize,
+        style.primary_colour,
+        style.secondary_colour,
+        style.outline_colour,
+        style.back_colour,
+        format_bool(style.bold),
+        format_bool(style.italic),
+        format_bool(style.underline),
+        format_bool(style.strike_out),
+        style.scale_x,
+        style.scale_y,
+        style.spacing,
+        style.angle,
+        style.border_style,
+        style.outline,
+        style.shadow,
+        style.alignment,
+        style.margin_l,
+        style.margin_r,
+        style.margin_v,
+        style.encoding
+    )
+}
+
+/// 拆分行首的覆盖标签块，返回 (标签, 剩余文本)
+fn split_leading_override_tags(text: &str) -> (&str, &str) {
+    let mut end = 0;
+    while text[end..].starts_with('{') {
+        match text[end..].find('}') {
+            Some(close) => end += close + 1,
+            None => break,
+        }
+    }
+    text.split_at(end)
+}
+
+/// ASS 文本转换为编辑器文本：`\N` / `\n` 转换为换行，`\h` 转换为不换行空格
+fn ass_text_to_plain(text: &str) -> String {
+    text.replace("\\N", "\n")
+        .replace("\\n", "\n")
+        .replace("\\h", "\u{a0}")
+}
+
+fn plain_text_to_ass(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .replace('\n', "\\N")
+        .replace('\u{a0}', "\\h")
+}
+
+/// 解析 ASS/SSA 内容，返回文件头、字幕条目以及被跳过的事件行
+pub fn parse_ass(content: &str) -> Result<(AssHeader, Vec<SubtitleEntry>, Vec<ParseDiagnostic>), String> {
+    let content = super::normalize_line_endings(content);
+    let mut header = AssHeader::default();
+    let mut entries = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    let mut section = String::new();
+    let mut is_ssa = false;
+    let mut style_format: Vec<String> = Vec::new();
+    let mut event_format: Vec<String> = Vec::new();
+
+    for (line_no, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            section = trimmed[1..trimmed.len() - 1].to_lowercase();
+            if section == "v4 styles" {
+                is_ssa = true;
+            }
+            if !matches!(section.as_str(), "script info" | "v4+ styles" | "v4 styles" | "events") {
+                header.extra_sections.push_str(trimmed);
+                header.extra_sections.push('\n');
+            }
+            continue;
+        }
+
+        match section.as_str() {
+            "script info" => {
+                if trimmed.is_empty() || trimmed.starts_with(';') {
+                    continue;
+                }
+                if let Some((key, value)) = trimmed.split_once(':') {
+                    header
+                        .script_info
+                        .push((key.trim().to_string(), value.trim().to_string()));
+                }
+            }
+            "v4+ styles" | "v4 styles" => {
+                if let Some(value) = trimmed.strip_prefix("Format:") {
+                    style_format = value.split(',').map(|f| f.trim().to_lowercase()).collect();
+                } else if let Some(value) = trimmed.strip_prefix("Style:") {
+                    if style_format.is_empty() {
+                        style_format = V4_PLUS_STYLE_FORMAT
+                            .split(',')
+                            .map(|f| f.trim().to_lowercase())
+                            .collect();
+                    }
+                    header.styles.push(parse_style(&style_format, value.trim_start(), is_ssa));
+                }
+            }
+            "events" => {
+                if let Some(value) = trimmed.strip_prefix("Format:") {
+                    event_format = value.split(',').map(|f| f.trim().to_lowercase()).collect();
+                    continue;
+                }
+                let event = line.trim_start();
+                let (value, comment) = match event.strip_prefix("Dialogue:") {
+                    Some(value) => (value, false),
+                    None => match event.strip_prefix("Comment:") {
+                        Some(value) => (value, true),
+                        None => continue,
+                    },
+                };
+                if event_format.is_empty() {
+                    event_format = EVENT_FORMAT.split(',').map(|f| f.trim().to_lowercase()).collect();
+                }
+
+                // 单行格式错误时跳过该行并记录问题，不影响其他字幕
+                match parse_event(&event_format, value, comment, entries.len() as u32 + 1) {
+                    Ok(entry) => entries.push(entry),
+                    Err(e) => diagnostics.push(ParseDiagnostic {
+                        line: line_no + 1,
+                        block: 0,
+                        issue: format!("无法解析的 {} 行: {}", if comment { "Comment" } else { "Dialogue" }, e),
+                        auto_fix: Some("已跳过该行".to_string()),
+                    }),
+                }
+            }
+            _ => {
+                header.extra_sections.push_str(line);
+                header.extra_sections.push('\n');
+            }
+        }
+    }
+
+    if entries.is_empty() && header.styles.is_empty() {
+        return Err("No [V4+ Styles] or [Events] section found".to_string());
+    }
+
+    Ok((header, entries, diagnostics))
+}
+
+/// 按 Format 行解析单个 Dialogue/Comment 事件
+fn parse_event(event_format: &[String], value: &str, comment: bool, id: u32) -> Result<SubtitleEntry, String> {
+    let fields: Vec<&str> = value.trim_start().splitn(event_format.len(), ',').collect();
+    if fields.len() != event_format.len() {
+        return Err(format!("字段数为 {}，应为 {}", fields.len(), event_format.len()));
+    }
+
+    let mut meta = AssEventMeta { comment, ..Default::default() };
+    let mut start_time = None;
+    let mut end_time = None;
+    let mut text = String::new();
+    for (key, raw) in event_format.iter().zip(fields.iter()) {
+        let v = raw.trim();
+        let int = || v.parse::<i32>().unwrap_or(0);
+        match key.as_str() {
+            "layer" => meta.layer = int(),
+            "start" => start_time = Some(parse_ass_time(v)?),
+            "end" => end_time = Some(parse_ass_time(v)?),
+            "style" => meta.style = v.trim_start_matches('*').to_string(),
+            "name" | "actor" => meta.name = v.to_string(),
+            "marginl" => meta.margin_l = int(),
+            "marginr" => meta.margin_r = int(),
+            "marginv" => meta.margin_v = int(),
+            "effect" => meta.effect = v.to_string(),
+            // 文本字段不 trim，保留首尾空格
+            "text" => text = raw.to_string(),
+            _ => {}
+        }
+    }
+
+    let (tags, body) = split_leading_override_tags(&text);
+    meta.override_tags = tags.to_string();
+
+    Ok(SubtitleEntry {
+        id,
+        start_time: start_time.ok_or("缺少 Start 字段")?,
+        end_time: end_time.ok_or("缺少 End 字段")?,
+        text: ass_text_to_plain(body),
+        ass_event: Some(meta),
+        ..Default::default()
+    })
+}
+
+/// 合并样式：以文件头中的样式为基础，追加/覆盖额外配置的样式，确保存在 Default 样式
+fn merge_styles(header: Option<&AssHeader>, extra_styles: &[AssStyle]) -> Vec<AssStyle> {
+    let mut styles: Vec<AssStyle> = header.map(|h| h.styles.clone()).unwrap_or_default();
+
+    for style in extra_styles {
+        match styles.iter_mut().find(|s| s.name == style.name) {
+            Some(existing) => *existing = style.clone(),
+            None => styles.push(style.clone()),
+        }
+    }
+
+    if !styles.iter().any(|s| s.name == "Default") {
+        styles.insert(0, AssStyle::default());
+    }
+
+    styles
+}
+
+/// 生成 ASS 文件内容
+pub fn build_ass(entries: &[SubtitleEntry], header: Option<&AssHeader>, extra_styles: &[AssStyle]) -> String {
+    let mut content = String::from("[Script Info]\n");
+
+    let script_info = header.map(|h| h.script_info.as_slice()).unwrap_or(&[]);
+    if script_info.is_empty() {
+        content.push_str("; Script generated by VoSub\n");
+        content.push_str("ScriptType: v4.00+\n");
+        content.push_str("WrapStyle: 0\n");
+        content.push_str("ScaledBorderAndShadow: yes\n");
+        content.push_str("PlayResX: 1920\n");
+        content.push_str("PlayResY: 1080\n");
+    } else {
+        for (key, value) in script_info {
+            // SSA 文件导出后统一升级为 ASS
+            let value = if key.eq_ignore_ascii_case("ScriptType") { "v4.00+" } else { value.as_str() };
+            content.push_str(&format!("{}: {}\n", key, value));
+        }
+    }
+
+    content.push_str("\n[V4+ Styles]\n");
+    content.push_str(&format!("Format: {}\n", V4_PLUS_STYLE_FORMAT));
+    for style in merge_styles(header, extra_styles) {
+        content.push_str(&format_style(&style));
+        content.push('\n');
+    }
+
+    content.push_str("\n[Events]\n");
+    content.push_str(&format!("Format: {}\n", EVENT_FORMAT));
+    let default_meta = AssEventMeta::default();
+    for entry in entries {
+        let meta = entry.ass_event.as_ref().unwrap_or(&default_meta);
+        content.push_str(&format!(
+            "{}: {},{},{},{},{},{},{},{},{},{}{}\n",
+            if meta.comment { "Comment" } else { "Dialogue" },
+            meta.layer,
+            format_ass_time(&entry.start_time),
+            format_ass_time(&entry.end_time),
+            meta.style,
+            meta.name,
+            meta.margin_l,
+            meta.margin_r,
+            meta.margin_v,
+            meta.effect,
+            meta.override_tags,
+            plain_text_to_ass(&entry.text)
+        ));
+    }
+
+    if let Some(extra) = header.map(|h| h.extra_sections.trim()).filter(|s| !s.is_empty()) {
+        content.push('\n');
+        content.push_str(extra);
+        content.push('\n');
+    }
+
+    content
+}
+
+/// Export to ASS (Advanced SubStation Alpha)
+/// header: 原 ASS 文件的文件头（用于保留脚本信息和样式），extra_styles: 额外配置的样式
+pub fn export_to_ass(
+    file_path: &str,
+    entries: &[SubtitleEntry],
+    header: Option<&AssHeader>,
+    extra_styles: &[AssStyle],
+) -> Result<(), String> {
+    let content = build_ass(entries, header, extra_styles);
+
+    write_atomic(file_path, content)
+        .map_err(|e| format!("Failed to write ASS file: {}", e))?;
+
+    println!("Successfully exported {} subtitles to ASS: {}", entries.len(), file_path);
+    Ok(())
+}
+
+/// 去掉 ASS 的 `Comment:` 事件，供非 ASS 格式写入时使用（其他格式没有注释事件，否则会被当作字幕显示）
+/// 不含注释事件时直接借用原切片
+pub fn without_ass_comments(entries: &[SubtitleEntry]) -> Cow<'_, [SubtitleEntry]> {
+    let is_comment = |e: &SubtitleEntry| e.ass_event.as_ref().is_some_and(|meta| meta.comment);
+    if entries.iter().any(is_comment) {
+        Cow::Owned(entries.iter().filter(|e| !is_comment(e)).cloned().collect())
+    } else {
+        Cow::Borrowed(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "[Script Info]\nTitle: Sample\nScriptType: v4.00+\nPlayResX: 1920\n\n[V4+ Styles]\nFormat: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\nStyle: Default,Arial,48,&H00FFFFFF,&H000000FF,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,2,1,2,20,20,40,1\nStyle: Top,Noto Sans CJK SC,40,&H0000FFFF,&H000000FF,&H00000000,&H80000000,-1,0,0,0,100,100,0,0,1,2,1,8,20,20,40,1\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nDialogue: 1,0:00:01.50,0:00:04.00,Top,Alice,0,0,12,,{\\an8}Hello, world\\Nsecond line\nComment: 0,0:00:04.00,0:00:05.00,Default,,0,0,0,,TODO: check timing\nDialogue: 0,0:00:05.00,0:00:06.25,Default,,0,0,0,,Plain\n\n[Fonts]\nfontname: custom.ttf\n";
+
+    #[test]
+    fn test_parse_ass() {
+        let (header, entries, _) = parse_ass(SAMPLE).unwrap();
+        assert_eq!(header.styles.len(), 2);
+        assert!(header.styles[1].bold);
+        assert_eq!(header.styles[1].alignment, 8);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].text, "Hello, world\nsecond line");
+        assert!(entries[1].ass_event.as_ref().unwrap().comment);
+        assert_eq!(entries[1].text, "TODO: check timing");
+        assert_eq!(entries[0].start_time.milliseconds, 500);
+
+        let meta = entries[0].ass_event.as_ref().unwrap();
+        assert_eq!(meta.layer, 1);
+        assert_eq!(meta.style, "Top");
+        assert_eq!(meta.name, "Alice");
+        assert_eq!(meta.margin_v, 12);
+        assert_eq!(meta.override_tags, "{\\an8}");
+    }
+
+    #[test]
+    fn test_ass_round_trip() {
+        let (header, entries, _) = parse_ass(SAMPLE).unwrap();
+        let output = build_ass(&entries, Some(&header), &[]);
+        let (header2, entries2, _) = parse_ass(&output).unwrap();
+        assert_eq!(header, header2);
+        assert_eq!(entries.len(), entries2.len());
+        for (a, b) in entries.iter().zip(entries2.iter()) {
+            assert_eq!(a.text, b.text);
+            assert_eq!(a.ass_event, b.ass_event);
+            assert_eq!(a.start_time.to_ms(), b.start_time.to_ms());
+            assert_eq!(a.end_time.to_ms(), b.end_time.to_ms());
+        }
+        assert!(output.contains("[Fonts]\nfontname: custom.ttf"));
+        assert!(output.contains("Comment: 0,0:00:04.00,0:00:05.00,Default,,0,0,0,,TODO: check timing\n"));
+    }
+
+    #[test]
+    fn test_parse_ass_skips_malformed_event() {
+        let content = SAMPLE.replace(
+            "Dialogue: 0,0:00:05.00,",
+            "Dialogue: 0,0:00:xx.00,0:00:05.00,Default,,0,0,0,,Broken\nDialogue: 0,0:00:05.00,",
+        );
+        let (_, entries, diagnostics) = parse_ass(&content).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].text, "Plain");
+        assert_eq!(entries[2].id, 3);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 15);
+        assert!(diagnostics[0].issue.contains("Dialogue"));
+    }
+
+    #[test]
+    fn test_without_ass_comments() {
+        let (_, entries, _) = parse_ass(SAMPLE).unwrap();
+        let dialogue = without_ass_comments(&entries);
+        assert_eq!(dialogue.len(), 2);
+        assert!(dialogue.iter().all(|e| e.text != "TODO: check timing"));
+
+        assert!(matches!(without_ass_comments(&dialogue), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_export_adds_default_and_extra_styles() {
+        let entries = vec![SubtitleEntry {
+            id: 1,
+            start_time: TimeStamp { hours: 0, minutes: 0, seconds: 1, milliseconds: 0 },
+            end_time: TimeStamp { hours: 0, minutes: 0, seconds: 2, milliseconds: 0 },
+            text: "Hi".to_string(),
+            ..Default::default()
+        }];
+        let extra = AssStyle { name: "Sign".to_string(), ..Default::default() };
+        let output = build_ass(&entries, None, &[extra]);
+        assert!(output.contains("Style: Default,"));
+        assert!(output.contains("Style: Sign,"));
+        assert!(output.contains("Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Hi"));
+    }
+
+    #[test]
+    fn test_parse_ass_time_rejects_non_ascii_fraction() {
+        assert!(parse_ass_time("0:00:01.12é").is_err());
+        assert!(parse_ass_time("0:00:01.é").is_err());
+        assert_eq!(parse_ass_time("0:00:01.1234").unwrap().milliseconds, 123);
+    }
+
+    #[test]
+    fn test_parse_ssa_alignment() {
+        let content = "[Script Info]\nScriptType: v4.00\n\n[V4 Styles]\nFormat: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, TertiaryColour, BackColour, Bold, Italic, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, AlphaLevel, Encoding\nStyle: Default,Arial,20,16777215,65535,65535,0,0,0,1,2,2,6,10,10,10,0,0\n\n[Events]\nFormat: Marked, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nDialogue: Marked=0,0:00:01.00,0:00:02.00,Default,,0000,0000,0000,,Old style\n";
+        let (header, entries, _) = parse_ass(content).unwrap();
+        assert_eq!(header.styles[0].alignment, 8);
+        assert_eq!(entries[0].text, "Old style");
+    }
+}
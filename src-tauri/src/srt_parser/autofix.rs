@@ -0,0 +1,273 @@
+//! 自动修复时间轴问题：重叠、相邻间隔过短、时长过短 / 过长
+//!
+//! 按开始时间顺序逐对处理相邻字幕，不改变字幕顺序、ID 和文本。
+//! 提供音频能量时，重叠的分界点和过长字幕的截断点取能量最低（最安静）的位置。
+//! 每次调整都记录在修改报告中，供用户逐条确认。
+
+use serde::{Deserialize, Serialize};
+
+use super::{SubtitleEntry, TimeStamp};
+
+/// 重叠处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverlapStrategy {
+    /// 截短前一条字幕，使其在后一条开始前结束
+    TrimPrevious,
+    /// 在重叠区间内取分界点，前一条在此结束、后一条在此开始
+    Split,
+}
+
+/// 自动修复选项（阈值为 0 表示不处理该项）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutoFixOptions {
+    pub fix_overlaps: bool,
+    pub overlap_strategy: OverlapStrategy,
+    /// 相邻字幕的最小间隔，如 25 fps 下 2 帧为 80 ms
+    pub min_gap_ms: u64,
+    /// 过短的字幕延长到该时长（不会碰到下一条字幕）
+    pub min_duration_ms: u64,
+    /// 过长的字幕截短到该时长
+    pub max_duration_ms: u64,
+}
+
+impl Default for AutoFixOptions {
+    fn default() -> Self {
+        AutoFixOptions {
+            fix_overlaps: true,
+            overlap_strategy: OverlapStrategy::Split,
+            min_gap_ms: 83,
+            min_duration_ms: 833,
+            max_duration_ms: 7000,
+        }
+    }
+}
+
+/// 修改类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimingFixKind {
+    Overlap,
+    MinGap,
+    MinDuration,
+    MaxDuration,
+}
+
+/// 一次时间调整
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimingFix {
+    pub entry_id: u32,
+    pub kind: TimingFixKind,
+    pub old_start: TimeStamp,
+    pub old_end: TimeStamp,
+    pub new_start: TimeStamp,
+    pub new_end: TimeStamp,
+}
+
+/// 音频能量包络，由波形的 min/max 数据得到
+#[derive(Debug, Clone)]
+pub struct AudioEnergy {
+    levels: Vec<f32>,
+    ms_per_point: f64,
+}
+
+impl AudioEnergy {
+    /// 由交错的 [min0, max0, min1, max1, ...] 波形数据和音频时长（秒）构造
+    pub fn from_min_max(peaks: &[f32], duration: f64) -> Option<Self> {
+        let levels: Vec<f32> = peaks.chunks_exact(2).map(|pair| (pair[1] - pair[0]).abs()).collect();
+        if levels.is_empty() || !(duration.is_finite() && duration > 0.0) {
+            return None;
+        }
+        let ms_per_point = duration * 1000.0 / levels.len() as f64;
+        Some(AudioEnergy { levels, ms_per_point })
+    }
+
+    /// [start_ms, end_ms] 内能量最低的时间点，能量相同时取最靠近 prefer_ms 的点
+    fn quietest_between(&self, start_ms: u64, end_ms: u64, prefer_ms: u64) -> Option<u64> {
+        let first = (start_ms as f64 / self.ms_per_point).ceil() as usize;
+        let last = ((end_ms as f64 / self.ms_per_point).floor() as usize).min(self.levels.len().checked_sub(1)?);
+        (first..=last)
+            .map(|i| ((i as f64 * self.ms_per_point).round() as u64, self.levels[i]))
+            .min_by(|a, b| a.1.total_cmp(&b.1).then(a.0.abs_diff(prefer_ms).cmp(&b.0.abs_diff(prefer_ms))))
+            .map(|(ms, _)| ms.clamp(start_ms, end_ms))
+    }
+}
+
+/// 正在修改的字幕时间（毫秒）
+struct Span {
+    index: usize,
+    start: u64,
+    end: u64,
+}
+
+fn record(fixes: &mut Vec<TimingFix>, entry: &SubtitleEntry, kind: TimingFixKind, before: (u64, u64), after: (u64, u64)) {
+    if before != after {
+        fixes.push(TimingFix {
+            entry_id: entry.id,
+            kind,
+            old_start: TimeStamp::from_ms(before.0),
+            old_end: TimeStamp::from_ms(before.1),
+            new_start: TimeStamp::from_ms(after.0),
+            new_end: TimeStamp::from_ms(after.1),
+        });
+    }
+}
+
+/// 按选项修复字幕时间，返回修改报告（按字幕顺序排列）
+pub fn auto_fix_timing(
+    entries: &mut [SubtitleEntry],
+    options: &AutoFixOptions,
+    energy: Option<&AudioEnergy>,
+) -> Vec<TimingFix> {
+    let mut spans: Vec<Span> = entries
+        .iter()
+        .enumerate()
+        .map(|(index, e)| Span { index, start: e.start_time.to_ms(), end: e.end_time.to_ms() })
+        .collect();
+    spans.sort_by_key(|s| s.start);
+    let mut fixes = Vec::new();
+
+    // 重叠和间隔：只调整相邻两条字幕的交界处
+    for i in 1..spans.len() {
+        let (head, tail) = spans.split_at_mut(i);
+        let (prev, next) = (&mut head[i - 1], &mut tail[0]);
+        let (prev_before, next_before) = ((prev.start, prev.end), (next.start, next.end));
+
+        if options.fix_overlaps && prev.end > next.start {
+            let cut = match options.overlap_strategy {
+                OverlapStrategy::TrimPrevious => next.start,
+                OverlapStrategy::Split => {
+                    let (low, high) = (next.start, prev.end.min(next.end));
+                    let middle = low + (high - low) / 2;
+                    energy.and_then(|e| e.quietest_between(low, high, middle)).unwrap_or(middle)
+                }
+            };
+            // 后一条完全落在前一条开头时无法在交界处分开，保持原样
+            if cut > prev.start && cut < next.end {
+                prev.end = cut;
+                next.start = cut;
+            }
+            record(&mut fixes, &entries[prev.index], TimingFixKind::Overlap, prev_before, (prev.start, prev.end));
+            record(&mut fixes, &entries[next.index], TimingFixKind::Overlap, next_before, (next.start, next.end));
+        }
+
+        let gap_before = ((prev.start, prev.end), (next.start, next.end));
+        if options.min_gap_ms > 0 && prev.end <= next.start && next.start - prev.end < options.min_gap_ms {
+            // 优先提前前一条的结束时间；前一条太短时推后后一条的开始时间
+            let trimmed_end = next.start.saturating_sub(options.min_gap_ms);
+            if trimmed_end > prev.start {
+                prev.end = trimmed_end;
+            } else if prev.end + options.min_gap_ms < next.end {
+                next.start = prev.end + options.min_gap_ms;
+            }
+            record(&mut fixes, &entries[prev.index], TimingFixKind::MinGap, gap_before.0, (prev.start, prev.end));
+            record(&mut fixes, &entries[next.index], TimingFixKind::MinGap, gap_before.1, (next.start, next.end));
+        }
+    }
+
+    // 时长：延长时不越过下一条字幕开始前的最小间隔
+    for i in 0..spans.len() {
+        let limit = spans.get(i + 1).map(|next| next.start.saturating_sub(options.min_gap_ms));
+        let span = &mut spans[i];
+        let before = (span.start, span.end);
+        let duration = span.end.saturating_sub(span.start);
+
+        if options.min_duration_ms > 0 && duration < options.min_duration_ms {
+            let target = span.start + options.min_duration_ms;
+            let end = limit.map_or(target, |l| target.min(l));
+            if end > span.end {
+                span.end = end;
+            }
+            record(&mut fixes, &entries[span.index], TimingFixKind::MinDuration, before, (span.start, span.end));
+        } else if options.max_duration_ms > 0 && duration > options.max_duration_ms {
+            let latest = span.start + options.max_duration_ms;
+            // 在最长时长的最后四分之一内找停顿，避免截断在句子中间
+            let earliest = span.start + (options.max_duration_ms * 3 / 4).max(options.min_duration_ms).min(options.max_duration_ms);
+            span.end = energy
+                .and_then(|e| e.quietest_between(earliest, latest, latest))
+                .unwrap_or(latest);
+            record(&mut fixes, &entries[span.index], TimingFixKind::MaxDuration, before, (span.start, span.end));
+        }
+    }
+
+    for span in &spans {
+        let entry = &mut entries[span.index];
+        entry.start_time = TimeStamp::from_ms(span.start);
+        entry.end_time = TimeStamp::from_ms(span.end);
+    }
+
+    let order: std::collections::HashMap<u32, usize> = entries.iter().enumerate().map(|(i, e)| (e.id, i)).collect();
+    fixes.sort_by_key(|f| order.get(&f.entry_id).copied().unwrap_or(usize::MAX));
+    fixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(times: &[(u64, u64)]) -> Vec<SubtitleEntry> {
+        times
+            .iter()
+            .enumerate()
+            .map(|(i, &(start, end))| SubtitleEntry {
+                id: i as u32 + 1,
+                start_time: TimeStamp::from_ms(start),
+                end_time: TimeStamp::from_ms(end),
+                text: format!("line {}", i + 1),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn times(entries: &[SubtitleEntry]) -> Vec<(u64, u64)> {
+        entries.iter().map(|e| (e.start_time.to_ms(), e.end_time.to_ms())).collect()
+    }
+
+    #[test]
+    fn test_fix_overlap_gap_and_durations() {
+        let mut list = entries(&[(0, 2400), (2000, 4000), (4030, 4300), (6000, 16_000)]);
+        let fixes = auto_fix_timing(&mut list, &AutoFixOptions::default(), None);
+
+        // 重叠在中点分开，再留出 83 ms 间隔；第 3 条延长到 833 ms；第 4 条截到 7 秒
+        assert_eq!(times(&list), vec![(0, 2117), (2200, 3947), (4030, 4863), (6000, 13_000)]);
+        let kinds: Vec<(u32, TimingFixKind)> = fixes.iter().map(|f| (f.entry_id, f.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (1, TimingFixKind::Overlap),
+                (1, TimingFixKind::MinGap),
+                (2, TimingFixKind::Overlap),
+                (2, TimingFixKind::MinGap),
+                (3, TimingFixKind::MinDuration),
+                (4, TimingFixKind::MaxDuration),
+            ]
+        );
+        assert_eq!(fixes[0].old_end, TimeStamp::from_ms(2400));
+        assert_eq!(fixes[0].new_end, TimeStamp::from_ms(2200));
+
+        // 再次修复不应产生新的修改
+        assert!(auto_fix_timing(&mut list, &AutoFixOptions::default(), None).is_empty());
+    }
+
+    #[test]
+    fn test_trim_and_energy() {
+        let options = AutoFixOptions { overlap_strategy: OverlapStrategy::TrimPrevious, min_gap_ms: 0, ..Default::default() };
+        let mut list = entries(&[(0, 2400), (2000, 4000)]);
+        auto_fix_timing(&mut list, &options, None);
+        assert_eq!(times(&list), vec![(0, 2000), (2000, 4000)]);
+
+        // 每 100 ms 一个点，2300 ms 处最安静
+        let mut peaks = vec![];
+        for i in 0..100 {
+            let level = if i == 23 { 0.05 } else { 0.8 };
+            peaks.extend([-level, level]);
+        }
+        let energy = AudioEnergy::from_min_max(&peaks, 10.0).unwrap();
+        let options = AutoFixOptions { min_gap_ms: 0, ..Default::default() };
+        let mut list = entries(&[(0, 2400), (2000, 4000)]);
+        auto_fix_timing(&mut list, &options, Some(&energy));
+        assert_eq!(times(&list), vec![(0, 2300), (2300, 4000)]);
+    }
+}
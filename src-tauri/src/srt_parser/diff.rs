@@ -0,0 +1,498 @@
+//! 字幕版本比较与三方合并
+//!
+//! 比较：按时间重叠和文本相似度（而不是序号）对齐两个版本的字幕，报告新增、删除、改时间和改文字的字幕；
+//! 文字差异按词给出（CJK 逐字，其他文字按单词），与前端 textDiff 的逐字对比思路相同。
+//! 合并：分别比较共同基准与两个版本。只有一方修改的字幕、同一条字幕一方改时间一方改文字的情况自动合并；
+//! 两方对同一条字幕做了不同修改（或一方删除一方修改）时作为冲突返回，合并结果中暂用我方版本。
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::Serialize;
+
+use super::{is_cjk, SubtitleEntry};
+
+/// 时间重叠比例（交集 / 并集）达到该值时，文本完全改写也视为同一条字幕（如翻译）
+const SAME_SLOT_OVERLAP: f64 = 0.5;
+/// 时间部分重叠时视为同一条字幕所需的文本相似度
+const MIN_SIMILARITY: f64 = 0.3;
+/// 时间不重叠时，文本相似度达到该值且开始时间相差不超过 MAX_SHIFT_MS 仍视为同一条字幕（整体平移）
+const MOVED_SIMILARITY: f64 = 0.8;
+const MAX_SHIFT_MS: u64 = 10_000;
+
+/// 文字差异片段类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// 文字差异片段
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TextSegment {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+/// 字幕变化类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CueChangeKind {
+    Added,
+    Removed,
+    /// 时间或文字有变化，见 retimed / retexted
+    Changed,
+}
+
+/// 一条字幕的变化
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CueChange {
+    pub kind: CueChangeKind,
+    /// 在旧版本中的位置（Added 时为空）
+    pub old_index: Option<usize>,
+    /// 在新版本中的位置（Removed 时为空）
+    pub new_index: Option<usize>,
+    pub retimed: bool,
+    pub retexted: bool,
+    /// 文字差异（仅 retexted 时）
+    pub text_diff: Vec<TextSegment>,
+}
+
+/// 两个版本的比较结果，变化按时间排列
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleDiff {
+    pub changes: Vec<CueChange>,
+    /// 未变化的字幕数
+    pub unchanged: usize,
+}
+
+/// 合并冲突：两方对同一条字幕做了不同的修改
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeConflict {
+    /// 基准版本（两方在同一位置各自新增字幕时为空）
+    pub base: Option<SubtitleEntry>,
+    /// 我方版本（我方已删除时为空）
+    pub ours: Option<SubtitleEntry>,
+    /// 对方版本（对方已删除时为空）
+    pub theirs: Option<SubtitleEntry>,
+    /// 合并结果中暂用的字幕编号（暂用我方版本，我方已删除时用对方版本）
+    pub entry_id: Option<u32>,
+}
+
+/// 三方合并结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeResult {
+    /// 合并后的字幕，按时间排列并重新编号
+    pub entries: Vec<SubtitleEntry>,
+    pub conflicts: Vec<MergeConflict>,
+    /// 自动合并的对方修改数
+    pub applied: usize,
+}
+
+/// 拆分为比较单位：CJK 字符逐字，连续的其他字母数字为一个词，空白和标点各自独立
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut word_start = None;
+    for (i, c) in text.char_indices() {
+        if c.is_alphanumeric() && !is_cjk(c) {
+            word_start.get_or_insert(i);
+            continue;
+        }
+        if let Some(start) = word_start.take() {
+            tokens.push(&text[start..i]);
+        }
+        tokens.push(&text[i..i + c.len_utf8()]);
+    }
+    if let Some(start) = word_start {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// 最长公共子序列长度表：table[i][j] 为 a[i..] 与 b[j..] 的 LCS 长度
+fn lcs_table(a: &[&str], b: &[&str]) -> Vec<Vec<u32>> {
+    let mut table = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            table[i][j] = if a[i] == b[j] { table[i + 1][j + 1] + 1 } else { table[i + 1][j].max(table[i][j + 1]) };
+        }
+    }
+    table
+}
+
+/// 按词比较两段文字
+pub fn diff_words(old: &str, new: &str) -> Vec<TextSegment> {
+    let (a, b) = (tokenize(old), tokenize(new));
+    let table = lcs_table(&a, &b);
+    let mut segments: Vec<TextSegment> = Vec::new();
+    let mut push = |op: DiffOp, text: &str| match segments.last_mut() {
+        Some(last) if last.op == op => last.text.push_str(text),
+        _ => segments.push(TextSegment { op, text: text.to_string() }),
+    };
+
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            push(DiffOp::Equal, a[i]);
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            push(DiffOp::Delete, a[i]);
+            i += 1;
+        } else {
+            push(DiffOp::Insert, b[j]);
+            j += 1;
+        }
+    }
+    a[i..].iter().for_each(|token| push(DiffOp::Delete, token));
+    b[j..].iter().for_each(|token| push(DiffOp::Insert, token));
+    segments
+}
+
+/// 文本相似度（0~1），忽略空白
+fn text_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<&str> = tokenize(a).into_iter().filter(|t| !t.trim().is_empty()).collect();
+    let b: Vec<&str> = tokenize(b).into_iter().filter(|t| !t.trim().is_empty()).collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let common = lcs_table(&a, &b)[0][0] as f64;
+    2.0 * common / (a.len() + b.len()) as f64
+}
+
+fn span(entry: &SubtitleEntry) -> (u64, u64) {
+    let start = entry.start_time.to_ms();
+    (start, entry.end_time.to_ms().max(start))
+}
+
+/// 时间重叠比例（交集 / 并集）
+fn overlap_ratio(a: &SubtitleEntry, b: &SubtitleEntry) -> f64 {
+    let ((a_start, a_end), (b_start, b_end)) = (span(a), span(b));
+    let union = a_end.max(b_end) - a_start.min(b_start);
+    if union == 0 {
+        return if a_start == b_start { 1.0 } else { 0.0 };
+    }
+    a_end.min(b_end).saturating_sub(a_start.max(b_start)) as f64 / union as f64
+}
+
+/// 两条字幕视为同一条时的匹配分数，越高越相似；不视为同一条时为 None
+fn match_score(a: &SubtitleEntry, b: &SubtitleEntry) -> Option<f64> {
+    let overlap = overlap_ratio(a, b);
+    let similarity = text_similarity(&a.text, &b.text);
+    let shift = a.start_time.to_ms().abs_diff(b.start_time.to_ms());
+    let matched = overlap >= SAME_SLOT_OVERLAP
+        || (overlap > 0.0 && similarity >= MIN_SIMILARITY)
+        || (similarity >= MOVED_SIMILARITY && shift <= MAX_SHIFT_MS);
+    matched.then_some(overlap + similarity)
+}
+
+/// 按开始时间排列的下标
+fn time_order(entries: &[SubtitleEntry]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by_key(|&i| span(&entries[i]));
+    order
+}
+
+/// 对齐两个版本，返回匹配的 (旧下标, 新下标)
+///
+/// 先找出时间窗口内所有可能的匹配，再按分数从高到低选取，保证匹配按时间顺序互不交叉。
+fn align(old: &[SubtitleEntry], new: &[SubtitleEntry]) -> Vec<(usize, usize)> {
+    let (old_order, new_order) = (time_order(old), time_order(new));
+    let new_starts: Vec<u64> = new_order.iter().map(|&j| new[j].start_time.to_ms()).collect();
+    let longest = new.iter().map(|e| span(e).1 - span(e).0).max().unwrap_or(0);
+
+    let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+    for (old_rank, &i) in old_order.iter().enumerate() {
+        let (start, end) = span(&old[i]);
+        let first = new_starts.partition_point(|&s| s + longest + MAX_SHIFT_MS < start);
+        for new_rank in first..new_order.len() {
+            if new_starts[new_rank] > end + MAX_SHIFT_MS {
+                break;
+            }
+            if let Some(score) = match_score(&old[i], &new[new_order[new_rank]]) {
+                candidates.push((score, old_rank, new_rank));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+    let mut pairs: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut used = vec![false; new.len()];
+    for (_, old_rank, new_rank) in candidates {
+        if used[new_rank] || pairs.contains_key(&old_rank) {
+            continue;
+        }
+        let before = pairs.range(..old_rank).next_back().is_none_or(|(_, &r)| r < new_rank);
+        let after = pairs.range(old_rank + 1..).next().is_none_or(|(_, &r)| r > new_rank);
+        if before && after {
+            pairs.insert(old_rank, new_rank);
+            used[new_rank] = true;
+        }
+    }
+    pairs.into_iter().map(|(o, n)| (old_order[o], new_order[n])).collect()
+}
+
+fn same_time(a: &SubtitleEntry, b: &SubtitleEntry) -> bool {
+    span(a) == span(b)
+}
+
+fn same_cue(a: &SubtitleEntry, b: &SubtitleEntry) -> bool {
+    same_time(a, b) && a.text == b.text
+}
+
+/// 按时间和文本对齐比较两个版本的字幕
+pub fn diff_subtitles(old: &[SubtitleEntry], new: &[SubtitleEntry]) -> SubtitleDiff {
+    let pairs = align(old, new);
+    let mut diff = SubtitleDiff::default();
+    let (mut old_matched, mut new_matched) = (vec![false; old.len()], vec![false; new.len()]);
+
+    for (i, j) in pairs {
+        old_matched[i] = true;
+        new_matched[j] = true;
+        let retimed = !same_time(&old[i], &new[j]);
+        let retexted = old[i].text != new[j].text;
+        if !retimed && !retexted {
+            diff.unchanged += 1;
+            continue;
+        }
+        diff.changes.push(CueChange {
+            kind: CueChangeKind::Changed,
+            old_index: Some(i),
+            new_index: Some(j),
+            retimed,
+            retexted,
+            text_diff: if retexted { diff_words(&old[i].text, &new[j].text) } else { Vec::new() },
+        });
+    }
+
+    let removed = (0..old.len()).filter(|&i| !old_matched[i]).map(|i| CueChange {
+        kind: CueChangeKind::Removed,
+        old_index: Some(i),
+        new_index: None,
+        retimed: false,
+        retexted: false,
+        text_diff: Vec::new(),
+    });
+    let added = (0..new.len()).filter(|&j| !new_matched[j]).map(|j| CueChange {
+        kind: CueChangeKind::Added,
+        old_index: None,
+        new_index: Some(j),
+        retimed: false,
+        retexted: false,
+        text_diff: Vec::new(),
+    });
+    diff.changes.extend(removed.chain(added).collect::<Vec<_>>());
+    diff.changes.sort_by_key(|change| match (change.new_index, change.old_index) {
+        (Some(j), _) => span(&new[j]),
+        (None, Some(i)) => span(&old[i]),
+        (None, None) => (0, 0),
+    });
+    diff
+}
+
+/// 合并同一条字幕的两方修改：时间和文字分别取修改过的一方；两方对同一项做了不同修改时返回 None
+///
+/// 返回合并后的字幕，以及是否采用了对方的修改。
+fn merge_cue(base: &SubtitleEntry, ours: &SubtitleEntry, theirs: &SubtitleEntry) -> Option<(SubtitleEntry, bool)> {
+    let (ours_retimed, theirs_retimed) = (!same_time(base, ours), !same_time(base, theirs));
+    let (ours_retexted, theirs_retexted) = (base.text != ours.text, base.text != theirs.text);
+    if (ours_retimed && theirs_retimed && !same_time(ours, theirs))
+        || (ours_retexted && theirs_retexted && ours.text != theirs.text)
+    {
+        return None;
+    }
+
+    let mut merged = ours.clone();
+    let mut took_theirs = false;
+    if theirs_retimed && !ours_retimed {
+        merged.start_time = theirs.start_time.clone();
+        merged.end_time = theirs.end_time.clone();
+        took_theirs = true;
+    }
+    if theirs_retexted && !ours_retexted {
+        merged.text = theirs.text.clone();
+        merged.lrc_words = theirs.lrc_words.clone();
+        took_theirs = true;
+    }
+    Some((merged, took_theirs))
+}
+
+/// 以共同版本为基准三方合并字幕
+pub fn merge_subtitles(base: &[SubtitleEntry], ours: &[SubtitleEntry], theirs: &[SubtitleEntry]) -> MergeResult {
+    let ours_of: HashMap<usize, usize> = align(base, ours).into_iter().collect();
+    let theirs_of: HashMap<usize, usize> = align(base, theirs).into_iter().collect();
+
+    let mut result = MergeResult::default();
+    // 合并后的字幕，以及它对应的冲突
+    let mut merged: Vec<(SubtitleEntry, Option<usize>)> = Vec::new();
+    let mut conflict = |result: &mut MergeResult,
+                        base: Option<&SubtitleEntry>,
+                        ours: Option<&SubtitleEntry>,
+                        theirs: Option<&SubtitleEntry>| {
+        if let Some(kept) = ours.or(theirs) {
+            merged.push((kept.clone(), Some(result.conflicts.len())));
+        }
+        result.conflicts.push(MergeConflict {
+            base: base.cloned(),
+            ours: ours.cloned(),
+            theirs: theirs.cloned(),
+            entry_id: None,
+        });
+    };
+
+    let mut resolved: Vec<SubtitleEntry> = Vec::new();
+    for (b, base_entry) in base.iter().enumerate() {
+        let ours_entry = ours_of.get(&b).map(|&i| &ours[i]);
+        let theirs_entry = theirs_of.get(&b).map(|&j| &theirs[j]);
+        match (ours_entry, theirs_entry) {
+            (None, None) => {}
+            (Some(o), None) if same_cue(o, base_entry) => result.applied += 1,
+            (None, Some(t)) if same_cue(t, base_entry) => {}
+            (Some(o), Some(t)) => match merge_cue(base_entry, o, t) {
+                Some((entry, took_theirs)) => {
+                    result.applied += usize::from(took_theirs);
+                    resolved.push(entry);
+                }
+                None => conflict(&mut result, Some(base_entry), Some(o), Some(t)),
+            },
+            (o, t) => conflict(&mut result, Some(base_entry), o, t),
+        }
+    }
+
+    // 两方各自新增的字幕：在同一位置新增了不同的字幕时为冲突
+    let ours_known: HashSet<usize> = ours_of.values().copied().collect();
+    let theirs_known: HashSet<usize> = theirs_of.values().copied().collect();
+    let ours_added: Vec<SubtitleEntry> =
+        ours.iter().enumerate().filter(|(i, _)| !ours_known.contains(i)).map(|(_, e)| e.clone()).collect();
+    let theirs_added: Vec<SubtitleEntry> =
+        theirs.iter().enumerate().filter(|(j, _)| !theirs_known.contains(j)).map(|(_, e)| e.clone()).collect();
+    let added_pairs = align(&ours_added, &theirs_added);
+    let (paired_ours, paired_theirs): (HashSet<usize>, HashSet<usize>) = added_pairs.iter().copied().unzip();
+    for (i, j) in added_pairs {
+        if same_cue(&ours_added[i], &theirs_added[j]) {
+            resolved.push(ours_added[i].clone());
+        } else {
+            conflict(&mut result, None, Some(&ours_added[i]), Some(&theirs_added[j]));
+        }
+    }
+    resolved.extend(ours_added.iter().enumerate().filter(|(i, _)| !paired_ours.contains(i)).map(|(_, e)| e.clone()));
+    for (_, entry) in theirs_added.iter().enumerate().filter(|(j, _)| !paired_theirs.contains(j)) {
+        resolved.push(entry.clone());
+        result.applied += 1;
+    }
+
+    merged.extend(resolved.into_iter().map(|entry| (entry, None)));
+    merged.sort_by_key(|(entry, _)| span(entry));
+    for (index, (mut entry, conflict_index)) in merged.into_iter().enumerate() {
+        entry.id = index as u32 + 1;
+        if let Some(c) = conflict_index {
+            result.conflicts[c].entry_id = Some(entry.id);
+        }
+        result.entries.push(entry);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srt_parser::TimeStamp;
+
+    fn entry(id: u32, start_ms: u64, end_ms: u64, text: &str) -> SubtitleEntry {
+        SubtitleEntry {
+            id,
+            start_time: TimeStamp::from_ms(start_ms),
+            end_time: TimeStamp::from_ms(end_ms),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diff_words() {
+        let segments = diff_words("我们明天见 see you", "我们后天见 see them");
+        let ops: Vec<(DiffOp, &str)> = segments.iter().map(|s| (s.op, s.text.as_str())).collect();
+        assert_eq!(
+            ops,
+            vec![
+                (DiffOp::Equal, "我们"),
+                (DiffOp::Delete, "明"),
+                (DiffOp::Insert, "后"),
+                (DiffOp::Equal, "天见 see "),
+                (DiffOp::Delete, "you"),
+                (DiffOp::Insert, "them"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_aligns_by_time_not_index() {
+        let old = vec![
+            entry(1, 0, 2000, "第一句"),
+            entry(2, 3000, 5000, "第二句"),
+            entry(3, 6000, 8000, "第三句"),
+            entry(4, 9000, 11000, "第四句"),
+        ];
+        // 删除第一条后编号整体前移；第二条改时间，第三条改文字，并新增一条
+        let new = vec![
+            entry(1, 3200, 5200, "第二句"),
+            entry(2, 6000, 8000, "第三句话"),
+            entry(3, 9000, 11000, "第四句"),
+            entry(4, 12000, 13000, "新增"),
+        ];
+        let diff = diff_subtitles(&old, &new);
+        assert_eq!(diff.unchanged, 1);
+        let summary: Vec<_> = diff
+            .changes
+            .iter()
+            .map(|c| (c.kind, c.old_index, c.new_index, c.retimed, c.retexted))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (CueChangeKind::Removed, Some(0), None, false, false),
+                (CueChangeKind::Changed, Some(1), Some(0), true, false),
+                (CueChangeKind::Changed, Some(2), Some(1), false, true),
+                (CueChangeKind::Added, None, Some(3), false, false),
+            ]
+        );
+        assert_eq!(diff.changes[2].text_diff.last().map(|s| (s.op, s.text.as_str())), Some((DiffOp::Insert, "话")));
+    }
+
+    #[test]
+    fn test_three_way_merge() {
+        let base = vec![
+            entry(1, 0, 2000, "hello"),
+            entry(2, 3000, 5000, "world"),
+            entry(3, 6000, 8000, "foo"),
+            entry(4, 9000, 11000, "bar"),
+        ];
+        // 我方：改第一条文字，删除第四条
+        let ours = vec![
+            entry(1, 0, 2000, "hello there"),
+            entry(2, 3000, 5000, "world"),
+            entry(3, 6000, 8000, "foo mine"),
+        ];
+        // 对方：改第一条时间，第三条改成不同文字，新增一条
+        let theirs = vec![
+            entry(1, 100, 2100, "hello"),
+            entry(2, 3000, 5000, "world"),
+            entry(3, 6000, 8000, "foo theirs"),
+            entry(4, 9000, 11000, "bar"),
+            entry(5, 12000, 13000, "extra"),
+        ];
+        let result = merge_subtitles(&base, &ours, &theirs);
+        let texts: Vec<(&str, u64)> = result.entries.iter().map(|e| (e.text.as_str(), e.start_time.to_ms())).collect();
+        assert_eq!(texts, vec![("hello there", 100), ("world", 3000), ("foo mine", 6000), ("extra", 12000)]);
+        assert_eq!(result.applied, 2);
+        assert_eq!(result.conflicts.len(), 1);
+        let conflict = &result.conflicts[0];
+        assert_eq!(conflict.theirs.as_ref().map(|e| e.text.as_str()), Some("foo theirs"));
+        assert_eq!(conflict.entry_id, Some(3));
+    }
+}
@@ -0,0 +1,637 @@
+//! EBU STL (EBU Tech 3264) 二进制字幕读写
+//!
+//! 文件由 1024 字节的 GSI 头和若干 128 字节的 TTI 块组成。TTI 文本区按 GSI 中的字符表（CCT）
+//! 编码：`00` 为 ISO 6937 拉丁字母，`01`-`04` 为 ISO 8859-5/6/7/8。文本超过 112 字节时使用扩展块。
+//! GSI 中的标题等文本字段只写入 ASCII 字符。
+
+use encoding_rs::{Encoding, ISO_8859_5, ISO_8859_6, ISO_8859_7, ISO_8859_8};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::file_safety::write_atomic;
+use super::timecode::FrameRate;
+use super::{ParseDiagnostic, SubtitleEntry, TimeStamp};
+
+const GSI_SIZE: usize = 1024;
+const TTI_SIZE: usize = 128;
+const TEXT_FIELD_SIZE: usize = 112;
+
+// TTI 文本区控制码
+const ITALIC_ON: u8 = 0x80;
+const ITALIC_OFF: u8 = 0x81;
+const UNDERLINE_ON: u8 = 0x82;
+const UNDERLINE_OFF: u8 = 0x83;
+const LINE_BREAK: u8 = 0x8A;
+const UNUSED_SPACE: u8 = 0x8F;
+const DOUBLE_HEIGHT: u8 = 0x0D;
+const START_BOX: u8 = 0x0B;
+const END_BOX: u8 = 0x0A;
+/// 扩展块号：该字幕的最后一个 TTI 块
+const LAST_EXTENSION_BLOCK: u8 = 0xFF;
+/// 扩展块号：其余 TTI 块依次为 0x00-0xEF
+const MAX_EXTENSION_BLOCK: usize = 0xEF;
+/// TTI 中的字幕编号为 16 位
+const MAX_SUBTITLES: usize = u16::MAX as usize;
+/// GSI 中的 TTI 块总数（TNB）只有 5 位数字
+const MAX_BLOCKS: usize = 99_999;
+
+/// EBU STL 导出选项（读取时也用于保存 GSI 头中的信息）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EbuStlOptions {
+    /// 帧率：25（STL25.01）或 29.97 / 30（STL30.01，读取时按 29.97）
+    pub frame_rate: f64,
+    /// GSI 代码页（CPN），如 "437"、"850"
+    pub code_page: String,
+    /// 显示标准（DSC）："0" 开放字幕，"1" / "2" 图文电视 Level 1 / Level 2
+    pub display_standard: String,
+    /// 字符表（CCT）："00" 拉丁，"01" 西里尔，"02" 阿拉伯，"03" 希腊，"04" 希伯来
+    pub character_table: String,
+    /// 语言代码（LC），如 "09" 英语、"0F" 法语、"08" 德语
+    pub language_code: String,
+    /// 每行最多字符数（MNC）
+    pub max_row_length: usize,
+    /// 最大行数（MNR）
+    pub max_rows: usize,
+    pub programme_title: String,
+    pub episode_title: String,
+    /// 出品国家（CO），三位字母代码
+    pub country_of_origin: String,
+    pub publisher: String,
+}
+
+impl Default for EbuStlOptions {
+    fn default() -> Self {
+        EbuStlOptions {
+            frame_rate: 25.0,
+            code_page: "850".to_string(),
+            display_standard: "1".to_string(),
+            character_table: "00".to_string(),
+            language_code: "09".to_string(),
+            max_row_length: 40,
+            max_rows: 23,
+            programme_title: String::new(),
+            episode_title: String::new(),
+            country_of_origin: String::new(),
+            publisher: String::new(),
+        }
+    }
+}
+
+impl EbuStlOptions {
+    fn is_teletext(&self) -> bool {
+        self.display_standard == "1" || self.display_standard == "2"
+    }
+}
+
+// ============ 字符表 ============
+
+/// ISO 6937 中不需要组合的特殊字符
+const ISO6937_SPECIALS: &[(char, u8)] = &[
+    ('¡', 0xA1), ('¢', 0xA2), ('£', 0xA3), ('$', 0xA4), ('¥', 0xA5), ('#', 0xA6), ('§', 0xA7),
+    ('¤', 0xA8), ('‘', 0xA9), ('“', 0xAA), ('«', 0xAB), ('←', 0xAC), ('↑', 0xAD), ('→', 0xAE),
+    ('↓', 0xAF), ('°', 0xB0), ('±', 0xB1), ('²', 0xB2), ('³', 0xB3), ('×', 0xB4), ('µ', 0xB5),
+    ('¶', 0xB6), ('·', 0xB7), ('÷', 0xB8), ('’', 0xB9), ('”', 0xBA), ('»', 0xBB), ('¼', 0xBC),
+    ('½', 0xBD), ('¾', 0xBE), ('¿', 0xBF), ('―', 0xD0), ('¹', 0xD1), ('®', 0xD2), ('©', 0xD3),
+    ('™', 0xD4), ('♪', 0xD5), ('¬', 0xD6), ('¦', 0xD7), ('⅛', 0xDC), ('⅜', 0xDD), ('⅝', 0xDE),
+    ('⅞', 0xDF), ('Ω', 0xE0), ('Æ', 0xE1), ('Đ', 0xE2), ('ª', 0xE3), ('Ħ', 0xE4), ('Ĳ', 0xE6),
+    ('Ŀ', 0xE7), ('Ł', 0xE8), ('Ø', 0xE9), ('Œ', 0xEA), ('º', 0xEB), ('Þ', 0xEC), ('Ŧ', 0xED),
+    ('Ŋ', 0xEE), ('ŉ', 0xEF), ('ĸ', 0xF0), ('æ', 0xF1), ('đ', 0xF2), ('ð', 0xF3), ('ħ', 0xF4),
+    ('ı', 0xF5), ('ĳ', 0xF6), ('ŀ', 0xF7), ('ł', 0xF8), ('ø', 0xF9), ('œ', 0xFA), ('ß', 0xFB),
+    ('þ', 0xFC), ('ŧ', 0xFD), ('ŋ', 0xFE),
+];
+
+/// ISO 6937 组合附加符号：(前缀字节, 带附加符号的字符, 对应的基础字母)
+const ISO6937_DIACRITICS: &[(u8, &str, &str)] = &[
+    (0xC1, "ÀÈÌÒÙàèìòù", "AEIOUaeiou"),
+    (0xC2, "ÁĆÉÍĹŃÓŔŚÚÝŹáćéíĺńóŕśúýź", "ACEILNORSUYZaceilnorsuyz"),
+    (0xC3, "ÂĈÊĜĤÎĴÔŜÛŴŶâĉêĝĥîĵôŝûŵŷ", "ACEGHIJOSUWYaceghijosuwy"),
+    (0xC4, "ÃĨÑÕŨãĩñõũ", "AINOUainou"),
+    (0xC5, "ĀĒĪŌŪāēīōū", "AEIOUaeiou"),
+    (0xC6, "ĂĞŬăğŭ", "AGUagu"),
+    (0xC7, "ĊĖĠİŻċėġż", "CEGIZcegz"),
+    (0xC8, "ÄËÏÖÜŸäëïöüÿ", "AEIOUYaeiouy"),
+    (0xCA, "ÅŮåů", "AUau"),
+    (0xCB, "ÇĢĶĻŅŖŞŢçķļņŗşţ", "CGKLNRSTcklnrst"),
+    (0xCD, "ŐŰőű", "OUou"),
+    (0xCE, "ĄĘĮŲąęįų", "AEIUaeiu"),
+    (0xCF, "ČĎĚĽŇŘŠŤŽčďěľňřšťž", "CDELNRSTZcdelnrstz"),
+];
+
+fn encode_iso6937(c: char) -> Option<Vec<u8>> {
+    if let Some(&(_, byte)) = ISO6937_SPECIALS.iter().find(|(ch, _)| *ch == c) {
+        return Some(vec![byte]);
+    }
+    if (' '..='~').contains(&c) {
+        return Some(vec![c as u8]);
+    }
+    ISO6937_DIACRITICS.iter().find_map(|(prefix, accented, bases)| {
+        let index = accented.chars().position(|ch| ch == c)?;
+        Some(vec![*prefix, bases.chars().nth(index)? as u8])
+    })
+}
+
+fn decode_iso6937(prefix: Option<u8>, byte: u8) -> Option<char> {
+    if let Some(prefix) = prefix {
+        let (_, accented, bases) = ISO6937_DIACRITICS.iter().find(|(p, _, _)| *p == prefix)?;
+        let index = bases.chars().position(|ch| ch as u32 == byte as u32)?;
+        return accented.chars().nth(index);
+    }
+    match byte {
+        0x20..=0x7E => Some(byte as char),
+        _ => ISO6937_SPECIALS.iter().find(|(_, b)| *b == byte).map(|(c, _)| *c),
+    }
+}
+
+fn iso8859_table(character_table: &str) -> Result<Option<&'static Encoding>, String> {
+    match character_table {
+        "00" => Ok(None),
+        "01" => Ok(Some(ISO_8859_5)),
+        "02" => Ok(Some(ISO_8859_6)),
+        "03" => Ok(Some(ISO_8859_7)),
+        "04" => Ok(Some(ISO_8859_8)),
+        other => Err(format!("不支持的 EBU STL 字符表: {}", other)),
+    }
+}
+
+/// 按字符表编码单个字符
+fn encode_char(c: char, table: Option<&'static Encoding>) -> Option<Vec<u8>> {
+    match table {
+        None => encode_iso6937(c),
+        Some(_) if (' '..='~').contains(&c) => Some(vec![c as u8]),
+        Some(encoding) => {
+            let mut buf = [0u8; 4];
+            let (bytes, _, had_errors) = encoding.encode(c.encode_utf8(&mut buf));
+            (!had_errors && bytes.len() == 1 && bytes[0] >= 0xA0).then(|| bytes.to_vec())
+        }
+    }
+}
+
+// ============ 时间码 ============
+
+/// 帧率对应的 DFC 代码和实际帧率
+/// 29.97 与 30 都标记为 STL30.01，但按 30000/1001 计帧（时间码每秒 30 帧、不丢帧），避免每小时约 3.6 秒的漂移
+fn disk_format(frame_rate: f64) -> Result<(&'static str, FrameRate), String> {
+    let unsupported = || format!("EBU STL 仅支持 25、29.97 或 30 fps，当前为 {}", frame_rate);
+    let rate = FrameRate::from_fps(frame_rate).map_err(|_| unsupported())?;
+    match (rate.numerator, rate.denominator) {
+        (25, 1) => Ok(("STL25.01", rate)),
+        (30_000, 1001) | (30, 1) => Ok(("STL30.01", rate)),
+        _ => Err(unsupported()),
+    }
+}
+
+/// 毫秒转换为 (时, 分, 秒, 帧)
+fn to_timecode(ms: u64, rate: FrameRate) -> [u8; 4] {
+    let base = rate.timecode_base();
+    let frames = rate.frames_from_ms(ms);
+    [
+        (frames / (base * 3600)).min(99) as u8,
+        ((frames / (base * 60)) % 60) as u8,
+        ((frames / base) % 60) as u8,
+        (frames % base) as u8,
+    ]
+}
+
+fn from_timecode(tc: &[u8], rate: FrameRate) -> u64 {
+    let seconds = tc[0] as u64 * 3600 + tc[1] as u64 * 60 + tc[2] as u64;
+    rate.ms_from_frames(seconds * rate.timecode_base() + tc[3] as u64)
+}
+
+/// 当前日期 YYMMDD（UTC）
+fn today_yymmdd() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or(0) as i64;
+    // 公历日期换算（Howard Hinnant 的 civil_from_days 算法）
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:02}{:02}{:02}", year % 100, month, day)
+}
+
+// ============ 导出 ============
+
+/// 写入定长 ASCII 字段，不足部分补空格
+fn push_field(buf: &mut Vec<u8>, value: &str, len: usize) {
+    let mut bytes: Vec<u8> = value
+        .chars()
+        .map(|c| if (' '..='~').contains(&c) { c as u8 } else { b'?' })
+        .take(len)
+        .collect();
+    bytes.resize(len, b' ');
+    buf.extend_from_slice(&bytes);
+}
+
+/// 把一条字幕编码为 TTI 文本区字节，同时检查每行字符数
+fn encode_text(
+    entry: &SubtitleEntry,
+    options: &EbuStlOptions,
+    table: Option<&'static Encoding>,
+    violations: &mut Vec<String>,
+) -> Vec<u8> {
+    let teletext = options.is_teletext();
+    let mut bytes = Vec::new();
+
+    for (line_index, line) in entry.text.lines().enumerate() {
+        if line_index > 0 {
+            // 图文电视使用双倍高度，每行占两行位置
+            bytes.push(LINE_BREAK);
+            if teletext {
+                bytes.push(LINE_BREAK);
+            }
+        }
+        if teletext {
+            bytes.extend_from_slice(&[DOUBLE_HEIGHT, START_BOX, START_BOX]);
+        }
+
+        let mut visible = 0;
+        let mut rest = line;
+        while let Some(c) = rest.chars().next() {
+            let tag = [("<i>", ITALIC_ON), ("</i>", ITALIC_OFF), ("<u>", UNDERLINE_ON), ("</u>", UNDERLINE_OFF)]
+                .into_iter()
+                .find(|(tag, _)| rest.get(..tag.len()).is_some_and(|s| s.eq_ignore_ascii_case(tag)));
+            if let Some((tag, code)) = tag {
+                bytes.push(code);
+                rest = &rest[tag.len()..];
+                continue;
+            }
+            match encode_char(c, table) {
+                Some(encoded) => bytes.extend_from_slice(&encoded),
+                None => violations.push(format!("第 {} 条字幕包含字符表 {} 无法表示的字符「{}」", entry.id, options.character_table, c)),
+            }
+            visible += 1;
+            rest = &rest[c.len_utf8()..];
+        }
+
+        if teletext {
+            bytes.extend_from_slice(&[END_BOX, END_BOX]);
+        }
+        if visible > options.max_row_length {
+            violations.push(format!(
+                "第 {} 条字幕第 {} 行有 {} 个字符，超过每行 {} 个字符的限制",
+                entry.id,
+                line_index + 1,
+                visible,
+                options.max_row_length
+            ));
+        }
+    }
+    bytes
+}
+
+/// 生成 EBU STL 文件内容，有违反约束的字幕时返回全部问题
+pub fn build_ebu_stl(entries: &[SubtitleEntry], options: &EbuStlOptions) -> Result<Vec<u8>, String> {
+    let (dfc, rate) = disk_format(options.frame_rate)?;
+    let table = iso8859_table(&options.character_table)?;
+    if !matches!(options.display_standard.as_str(), " " | "0" | "1" | "2") {
+        return Err(format!("不支持的 EBU STL 显示标准: {}", options.display_standard));
+    }
+
+    if entries.len() > MAX_SUBTITLES {
+        return Err(format!("EBU STL 最多 {} 条字幕，当前为 {} 条", MAX_SUBTITLES, entries.len()));
+    }
+
+    let mut violations = Vec::new();
+    let mut tti = Vec::new();
+    let mut block_count = 0;
+
+    for (index, entry) in entries.iter().enumerate() {
+        let text = encode_text(entry, options, table, &mut violations);
+        let line_count = entry.text.lines().count().max(1);
+        let rows_used = if options.is_teletext() { line_count * 2 } else { line_count };
+        if rows_used > options.max_rows {
+            violations.push(format!("第 {} 条字幕有 {} 行，超过 {} 行的限制", entry.id, line_count, options.max_rows));
+        }
+        // 底部对齐：最后一行位于倒数第二行
+        let vertical_position = (options.max_rows.saturating_sub(rows_used)).max(1) as u8;
+
+        let chunks: Vec<&[u8]> = if text.is_empty() { vec![&[]] } else { text.chunks(TEXT_FIELD_SIZE).collect() };
+        if chunks.len() > MAX_EXTENSION_BLOCK + 2 {
+            violations.push(format!(
+                "第 {} 条字幕文本过长，需要 {} 个 TTI 块，超过 {} 个的限制",
+                entry.id,
+                chunks.len(),
+                MAX_EXTENSION_BLOCK + 2
+            ));
+            continue;
+        }
+        for (chunk_index, chunk) in chunks.iter().enumerate() {
+            let extension = if chunk_index + 1 == chunks.len() { LAST_EXTENSION_BLOCK } else { chunk_index as u8 };
+            tti.push(0); // SGN
+            tti.extend_from_slice(&((index + 1) as u16).to_le_bytes()); // SN
+            tti.push(extension); // EBN
+            tti.push(0); // CS
+            tti.extend_from_slice(&to_timecode(entry.start_time.to_ms(), rate)); // TCI
+            tti.extend_from_slice(&to_timecode(entry.end_time.to_ms(), rate)); // TCO
+            tti.push(vertical_position); // VP
+            tti.push(2); // JC: 居中
+            tti.push(0); // CF
+            tti.extend_from_slice(chunk);
+            tti.resize(tti.len() + TEXT_FIELD_SIZE - chunk.len(), UNUSED_SPACE);
+            block_count += 1;
+        }
+    }
+
+    if block_count > MAX_BLOCKS {
+        violations.push(format!("共需要 {} 个 TTI 块，超过 {} 个的限制", block_count, MAX_BLOCKS));
+    }
+    if !violations.is_empty() {
+        return Err(violations.join("\n"));
+    }
+
+    let first_in = entries.first().map(|e| to_timecode(e.start_time.to_ms(), rate)).unwrap_or([0; 4]);
+    let first_in = format!("{:02}{:02}{:02}{:02}", first_in[0], first_in[1], first_in[2], first_in[3]);
+    let date = today_yymmdd();
+
+    let mut gsi = Vec::with_capacity(GSI_SIZE);
+    push_field(&mut gsi, &options.code_page, 3); // CPN
+    push_field(&mut gsi, dfc, 8); // DFC
+    push_field(&mut gsi, &options.display_standard, 1); // DSC
+    push_field(&mut gsi, &options.character_table, 2); // CCT
+    push_field(&mut gsi, &options.language_code, 2); // LC
+    push_field(&mut gsi, &options.programme_title, 32); // OPT
+    push_field(&mut gsi, &options.episode_title, 32); // OET
+    push_field(&mut gsi, "", 32); // TPT
+    push_field(&mut gsi, "", 32); // TET
+    push_field(&mut gsi, "", 32); // TN
+    push_field(&mut gsi, "", 32); // TCD
+    push_field(&mut gsi, "", 16); // SLR
+    push_field(&mut gsi, &date, 6); // CD
+    push_field(&mut gsi, &date, 6); // RD
+    push_field(&mut gsi, "00", 2); // RN
+    push_field(&mut gsi, &format!("{:05}", block_count), 5); // TNB
+    push_field(&mut gsi, &format!("{:05}", entries.len()), 5); // TNS
+    push_field(&mut gsi, "001", 3); // TNG
+    push_field(&mut gsi, &format!("{:02}", options.max_row_length.min(99)), 2); // MNC
+    push_field(&mut gsi, &format!("{:02}", options.max_rows.min(99)), 2); // MNR
+    push_field(&mut gsi, "1", 1); // TCS
+    push_field(&mut gsi, "00000000", 8); // TCP
+    push_field(&mut gsi, &first_in, 8); // TCF
+    push_field(&mut gsi, "1", 1); // TND
+    push_field(&mut gsi, "1", 1); // DSN
+    push_field(&mut gsi, &options.country_of_origin, 3); // CO
+    push_field(&mut gsi, &options.publisher, 32); // PUB
+    push_field(&mut gsi, "", 32); // EN
+    push_field(&mut gsi, "", 32); // ECD
+    gsi.resize(GSI_SIZE, b' '); // 备用区和用户定义区
+
+    gsi.extend_from_slice(&tti);
+    Ok(gsi)
+}
+
+/// Export to EBU STL (Tech 3264)
+pub fn export_to_ebu_stl(file_path: &str, entries: &[SubtitleEntry], options: &EbuStlOptions) -> Result<(), String> {
+    let content = build_ebu_stl(entries, options)?;
+
+    write_atomic(file_path, content)
+        .map_err(|e| format!("Failed to write EBU STL file: {}", e))?;
+
+    println!("Successfully exported {} subtitles to EBU STL: {}", entries.len(), file_path);
+    Ok(())
+}
+
+// ============ 导入 ============
+
+/// 判断内容是否为 EBU STL（GSI 中的 DFC 字段）
+pub fn is_ebu_stl(bytes: &[u8]) -> bool {
+    matches!(bytes.get(3..11), Some(b"STL25.01") | Some(b"STL30.01"))
+}
+
+fn gsi_text(gsi: &[u8], start: usize, len: usize) -> String {
+    String::from_utf8_lossy(&gsi[start..start + len]).trim().to_string()
+}
+
+/// 解码 TTI 文本区（已拼接扩展块）
+fn decode_text(bytes: &[u8], table: Option<&'static Encoding>) -> String {
+    let mut text = String::new();
+    let mut prefix: Option<u8> = None;
+
+    for &byte in bytes {
+        match byte {
+            UNUSED_SPACE => break,
+            LINE_BREAK => {
+                if !text.ends_with('\n') {
+                    text.push('\n');
+                }
+            }
+            ITALIC_ON => text.push_str("<i>"),
+            ITALIC_OFF => text.push_str("</i>"),
+            UNDERLINE_ON => text.push_str("<u>"),
+            UNDERLINE_OFF => text.push_str("</u>"),
+            // 图文电视控制码（颜色、双倍高度、方框等）
+            0x00..=0x1F | 0x84..=0x9F => {}
+            0xC1..=0xCF if table.is_none() => prefix = Some(byte),
+            _ => {
+                let decoded = match table {
+                    None => decode_iso6937(prefix.take(), byte),
+                    Some(_) if byte < 0x80 => Some(byte as char),
+                    Some(encoding) => encoding.decode_without_bom_handling(&[byte]).0.chars().next(),
+                };
+                if let Some(c) = decoded {
+                    text.push(c);
+                }
+            }
+        }
+    }
+
+    text.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect::<Vec<_>>().join("\n")
+}
+
+/// 解析 EBU STL 文件，返回 GSI 信息、字幕和发现的问题（用于检查合作方交付的文件）
+pub fn parse_ebu_stl(bytes: &[u8]) -> Result<(EbuStlOptions, Vec<SubtitleEntry>, Vec<ParseDiagnostic>), String> {
+    if bytes.len() < GSI_SIZE || !is_ebu_stl(bytes) {
+        return Err("不是有效的 EBU STL 文件".to_string());
+    }
+    let gsi = &bytes[..GSI_SIZE];
+    let number = |start: usize, len: usize| gsi_text(gsi, start, len).parse::<usize>().ok();
+
+    // STL30.01 不区分 29.97 和 30，按更常见的 29.97 读取
+    let (_, rate) = disk_format(if &gsi[3..11] == b"STL25.01" { 25.0 } else { 29.97 })?;
+    let options = EbuStlOptions {
+        frame_rate: rate.fps(),
+        code_page: gsi_text(gsi, 0, 3),
+        display_standard: String::from_utf8_lossy(&gsi[11..12]).to_string(),
+        character_table: gsi_text(gsi, 12, 2),
+        language_code: gsi_text(gsi, 14, 2),
+        programme_title: gsi_text(gsi, 16, 32),
+        episode_title: gsi_text(gsi, 48, 32),
+        max_row_length: number(251, 2).unwrap_or(40),
+        max_rows: number(253, 2).unwrap_or(23),
+        country_of_origin: gsi_text(gsi, 274, 3),
+        publisher: gsi_text(gsi, 277, 32),
+    };
+    let table = iso8859_table(if options.character_table.is_empty() { "00" } else { &options.character_table })?;
+
+    let mut diagnostics = Vec::new();
+    let mut diagnose = |block: usize, issue: String| {
+        diagnostics.push(ParseDiagnostic { line: 0, block, issue, auto_fix: Some("保留原内容".to_string()) });
+    };
+
+    let body = &bytes[GSI_SIZE..];
+    if !body.len().is_multiple_of(TTI_SIZE) {
+        diagnose(0, format!("文件末尾有 {} 字节不完整的 TTI 块", body.len() % TTI_SIZE));
+    }
+    let blocks: Vec<&[u8]> = body.chunks_exact(TTI_SIZE).collect();
+    if let Some(declared) = number(238, 5).filter(|&n| n != blocks.len()) {
+        diagnose(0, format!("GSI 声明 {} 个 TTI 块，实际为 {} 个", declared, blocks.len()));
+    }
+
+    let mut entries: Vec<SubtitleEntry> = Vec::new();
+    let mut text_bytes: Vec<u8> = Vec::new();
+    for (index, block) in blocks.iter().enumerate() {
+        let extension = block[3];
+        // 注释块和用户数据块不含字幕
+        if block[15] != 0 || extension == 0xFE {
+            continue;
+        }
+        text_bytes.extend_from_slice(&block[16..]);
+        if extension != LAST_EXTENSION_BLOCK {
+            continue;
+        }
+
+        let subtitle_number = u16::from_le_bytes([block[1], block[2]]) as usize;
+        for (label, tc) in [("入点", &block[5..9]), ("出点", &block[9..13])] {
+            if tc[1] > 59 || tc[2] > 59 || tc[3] as u64 >= rate.timecode_base() {
+                diagnose(subtitle_number, format!("TTI 块 {} 的{}时间码无效", index + 1, label));
+            }
+        }
+        let start = from_timecode(&block[5..9], rate);
+        let end = from_timecode(&block[9..13], rate);
+        if end <= start {
+            diagnose(subtitle_number, format!("字幕 {} 的出点不晚于入点", subtitle_number));
+        }
+
+        let text = decode_text(&std::mem::take(&mut text_bytes), table);
+        for (line_index, line) in text.lines().enumerate() {
+            let visible = line.replace("<i>", "").replace("</i>", "").replace("<u>", "").replace("</u>", "");
+            if visible.chars().count() > options.max_row_length {
+                diagnose(subtitle_number, format!(
+                    "字幕 {} 第 {} 行超过每行 {} 个字符的限制",
+                    subtitle_number,
+                    line_index + 1,
+                    options.max_row_length
+                ));
+            }
+        }
+
+        entries.push(SubtitleEntry {
+            id: entries.len() as u32 + 1,
+            start_time: TimeStamp::from_ms(start),
+            end_time: TimeStamp::from_ms(end),
+            text,
+            ..Default::default()
+        });
+    }
+
+    if let Some(declared) = number(243, 5).filter(|&n| n != entries.len()) {
+        diagnose(0, format!("GSI 声明 {} 条字幕，实际为 {} 条", declared, entries.len()));
+    }
+
+    Ok((options, entries, diagnostics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u32, start_ms: u64, end_ms: u64, text: &str) -> SubtitleEntry {
+        SubtitleEntry {
+            id,
+            start_time: TimeStamp::from_ms(start_ms),
+            end_time: TimeStamp::from_ms(end_ms),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diacritic_tables_are_aligned() {
+        for (prefix, accented, bases) in ISO6937_DIACRITICS {
+            assert_eq!(accented.chars().count(), bases.chars().count(), "prefix {:#X}", prefix);
+        }
+    }
+
+    #[test]
+    fn test_ebu_stl_round_trip() {
+        let long_text = "A fairly long line with café\n<i>and a second, longer line that needs</i> ".repeat(2);
+        let entries = vec![
+            entry(1, 1000, 3500, "Çà va? Œuvre £5"),
+            entry(2, 4000, 6040, long_text.trim()),
+        ];
+        let options = EbuStlOptions { max_row_length: 80, ..Default::default() };
+        let bytes = build_ebu_stl(&entries, &options).unwrap();
+        assert_eq!(&bytes[3..11], b"STL25.01");
+        // 第二条字幕超过 112 字节，使用了扩展块
+        assert_eq!((bytes.len() - GSI_SIZE) / TTI_SIZE, 3);
+
+        let (parsed_options, parsed, diagnostics) = parse_ebu_stl(&bytes).unwrap();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(parsed_options.max_row_length, 80);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].text, "Çà va? Œuvre £5");
+        assert_eq!(parsed[0].end_time.to_ms(), 3520);
+        assert_eq!(parsed[1].text, long_text.trim());
+        assert_eq!(parsed[1].end_time.to_ms(), 6040);
+    }
+
+    #[test]
+    fn test_ebu_stl_reports_violations() {
+        let entries = vec![entry(7, 0, 1000, "This line is definitely longer than twenty"), entry(8, 1000, 2000, "中文")];
+        let options = EbuStlOptions { max_row_length: 20, ..Default::default() };
+        let err = build_ebu_stl(&entries, &options).unwrap_err();
+        assert!(err.contains("第 7 条字幕第 1 行"));
+        assert!(err.contains("第 8 条字幕包含字符表 00 无法表示的字符「中」"));
+
+        assert!(build_ebu_stl(&[], &EbuStlOptions { frame_rate: 24.0, ..Default::default() }).is_err());
+    }
+
+    #[test]
+    fn test_ebu_stl_rejects_counts_beyond_format_limits() {
+        // 每行 40 字符，242 行需要 242 个以上的 TTI 块，扩展块号超出 0xEF
+        let long_text = vec!["x".repeat(40); 700].join("\n");
+        let options = EbuStlOptions { max_rows: 1000, ..Default::default() };
+        let err = build_ebu_stl(&[entry(3, 0, 1000, &long_text)], &options).unwrap_err();
+        assert!(err.contains("第 3 条字幕文本过长"), "{}", err);
+
+        let entries: Vec<SubtitleEntry> = (0..=MAX_SUBTITLES as u32).map(|i| entry(i + 1, 0, 1000, "a")).collect();
+        assert!(build_ebu_stl(&entries, &EbuStlOptions::default()).unwrap_err().contains("最多 65535 条字幕"));
+    }
+
+    #[test]
+    fn test_ebu_stl_greek_table() {
+        let options = EbuStlOptions {
+            frame_rate: 29.97,
+            display_standard: "0".to_string(),
+            character_table: "03".to_string(),
+            ..Default::default()
+        };
+        let bytes = build_ebu_stl(&[entry(1, 0, 1000, "Καλημέρα\nκόσμε")], &options).unwrap();
+        assert_eq!(&bytes[3..11], b"STL30.01");
+        let (parsed_options, parsed, _) = parse_ebu_stl(&bytes).unwrap();
+        assert!((parsed_options.frame_rate - 29.97).abs() < 0.01);
+        assert_eq!(parsed[0].text, "Καλημέρα\nκόσμε");
+        // 1000 ms 最接近第 30 帧，即 1001 ms
+        assert_eq!(parsed[0].end_time.to_ms(), 1001);
+        assert_eq!(to_timecode(1999, disk_format(25.0).unwrap().1), [0, 0, 2, 0]);
+    }
+
+    #[test]
+    fn test_ebu_stl_ntsc_frame_count() {
+        let (dfc, rate) = disk_format(29.97).unwrap();
+        assert_eq!(dfc, "STL30.01");
+        // 一小时实际为 107892 帧，按每秒 30 帧的时间码标记
+        assert_eq!(to_timecode(3_600_000, rate), [0, 59, 56, 12]);
+        // 第 107892 帧位于 3599996.4 ms
+        assert_eq!(from_timecode(&[0, 59, 56, 12], rate), 3_599_996);
+        assert_eq!(to_timecode(3_600_000, disk_format(30.0).unwrap().1), [1, 0, 0, 0]);
+        assert!(disk_format(24.0).is_err());
+    }
+}
@@ -0,0 +1,889 @@
+//! FCPXML（Final Cut Pro XML）读写
+//!
+//! 所有 `offset` / `duration` 都以帧为单位换算为有理数秒，NTSC 帧率使用 1001 为分母的精确时基
+//! （如 29.97 fps 的一帧为 `1001/30000s`），避免在 23.976 / 29.97 / 59.94 时间线上累积漂移。
+//! 导入时读取第一个 sequence 中的 `<title>` 和 `<caption>`，按父元素的 offset / start 换算为时间线上的绝对时间。
+
+use serde::{Deserialize, Serialize};
+
+use crate::file_safety::write_atomic;
+use super::timecode::FrameRate;
+use super::{escape_xml, scc, SubtitleEntry, TimeStamp};
+
+/// FCPXML 的有理数时间写法
+impl FrameRate {
+    /// 一帧时长的分子和分母（秒）。整数帧率沿用 FCP 的写法 100/2500s
+    fn frame_duration_parts(&self) -> (u64, u64) {
+        if self.denominator == 1 {
+            (100, self.numerator * 100)
+        } else {
+            (self.denominator, self.numerator)
+        }
+    }
+
+    /// 一帧的时长，如 `1001/30000s`
+    pub fn frame_duration(&self) -> String {
+        let (num, den) = self.frame_duration_parts();
+        format!("{}/{}s", num, den)
+    }
+
+    /// 帧数换算为 FCPXML 有理数时间，如 `30030/30000s`
+    pub fn rational_time(&self, frames: u64) -> String {
+        if frames == 0 {
+            return "0s".to_string();
+        }
+        let (num, den) = self.frame_duration_parts();
+        format!("{}/{}s", frames * num, den)
+    }
+
+    /// FCP 格式名中的帧率部分：2398、25、2997、5994 等
+    fn format_suffix(&self) -> String {
+        let hundredths = (self.fps() * 100.0).round() as u64;
+        if hundredths.is_multiple_of(100) {
+            (hundredths / 100).to_string()
+        } else {
+            hundredths.to_string()
+        }
+    }
+}
+
+/// FCPXML 字幕写为 `<title>`（图形标题）还是 `<caption>`（FCP 字幕检查器中的辅助字幕）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FcpxmlMode {
+    #[default]
+    Titles,
+    Captions,
+}
+
+/// FCPXML 导出选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FcpxmlExportOptions {
+    /// 帧率，如 23.976、25、29.97、59.94
+    pub fps: f64,
+    /// 使用丢帧时间码（DF），仅 29.97 / 59.94 可用
+    pub drop_frame: bool,
+    pub width: u32,
+    pub height: u32,
+    /// FCPXML 版本
+    pub version: String,
+    /// 字幕 X 位置
+    pub position_x: i32,
+    /// 字幕 Y 位置
+    pub position_y: i32,
+    /// 标题样式
+    pub style: FcpxmlTitleStyle,
+    pub mode: FcpxmlMode,
+    /// 字幕格式（仅 captions 模式）："ITT"、"CEA608" 或 "SRT"
+    pub caption_format: String,
+    /// 字幕语言（仅 captions 模式），如 "en"、"zh-Hans"
+    pub language: String,
+}
+
+impl Default for FcpxmlExportOptions {
+    fn default() -> Self {
+        FcpxmlExportOptions {
+            fps: 25.0,
+            drop_frame: false,
+            width: 1920,
+            height: 1080,
+            version: "1.10".to_string(),
+            position_x: 0,
+            position_y: -415,
+            style: FcpxmlTitleStyle::default(),
+            mode: FcpxmlMode::Titles,
+            caption_format: "ITT".to_string(),
+            language: "en".to_string(),
+        }
+    }
+}
+
+/// 字幕格式对应的角色名
+const CAPTION_ROLES: [(&str, &str); 3] = [("ITT", "iTT"), ("CEA608", "CEA-608"), ("SRT", "SRT")];
+
+/// 字幕角色，如 `iTT?captionFormat=ITT.en`
+fn caption_role(caption_format: &str, language: &str) -> Result<String, String> {
+    let (format, role) = CAPTION_ROLES
+        .iter()
+        .find(|(format, _)| format.eq_ignore_ascii_case(caption_format))
+        .ok_or_else(|| format!("Unsupported caption format: {}", caption_format))?;
+    let valid_language = !language.is_empty()
+        && language.split('-').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()));
+    if !valid_language {
+        return Err(format!("Invalid caption language: {}", language));
+    }
+    Ok(format!("{}?captionFormat={}.{}", role, format, language))
+}
+
+/// 从字幕角色读取字幕格式和语言
+fn parse_caption_role(role: &str) -> Option<(String, String)> {
+    let (_, query) = role.split_once("?captionFormat=")?;
+    let (format, language) = query.split_once('.')?;
+    Some((format.to_string(), language.to_string()))
+}
+
+/// 自定标题效果（Custom.moti），位置和对齐参数的 key 对应该效果
+const CUSTOM_TITLE_EFFECT_UID: &str = ".../Titles.localized/Build In:Out.localized/Custom.localized/Custom.moti";
+
+/// FCPXML 标题样式，颜色为 `#RRGGBB` 或 `#RRGGBBAA`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FcpxmlTitleStyle {
+    /// 预设名称
+    pub name: String,
+    pub font: String,
+    /// 字重，如 Regular、Semibold、W6
+    pub font_face: String,
+    pub font_size: f64,
+    pub font_color: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub stroke_color: String,
+    /// 描边宽度，负值为外描边，0 为不描边
+    pub stroke_width: f64,
+    pub shadow_color: String,
+    pub shadow_distance: f64,
+    /// 阴影角度（度）
+    pub shadow_angle: f64,
+    /// 字距
+    pub kerning: f64,
+    /// 行距
+    pub line_spacing: f64,
+    /// left / center / right
+    pub alignment: String,
+    /// 标题效果 UID
+    pub effect_uid: String,
+    /// 标题效果名称
+    pub effect_name: String,
+}
+
+impl Default for FcpxmlTitleStyle {
+    fn default() -> Self {
+        FcpxmlTitleStyle {
+            name: "PingFang SC（中文）".to_string(),
+            font: "PingFang SC".to_string(),
+            font_face: "Semibold".to_string(),
+            font_size: 62.0,
+            font_color: "#FFFFFFFF".to_string(),
+            bold: true,
+            italic: false,
+            stroke_color: "#545454FF".to_string(),
+            stroke_width: -1.0,
+            shadow_color: "#000000BF".to_string(),
+            shadow_distance: 3.0,
+            shadow_angle: 315.0,
+            kerning: 1.24,
+            line_spacing: 0.0,
+            alignment: "center".to_string(),
+            effect_uid: CUSTOM_TITLE_EFFECT_UID.to_string(),
+            effect_name: "自定".to_string(),
+        }
+    }
+}
+
+/// 内置标题样式预设
+pub fn builtin_title_styles() -> Vec<FcpxmlTitleStyle> {
+    let default = FcpxmlTitleStyle::default();
+    vec![
+        default.clone(),
+        FcpxmlTitleStyle {
+            name: "Helvetica Neue（English）".to_string(),
+            font: "Helvetica Neue".to_string(),
+            font_face: "Bold".to_string(),
+            font_size: 60.0,
+            stroke_color: "#333333FF".to_string(),
+            kerning: 0.0,
+            ..default.clone()
+        },
+        FcpxmlTitleStyle {
+            name: "Hiragino Sans（日本語）".to_string(),
+            font: "Hiragino Sans".to_string(),
+            font_face: "W6".to_string(),
+            font_size: 58.0,
+            bold: false,
+            kerning: 0.5,
+            ..default.clone()
+        },
+        FcpxmlTitleStyle {
+            name: "Apple SD Gothic Neo（한국어）".to_string(),
+            font: "Apple SD Gothic Neo".to_string(),
+            font_face: "Bold".to_string(),
+            font_size: 58.0,
+            kerning: 0.0,
+            ..default
+        },
+    ]
+}
+
+/// `#RRGGBB[AA]` 转换为 FCPXML 的 `r g b a`（0-1）
+fn fcp_color(hex: &str) -> Result<String, String> {
+    let digits = hex.trim().trim_start_matches('#');
+    let invalid = || format!("Invalid color: {}", hex);
+    if !(digits.len() == 6 || digits.len() == 8) || !digits.is_ascii() {
+        return Err(invalid());
+    }
+    let mut channels = (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if channels.len() == 3 {
+        channels.push(255);
+    }
+    Ok(channels
+        .iter()
+        .map(|&c| ((c as f64 / 255.0 * 1e6).round() / 1e6).to_string())
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+impl FcpxmlTitleStyle {
+    /// 对齐参数的值和 text-style 的 alignment
+    fn alignment_values(&self) -> Result<(&'static str, &'static str), String> {
+        match self.alignment.as_str() {
+            "left" => Ok(("0 (左)", "left")),
+            "center" => Ok(("1 (居中)", "center")),
+            "right" => Ok(("2 (右)", "right")),
+            other => Err(format!("Invalid title alignment: {}", other)),
+        }
+    }
+
+    /// 生成 `<text-style>` 的属性
+    fn text_style_attributes(&self) -> Result<String, String> {
+        let (_, alignment) = self.alignment_values()?;
+        let mut attributes = format!(
+            r#"font="{}" fontSize="{}" fontFace="{}" fontColor="{}""#,
+            escape_xml(&self.font),
+            self.font_size,
+            escape_xml(&self.font_face),
+            fcp_color(&self.font_color)?
+        );
+        if self.bold {
+            attributes.push_str(r#" bold="1""#);
+        }
+        if self.italic {
+            attributes.push_str(r#" italic="1""#);
+        }
+        if self.stroke_width != 0.0 {
+            attributes.push_str(&format!(
+                r#" strokeColor="{}" strokeWidth="{}""#,
+                fcp_color(&self.stroke_color)?,
+                self.stroke_width
+            ));
+        }
+        attributes.push_str(&format!(
+            r#" shadowColor="{}" shadowOffset="{} {}" kerning="{}""#,
+            fcp_color(&self.shadow_color)?,
+            self.shadow_distance,
+            self.shadow_angle,
+            self.kerning
+        ));
+        if self.line_spacing != 0.0 {
+            attributes.push_str(&format!(r#" lineSpacing="{}""#, self.line_spacing));
+        }
+        attributes.push_str(&format!(r#" alignment="{}""#, alignment));
+        Ok(attributes)
+    }
+
+    /// 生成字幕的 `<text-style>` 属性：字幕只支持颜色、粗体和斜体，背景固定为黑色
+    fn caption_style_attributes(&self) -> Result<String, String> {
+        let mut attributes = format!(r#"fontColor="{}""#, fcp_color(&self.font_color)?);
+        if self.bold {
+            attributes.push_str(r#" bold="1""#);
+        }
+        if self.italic {
+            attributes.push_str(r#" italic="1""#);
+        }
+        attributes.push_str(r#" backgroundColor="0 0 0 1""#);
+        Ok(attributes)
+    }
+}
+
+/// FCP 内置格式名，非标准分辨率返回 None（FCP 按 width / height 识别为自定格式）
+fn format_name(width: u32, height: u32, rate: &FrameRate) -> Option<String> {
+    let size = match (width, height) {
+        (1280, 720) => "720p".to_string(),
+        (1920, 1080) => "1080p".to_string(),
+        (3840, 2160) | (4096, 2160) => format!("{}x{}p", width, height),
+        _ => return None,
+    };
+    Some(format!("FFVideoFormat{}{}", size, rate.format_suffix()))
+}
+
+/// 片段名取字幕前 20 个字符，先截断再转义，避免把实体引用截成两半
+fn clip_name(text: &str) -> String {
+    escape_xml(&text.replace('\n', " ").chars().take(20).collect::<String>())
+}
+
+/// 生成 FCPXML 文件内容
+pub fn build_fcpxml(entries: &[SubtitleEntry], options: &FcpxmlExportOptions) -> Result<String, String> {
+    let rate = FrameRate::from_fps(options.fps)?;
+    if options.drop_frame && !rate.supports_drop_frame() {
+        return Err(format!("丢帧时间码仅适用于 29.97 / 59.94 fps，当前帧率为 {}", options.fps));
+    }
+    if options.width == 0 || options.height == 0 {
+        return Err(format!("Invalid resolution: {}x{}", options.width, options.height));
+    }
+
+    // 空隙比最后一条字幕多留 100 帧
+    let total_frames = entries
+        .last()
+        .map(|e| rate.frames_from_ms(e.end_time.to_ms()))
+        .unwrap_or(0);
+    let gap_duration = rate.rational_time(total_frames + 100);
+    let name_attribute = format_name(options.width, options.height, &rate)
+        .map(|name| format!(" name=\"{}\"", name))
+        .unwrap_or_default();
+    let tc_format = if options.drop_frame { "DF" } else { "NDF" };
+    let style = &options.style;
+    let captions = options.mode == FcpxmlMode::Captions;
+    let (text_style, role) = if captions {
+        let role = caption_role(&options.caption_format, &options.language)?;
+        // CEA-608 字幕在 FCP 中同样受 32 列 / 4 行和字符集限制
+        if role.starts_with("CEA-608") {
+            scc::check_cea608(entries)?;
+        }
+        (style.caption_style_attributes()?, role)
+    } else {
+        (style.text_style_attributes()?, String::new())
+    };
+    let (alignment_param, _) = style.alignment_values()?;
+    // 字幕模式不需要标题效果
+    let effect = if captions {
+        String::new()
+    } else {
+        format!(
+            "\n    <effect id=\"r2\" name=\"{}\" uid=\"{}\"/>",
+            escape_xml(&style.effect_name),
+            escape_xml(&style.effect_uid)
+        )
+    };
+
+    let mut content = format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE fcpxml>
+
+<fcpxml version="{}">
+  <resources>
+    <format id="r1"{} frameDuration="{}" width="{}" height="{}" colorSpace="1-1-1 (Rec. 709)"/>{}
+  </resources>
+  <library>
+    <event name="Subtitles">
+      <project name="Subtitles">
+        <sequence duration="{}" format="r1" tcStart="0s" tcFormat="{}" audioLayout="stereo" audioRate="48k">
+          <spine>
+            <gap name="空隙" offset="0s" duration="{}">
+"#, escape_xml(&options.version), name_attribute, rate.frame_duration(), options.width, options.height,
+        effect, gap_duration, tc_format, gap_duration);
+
+    for (index, entry) in entries.iter().enumerate() {
+        let start_frames = rate.frames_from_ms(entry.start_time.to_ms());
+        let end_frames = rate.frames_from_ms(entry.end_time.to_ms());
+        let duration_frames = end_frames.saturating_sub(start_frames);
+
+        if captions {
+            // 字幕保留换行
+            let escaped_text = escape_xml(&entry.text);
+            content.push_str(&format!(
+                r#"<caption name="{}" lane="1" offset="{}" duration="{}" role="{}">
+<text placement="bottom">
+  <text-style ref="ts{}">{}</text-style>
+</text>
+<text-style-def id="ts{}">
+  <text-style {}/>
+</text-style-def>
+</caption>
+"#,
+                clip_name(&entry.text),
+                rate.rational_time(start_frames),
+                rate.rational_time(duration_frames),
+                escape_xml(&role),
+                index + 1,
+                escaped_text,
+                index + 1,
+                text_style
+            ));
+            continue;
+        }
+
+        // Escape XML special characters
+        let escaped_text = escape_xml(&entry.text).replace('\n', " ");
+
+        content.push_str(&format!(
+            r#"<title name="{} - {}" lane="1" offset="{}" ref="r2" duration="{}">
+<param name="位置" key="9999/10199/10201/1/100/101" value="{} {}"/>
+<param name="对齐" key="9999/10199/10201/2/354/1002961760/401" value="{}"/>
+<param name="Out Sequencing" key="9999/10199/10201/4/10233/201/202" value="0 (到)"/>
+
+<text>
+  <text-style ref="ts{}">{}</text-style>
+</text>
+<text-style-def id="ts{}">
+  <text-style {}/>
+</text-style-def>
+</title>
+"#,
+            clip_name(&entry.text),
+            escape_xml(&style.effect_name),
+            rate.rational_time(start_frames),
+            rate.rational_time(duration_frames),
+            options.position_x, options.position_y,
+            alignment_param,
+            index + 1,
+            escaped_text,
+            index + 1,
+            text_style
+        ));
+    }
+
+    content.push_str(r#"            </gap>
+          </spine>
+        </sequence>
+      </project>
+    </event>
+  </library>
+</fcpxml>"#);
+
+    Ok(content)
+}
+
+/// Export to FCPXML (Final Cut Pro XML)
+pub fn export_to_fcpxml(
+    file_path: &str,
+    entries: &[SubtitleEntry],
+    options: &FcpxmlExportOptions,
+) -> Result<(), String> {
+    let content = build_fcpxml(entries, options)?;
+
+    write_atomic(file_path, content).map_err(|e| format!("Failed to write FCPXML file: {}", e))
+}
+
+// ============ 导入 ============
+
+/// 解析有理数时间：`1001/30000s`、`5s`、`0s`
+fn parse_rational_time(value: &str) -> Result<f64, String> {
+    let invalid = || format!("Invalid FCPXML time: {}", value);
+    let number = value.trim().strip_suffix('s').ok_or_else(invalid)?;
+    match number.split_once('/') {
+        Some((num, den)) => {
+            let num = num.parse::<f64>().map_err(|_| invalid())?;
+            let den = den.parse::<f64>().map_err(|_| invalid())?;
+            if den == 0.0 {
+                return Err(invalid());
+            }
+            Ok(num / den)
+        }
+        None => number.parse::<f64>().map_err(|_| invalid()),
+    }
+}
+
+fn time_attribute(node: &roxmltree::Node, name: &str) -> Result<f64, String> {
+    node.attribute(name).map(parse_rational_time).unwrap_or(Ok(0.0))
+}
+
+/// FCPXML 的 `r g b a`（0-1）转换为 `#RRGGBBAA`
+fn hex_color(value: &str) -> Option<String> {
+    let channels: Vec<f64> = value.split_whitespace().map(|c| c.parse::<f64>().ok()).collect::<Option<_>>()?;
+    if !(channels.len() == 3 || channels.len() == 4) {
+        return None;
+    }
+    let mut hex = String::from("#");
+    for channel in channels.iter().chain(std::iter::once(&1.0)).take(4) {
+        hex.push_str(&format!("{:02X}", (channel.clamp(0.0, 1.0) * 255.0).round() as u8));
+    }
+    Some(hex)
+}
+
+/// 从 `<text-style>` 属性和标题效果读取样式，没有的属性沿用默认值
+fn read_title_style(text_style: &roxmltree::Node, effect: Option<&roxmltree::Node>) -> FcpxmlTitleStyle {
+    let mut style = FcpxmlTitleStyle { name: "导入的样式".to_string(), ..Default::default() };
+    let number = |name: &str| text_style.attribute(name).and_then(|v| v.trim().parse::<f64>().ok());
+    let color = |name: &str| text_style.attribute(name).and_then(hex_color);
+
+    if let Some(font) = text_style.attribute("font") {
+        style.font = font.to_string();
+    }
+    if let Some(face) = text_style.attribute("fontFace") {
+        style.font_face = face.to_string();
+    }
+    style.font_size = number("fontSize").unwrap_or(style.font_size);
+    style.font_color = color("fontColor").unwrap_or(style.font_color);
+    style.bold = text_style.attribute("bold") == Some("1");
+    style.italic = text_style.attribute("italic") == Some("1");
+    style.stroke_width = number("strokeWidth").unwrap_or(0.0);
+    style.stroke_color = color("strokeColor").unwrap_or(style.stroke_color);
+    style.shadow_color = color("shadowColor").unwrap_or(style.shadow_color);
+    if let Some((distance, angle)) = text_style.attribute("shadowOffset").and_then(|v| v.split_once(' ')) {
+        style.shadow_distance = distance.trim().parse().unwrap_or(style.shadow_distance);
+        style.shadow_angle = angle.trim().parse().unwrap_or(style.shadow_angle);
+    }
+    style.kerning = number("kerning").unwrap_or(0.0);
+    style.line_spacing = number("lineSpacing").unwrap_or(0.0);
+    if let Some(alignment @ ("left" | "center" | "right")) = text_style.attribute("alignment") {
+        style.alignment = alignment.to_string();
+    }
+    if let Some(effect) = effect {
+        style.effect_uid = effect.attribute("uid").unwrap_or_default().to_string();
+        style.effect_name = effect.attribute("name").unwrap_or_default().to_string();
+    }
+    style
+}
+
+/// 标题的文本：各 `<text>` 之间换行
+fn title_text(node: &roxmltree::Node) -> String {
+    node.children()
+        .filter(|c| c.has_tag_name("text"))
+        .map(|text| text.descendants().filter(|d| d.is_text()).filter_map(|d| d.text()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// 标题中的位置参数（自定标题效果的 key，或名称为 Position / 位置）
+fn title_position(node: &roxmltree::Node) -> Option<(i32, i32)> {
+    let param = node.children().find(|c| {
+        c.has_tag_name("param")
+            && (c.attribute("key").is_some_and(|k| k.ends_with("/1/100/101"))
+                || matches!(c.attribute("name"), Some("Position" | "位置")))
+    })?;
+    let mut values = param.attribute("value")?.split_whitespace().map(|v| v.parse::<f64>().ok());
+    Some((values.next()??.round() as i32, values.next()??.round() as i32))
+}
+
+/// 导入过程中的状态
+struct ImportContext<'a, 'input> {
+    effects: std::collections::HashMap<&'a str, roxmltree::Node<'a, 'input>>,
+    text_styles: std::collections::HashMap<&'a str, roxmltree::Node<'a, 'input>>,
+    /// (开始秒, 结束秒, 文本)
+    items: Vec<(f64, f64, String)>,
+    style: Option<FcpxmlTitleStyle>,
+    position: Option<(i32, i32)>,
+    /// 第一个 caption 的格式和语言
+    caption: Option<(String, String)>,
+}
+
+impl<'a, 'input> ImportContext<'a, 'input> {
+    /// parent_absolute: 父元素在时间线上的开始时间；parent_start: 父元素自身时间轴的起点（start 属性）
+    fn walk(&mut self, node: roxmltree::Node<'a, 'input>, parent_absolute: f64, parent_start: f64) -> Result<(), String> {
+        for child in node.children().filter(|c| c.is_element()) {
+            if child.attribute("enabled") == Some("0") {
+                continue;
+            }
+            let absolute = parent_absolute + time_attribute(&child, "offset")? - parent_start;
+            if matches!(child.tag_name().name(), "title" | "caption") {
+                let text = title_text(&child);
+                if !text.is_empty() {
+                    let duration = time_attribute(&child, "duration")?;
+                    self.items.push((absolute, absolute + duration, text));
+                }
+                if child.has_tag_name("title") && self.style.is_none() {
+                    self.read_style(&child);
+                }
+                if child.has_tag_name("caption") && self.caption.is_none() {
+                    self.caption = child.attribute("role").and_then(parse_caption_role);
+                }
+            }
+            self.walk(child, absolute, time_attribute(&child, "start")?)?;
+        }
+        Ok(())
+    }
+
+    fn read_style(&mut self, title: &roxmltree::Node) {
+        let Some(text_style) = title
+            .descendants()
+            .filter(|d| d.has_tag_name("text-style"))
+            .find_map(|d| d.attribute("ref"))
+            .and_then(|id| self.text_styles.get(id))
+        else {
+            return;
+        };
+        let effect = title.attribute("ref").and_then(|id| self.effects.get(id));
+        self.style = Some(read_title_style(text_style, effect));
+        self.position = title_position(title);
+    }
+}
+
+/// 解析 FCPXML，返回导出选项（帧率、分辨率、时间码格式、位置、第一个标题的样式或字幕角色）和字幕
+pub fn parse_fcpxml(content: &str) -> Result<(FcpxmlExportOptions, Vec<SubtitleEntry>), String> {
+    let options = roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() };
+    let doc = roxmltree::Document::parse_with_options(content, options)
+        .map_err(|e| format!("Invalid FCPXML: {}", e))?;
+    let root = doc.root_element();
+    if !root.has_tag_name("fcpxml") {
+        return Err("Root element is not <fcpxml>".to_string());
+    }
+    let sequence = root
+        .descendants()
+        .find(|n| n.has_tag_name("sequence"))
+        .ok_or_else(|| "FCPXML 中没有 sequence".to_string())?;
+
+    let mut options = FcpxmlExportOptions {
+        version: root.attribute("version").unwrap_or("1.10").to_string(),
+        drop_frame: sequence.attribute("tcFormat") == Some("DF"),
+        ..Default::default()
+    };
+    let format = sequence
+        .attribute("format")
+        .and_then(|id| root.descendants().find(|n| n.has_tag_name("format") && n.attribute("id") == Some(id)));
+    if let Some(format) = format {
+        if let Some(frame_duration) = format.attribute("frameDuration") {
+            let seconds = parse_rational_time(frame_duration)?;
+            if seconds > 0.0 {
+                options.fps = FrameRate::from_fps(1.0 / seconds)?.fps();
+            }
+        }
+        options.width = format.attribute("width").and_then(|v| v.parse().ok()).unwrap_or(options.width);
+        options.height = format.attribute("height").and_then(|v| v.parse().ok()).unwrap_or(options.height);
+    }
+
+    let mut context = ImportContext {
+        effects: root
+            .descendants()
+            .filter(|n| n.has_tag_name("effect"))
+            .filter_map(|n| Some((n.attribute("id")?, n)))
+            .collect(),
+        text_styles: root
+            .descendants()
+            .filter(|n| n.has_tag_name("text-style-def"))
+            .filter_map(|n| Some((n.attribute("id")?, n.children().find(|c| c.has_tag_name("text-style"))?)))
+            .collect(),
+        items: Vec::new(),
+        style: None,
+        position: None,
+        caption: None,
+    };
+    // 时间线从 tcStart 开始
+    context.walk(sequence, 0.0, time_attribute(&sequence, "tcStart")?)?;
+
+    // 只有 caption 没有 title 时再次导出为字幕
+    match (context.style, context.caption) {
+        (Some(style), _) => options.style = style,
+        (None, Some((caption_format, language))) => {
+            options.mode = FcpxmlMode::Captions;
+            options.caption_format = caption_format;
+            options.language = language;
+        }
+        (None, None) => {}
+    }
+    if let Some((x, y)) = context.position {
+        options.position_x = x;
+        options.position_y = y;
+    }
+
+    let mut items = context.items;
+    items.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let to_timestamp = |seconds: f64| TimeStamp::from_ms((seconds.max(0.0) * 1000.0).round() as u64);
+    let entries = items
+        .into_iter()
+        .enumerate()
+        .map(|(index, (start, end, text))| SubtitleEntry {
+            id: index as u32 + 1,
+            start_time: to_timestamp(start),
+            end_time: to_timestamp(end),
+            text,
+            ..Default::default()
+        })
+        .collect();
+
+    Ok((options, entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(start_ms: u64, end_ms: u64, text: &str) -> SubtitleEntry {
+        SubtitleEntry {
+            id: 1,
+            start_time: TimeStamp::from_ms(start_ms),
+            end_time: TimeStamp::from_ms(end_ms),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_frame_rate_from_fps() {
+        assert_eq!(FrameRate::from_fps(23.976).unwrap(), FrameRate { numerator: 24_000, denominator: 1001 });
+        assert_eq!(FrameRate::from_fps(29.97).unwrap(), FrameRate { numerator: 30_000, denominator: 1001 });
+        assert_eq!(FrameRate::from_fps(59.94).unwrap(), FrameRate { numerator: 60_000, denominator: 1001 });
+        assert_eq!(FrameRate::from_fps(50.0).unwrap(), FrameRate { numerator: 50, denominator: 1 });
+        assert_eq!(FrameRate::from_fps(12.5).unwrap(), FrameRate { numerator: 25, denominator: 2 });
+        assert!(FrameRate::from_fps(0.0).is_err());
+
+        let ntsc = FrameRate::from_fps(29.97).unwrap();
+        assert_eq!(ntsc.frame_duration(), "1001/30000s");
+        assert_eq!(ntsc.rational_time(30), "30030/30000s");
+        // 一小时在 29.97 下是 107892 帧（不是 108000 帧）
+        assert_eq!(ntsc.frames_from_ms(3_600_000), 107_892);
+        assert_eq!(FrameRate::from_fps(25.0).unwrap().rational_time(50), "5000/2500s");
+    }
+
+    #[test]
+    fn test_build_fcpxml_ntsc() {
+        let entries = vec![entry(1000, 3000, "Hello & <world>")];
+        let options = FcpxmlExportOptions {
+            fps: 23.976,
+            width: 3840,
+            height: 2160,
+            ..Default::default()
+        };
+        let content = build_fcpxml(&entries, &options).unwrap();
+        assert!(content.contains(r#"<fcpxml version="1.10">"#));
+        assert!(content.contains(r#"name="FFVideoFormat3840x2160p2398" frameDuration="1001/24000s" width="3840" height="2160""#));
+        assert!(content.contains(r#"offset="24024/24000s" ref="r2" duration="48048/24000s""#));
+        assert!(content.contains("Hello &amp; &lt;world&gt;"));
+
+        let drop_frame = FcpxmlExportOptions { drop_frame: true, ..options.clone() };
+        assert!(build_fcpxml(&entries, &drop_frame).is_err());
+        let drop_frame = FcpxmlExportOptions { fps: 29.97, ..drop_frame };
+        assert!(build_fcpxml(&entries, &drop_frame).unwrap().contains(r#"tcFormat="DF""#));
+    }
+
+    #[test]
+    fn test_title_style() {
+        let entries = vec![entry(0, 1000, "こんにちは")];
+        let content = build_fcpxml(&entries, &FcpxmlExportOptions::default()).unwrap();
+        assert!(content.contains(r#"<text-style font="PingFang SC" fontSize="62" fontFace="Semibold" fontColor="1 1 1 1" bold="1" strokeColor="0.329412 0.329412 0.329412 1" strokeWidth="-1" shadowColor="0 0 0 0.74902" shadowOffset="3 315" kerning="1.24" alignment="center"/>"#));
+
+        let japanese = builtin_title_styles().into_iter().find(|s| s.font == "Hiragino Sans").unwrap();
+        let style = FcpxmlTitleStyle {
+            alignment: "left".to_string(),
+            line_spacing: -5.0,
+            effect_uid: "uid/with\"quote".to_string(),
+            ..japanese
+        };
+        let options = FcpxmlExportOptions { style, ..Default::default() };
+        let content = build_fcpxml(&entries, &options).unwrap();
+        assert!(content.contains(r#"font="Hiragino Sans" fontSize="58" fontFace="W6""#));
+        assert!(content.contains(r#"lineSpacing="-5" alignment="left""#));
+        assert!(content.contains(r#"value="0 (左)""#));
+        assert!(content.contains(r#"uid="uid/with&quot;quote""#));
+
+        let invalid = FcpxmlExportOptions {
+            style: FcpxmlTitleStyle { font_color: "white".to_string(), ..Default::default() },
+            ..Default::default()
+        };
+        assert!(build_fcpxml(&entries, &invalid).is_err());
+    }
+
+    #[test]
+    fn test_fcpxml_round_trip() {
+        let entries = vec![entry(1000, 3000, "第一句"), entry(3500, 5000, "Second & third")];
+        let options = FcpxmlExportOptions {
+            fps: 29.97,
+            drop_frame: true,
+            width: 3840,
+            height: 2160,
+            position_x: 10,
+            position_y: -300,
+            style: builtin_title_styles().remove(1),
+            ..Default::default()
+        };
+        let content = build_fcpxml(&entries, &options).unwrap();
+        let (imported, parsed) = parse_fcpxml(&content).unwrap();
+
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].text, "Second & third");
+        for (original, decoded) in entries.iter().zip(&parsed) {
+            assert!(original.start_time.to_ms().abs_diff(decoded.start_time.to_ms()) <= 17);
+            assert!(original.end_time.to_ms().abs_diff(decoded.end_time.to_ms()) <= 34);
+        }
+        assert_eq!(imported.fps, 30_000.0 / 1001.0);
+        assert!(imported.drop_frame);
+        assert_eq!((imported.width, imported.height), (3840, 2160));
+        assert_eq!((imported.position_x, imported.position_y), (10, -300));
+        assert_eq!(imported.style.font, "Helvetica Neue");
+        assert_eq!(imported.style.stroke_color, "#333333FF");
+        assert_eq!(imported.style.effect_uid, CUSTOM_TITLE_EFFECT_UID);
+    }
+
+    #[test]
+    fn test_parse_fcpxml_nested_timing() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE fcpxml>
+<fcpxml version="1.11">
+  <resources>
+    <format id="r1" frameDuration="100/2500s" width="1920" height="1080"/>
+    <effect id="r2" name="Basic Title" uid=".../Basic Title.moti"/>
+  </resources>
+  <library><event><project name="P">
+    <sequence format="r1" tcStart="3600s" tcFormat="NDF">
+      <spine>
+        <asset-clip offset="3600s" start="10s" duration="20s">
+          <title ref="r2" lane="1" offset="12s" duration="2s">
+            <text><text-style ref="ts1">Line one</text-style></text>
+            <text-style-def id="ts1"><text-style font="Avenir" fontSize="48" fontColor="1 0.5 0 1" alignment="left"/></text-style-def>
+          </title>
+          <caption lane="2" offset="15s" duration="1s" role="iTT?captionFormat=ITT.en">
+            <text><text-style>Caption</text-style></text>
+          </caption>
+          <title ref="r2" lane="1" offset="17s" duration="1s" enabled="0">
+            <text><text-style>Hidden</text-style></text>
+          </title>
+        </asset-clip>
+        <gap offset="3620s" start="3600s" duration="5s">
+          <title ref="r2" lane="1" offset="3601s" duration="1s">
+            <text><text-style>Gap title</text-style></text>
+          </title>
+        </gap>
+      </spine>
+    </sequence>
+  </project></event></library>
+</fcpxml>"#;
+        let (options, entries) = parse_fcpxml(content).unwrap();
+        assert_eq!(options.fps, 25.0);
+        assert_eq!(options.style.font, "Avenir");
+        assert_eq!(options.style.font_color, "#FF8000FF");
+        assert_eq!(options.style.alignment, "left");
+        assert_eq!(options.style.effect_name, "Basic Title");
+
+        let texts: Vec<_> = entries.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, ["Line one", "Caption", "Gap title"]);
+        assert_eq!(entries[0].start_time.to_ms(), 2000);
+        assert_eq!(entries[0].end_time.to_ms(), 4000);
+        assert_eq!(entries[1].start_time.to_ms(), 5000);
+        assert_eq!(entries[2].start_time.to_ms(), 21_000);
+    }
+
+    #[test]
+    fn test_caption_mode() {
+        let entries = vec![entry(1000, 3000, "Line one\nLine two")];
+        let options = FcpxmlExportOptions {
+            mode: FcpxmlMode::Captions,
+            language: "zh-Hans".to_string(),
+            ..Default::default()
+        };
+        let content = build_fcpxml(&entries, &options).unwrap();
+        assert!(content.contains(r#"role="iTT?captionFormat=ITT.zh-Hans""#));
+        assert!(content.contains("Line one\nLine two</text-style>"));
+        assert!(!content.contains("<effect"));
+        assert!(!content.contains("<title"));
+
+        let (imported, parsed) = parse_fcpxml(&content).unwrap();
+        assert_eq!(parsed[0].text, "Line one\nLine two");
+        assert_eq!(imported.mode, FcpxmlMode::Captions);
+        assert_eq!(imported.caption_format, "ITT");
+        assert_eq!(imported.language, "zh-Hans");
+
+        let cea608 = FcpxmlExportOptions { caption_format: "CEA608".to_string(), language: "en".to_string(), ..options.clone() };
+        assert!(build_fcpxml(&entries, &cea608).unwrap().contains(r#"role="CEA-608?captionFormat=CEA608.en""#));
+        let too_long = vec![entry(0, 1000, "This caption line is far too long for CEA-608")];
+        assert!(build_fcpxml(&too_long, &cea608).unwrap_err().contains("字幕 1"));
+
+        let invalid = FcpxmlExportOptions { language: "en us".to_string(), ..options };
+        assert!(build_fcpxml(&entries, &invalid).is_err());
+    }
+
+    #[test]
+    fn test_clip_name_truncated_before_escaping() {
+        let entries = vec![entry(0, 1000, "Tom and Jerry are &&& back")];
+        for mode in [FcpxmlMode::Titles, FcpxmlMode::Captions] {
+            let options = FcpxmlExportOptions { mode, ..Default::default() };
+            let content = build_fcpxml(&entries, &options).unwrap();
+            assert!(content.contains(r#"name="Tom and Jerry are &amp;"#), "{}", content);
+            let (_, parsed) = parse_fcpxml(&content).unwrap();
+            assert_eq!(parsed[0].text, "Tom and Jerry are &&& back");
+        }
+    }
+}
@@ -0,0 +1,294 @@
+//! 基于帧或简单时间码的字幕格式：MicroDVD (.sub)、YouTube (.sbv)、Spruce STL (.stl)
+//!
+//! MicroDVD 与 Spruce STL 的时间以帧表示，需要帧率才能换算为时间。文件中没有声明帧率
+//! 且调用方也没有提供时，返回以 `FPS_REQUIRED_ERROR` 开头的错误，由前端询问帧率后重试。
+
+use crate::file_safety::write_atomic;
+use super::timecode::FrameRate;
+use super::{SubtitleEntry, TimeStamp};
+
+/// 需要帧率时返回的错误前缀
+pub const FPS_REQUIRED_ERROR: &str = "FPS_REQUIRED";
+
+fn fps_required(format_name: &str) -> String {
+    format!("{}: {} 文件未声明帧率，请指定帧率", FPS_REQUIRED_ERROR, format_name)
+}
+
+fn new_entry(index: usize, start_time: TimeStamp, end_time: TimeStamp, text: String) -> SubtitleEntry {
+    SubtitleEntry {
+        id: index as u32 + 1,
+        start_time,
+        end_time,
+        text,
+        ..Default::default()
+    }
+}
+
+// ============ MicroDVD ============
+
+/// 解析 `{start}{end}text` 行，返回 (起始帧, 结束帧, 文本)
+fn parse_microdvd_line(line: &str) -> Option<(u64, u64, &str)> {
+    let rest = line.strip_prefix('{')?;
+    let (start, rest) = rest.split_once('}')?;
+    let rest = rest.strip_prefix('{')?;
+    let (end, text) = rest.split_once('}')?;
+    Some((start.trim().parse().ok()?, end.trim().parse().ok()?, text))
+}
+
+/// 解析 MicroDVD 内容，返回字幕和实际使用的帧率
+/// 首行 `{1}{1}23.976` 形式的帧率声明优先于参数 fps
+pub fn parse_microdvd(content: &str, fps: Option<f64>) -> Result<(Vec<SubtitleEntry>, f64), String> {
+    let content = super::normalize_line_endings(content);
+    let mut lines = content.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).peekable();
+
+    let declared = lines
+        .peek()
+        .and_then(|l| parse_microdvd_line(l))
+        .filter(|(start, end, _)| *start <= 1 && *end <= 1)
+        .and_then(|(_, _, text)| text.trim().parse::<f64>().ok());
+    if declared.is_some() {
+        lines.next();
+    }
+    let fps = declared.or(fps).ok_or_else(|| fps_required("MicroDVD"))?;
+    let rate = FrameRate::from_fps(fps)?;
+
+    let mut entries = Vec::new();
+    for (line_no, line) in lines.enumerate() {
+        let (start, end, text) = parse_microdvd_line(line)
+            .ok_or_else(|| format!("Invalid MicroDVD line {}: {}", line_no + 1, line))?;
+        entries.push(new_entry(
+            entries.len(),
+            TimeStamp::from_frames(start, rate),
+            TimeStamp::from_frames(end, rate),
+            text.replace('|', "\n"),
+        ));
+    }
+
+    Ok((entries, fps))
+}
+
+/// 生成 MicroDVD 内容，首行写入帧率声明
+pub fn build_microdvd(entries: &[SubtitleEntry], fps: f64) -> Result<String, String> {
+    let rate = FrameRate::from_fps(fps)?;
+    let mut content = format!("{{1}}{{1}}{}\n", fps);
+    for entry in entries {
+        content.push_str(&format!(
+            "{{{}}}{{{}}}{}\n",
+            entry.start_time.to_frames(rate),
+            entry.end_time.to_frames(rate),
+            entry.text.replace('\n', "|")
+        ));
+    }
+    Ok(content)
+}
+
+/// Export to MicroDVD (.sub)
+pub fn export_to_microdvd(file_path: &str, entries: &[SubtitleEntry], fps: f64) -> Result<(), String> {
+    let content = build_microdvd(entries, fps)?;
+
+    write_atomic(file_path, content)
+        .map_err(|e| format!("Failed to write MicroDVD file: {}", e))?;
+
+    println!("Successfully exported {} subtitles to MicroDVD ({}fps): {}", entries.len(), fps, file_path);
+    Ok(())
+}
+
+// ============ YouTube SBV ============
+
+/// 解析 SBV 时间：H:MM:SS.mmm
+fn parse_sbv_time(s: &str) -> Result<TimeStamp, String> {
+    let invalid = || format!("Invalid SBV timestamp: {}", s);
+    let (clock, ms) = s.trim().split_once('.').ok_or_else(invalid)?;
+    let parts: Vec<u64> = clock
+        .split(':')
+        .map(|p| p.parse::<u64>().map_err(|_| invalid()))
+        .collect::<Result<_, _>>()?;
+    let [h, m, sec] = parts.as_slice() else {
+        return Err(invalid());
+    };
+    let ms = format!("{:0<3}", ms).get(..3).ok_or_else(invalid)?.parse::<u64>().map_err(|_| invalid())?;
+    Ok(TimeStamp::from_ms((h * 3600 + m * 60 + sec) * 1000 + ms))
+}
+
+fn format_sbv_time(ts: &TimeStamp) -> String {
+    format!("{}:{:02}:{:02}.{:03}", ts.hours, ts.minutes, ts.seconds, ts.milliseconds)
+}
+
+/// 解析 YouTube SBV 内容
+pub fn parse_sbv(content: &str) -> Result<Vec<SubtitleEntry>, String> {
+    let content = super::normalize_line_endings(content);
+    let mut entries = Vec::new();
+
+    for block in content.split("\n\n").map(|b| b.trim()).filter(|b| !b.is_empty()) {
+        let mut lines = block.lines();
+        let timing = lines.next().unwrap_or("");
+        let (start, end) = timing
+            .split_once(',')
+            .ok_or_else(|| format!("Invalid SBV timing line: {}", timing))?;
+        entries.push(new_entry(
+            entries.len(),
+            parse_sbv_time(start)?,
+            parse_sbv_time(end)?,
+            lines.collect::<Vec<_>>().join("\n"),
+        ));
+    }
+
+    Ok(entries)
+}
+
+/// 生成 YouTube SBV 内容
+pub fn build_sbv(entries: &[SubtitleEntry]) -> String {
+    entries
+        .iter()
+        .map(|e| format!("{},{}\n{}\n", format_sbv_time(&e.start_time), format_sbv_time(&e.end_time), e.text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Export to YouTube SBV (.sbv)
+pub fn export_to_sbv(file_path: &str, entries: &[SubtitleEntry]) -> Result<(), String> {
+    write_atomic(file_path, build_sbv(entries))
+        .map_err(|e| format!("Failed to write SBV file: {}", e))?;
+
+    println!("Successfully exported {} subtitles to SBV: {}", entries.len(), file_path);
+    Ok(())
+}
+
+// ============ Spruce STL ============
+
+/// 解析 Spruce STL 时间码 HH:MM:SS:FF（NTSC 帧率按整数帧率计数，`;` 分隔时为丢帧时间码）
+fn parse_spruce_timecode(s: &str, rate: FrameRate) -> Result<TimeStamp, String> {
+    Ok(TimeStamp::from_frames(rate.parse_timecode(s)?, rate))
+}
+
+fn format_spruce_timecode(ts: &TimeStamp, rate: FrameRate) -> Result<String, String> {
+    rate.format_timecode(ts.to_frames(rate), false)
+}
+
+/// 解析 Spruce STL 内容（`$` 开头为设置指令，`//` 开头为注释，`|` 为换行）
+pub fn parse_spruce_stl(content: &str, fps: Option<f64>) -> Result<Vec<SubtitleEntry>, String> {
+    let rate = FrameRate::from_fps(fps.ok_or_else(|| fps_required("Spruce STL"))?)?;
+    let content = super::normalize_line_endings(content);
+    let mut entries = Vec::new();
+
+    for line in content.lines().map(|l| l.trim()) {
+        if line.is_empty() || line.starts_with('$') || line.starts_with("//") {
+            continue;
+        }
+        let mut fields = line.splitn(3, ',');
+        let (Some(start), Some(end), Some(text)) = (fields.next(), fields.next(), fields.next()) else {
+            return Err(format!("Invalid Spruce STL line: {}", line));
+        };
+        entries.push(new_entry(
+            entries.len(),
+            parse_spruce_timecode(start, rate)?,
+            parse_spruce_timecode(end, rate)?,
+            text.trim().replace('|', "\n"),
+        ));
+    }
+
+    Ok(entries)
+}
+
+/// 生成 Spruce STL 内容
+pub fn build_spruce_stl(entries: &[SubtitleEntry], fps: f64) -> Result<String, String> {
+    let rate = FrameRate::from_fps(fps)?;
+    let mut content = String::from(
+        "$FontName = Arial\n$FontSize = 42\n$HorzAlign = Center\n$VertAlign = Bottom\n$TapeOffset = FALSE\n\n",
+    );
+    for entry in entries {
+        content.push_str(&format!(
+            "{} , {} , {}\n",
+            format_spruce_timecode(&entry.start_time, rate)?,
+            format_spruce_timecode(&entry.end_time, rate)?,
+            entry.text.replace('\n', "|")
+        ));
+    }
+    Ok(content)
+}
+
+/// Export to Spruce STL (.stl)
+pub fn export_to_spruce_stl(file_path: &str, entries: &[SubtitleEntry], fps: f64) -> Result<(), String> {
+    let content = build_spruce_stl(entries, fps)?;
+
+    write_atomic(file_path, content)
+        .map_err(|e| format!("Failed to write Spruce STL file: {}", e))?;
+
+    println!("Successfully exported {} subtitles to Spruce STL ({}fps): {}", entries.len(), fps, file_path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(start_ms: u64, end_ms: u64, text: &str) -> SubtitleEntry {
+        new_entry(0, TimeStamp::from_ms(start_ms), TimeStamp::from_ms(end_ms), text.to_string())
+    }
+
+    #[test]
+    fn test_microdvd_declared_fps() {
+        let (entries, fps) = parse_microdvd("{1}{1}25\n{25}{50}Hello|World\n{75}{100}{y:i}Italic", None).unwrap();
+        assert_eq!(fps, 25.0);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].start_time.to_ms(), 1000);
+        assert_eq!(entries[0].end_time.to_ms(), 2000);
+        assert_eq!(entries[0].text, "Hello\nWorld");
+        assert_eq!(entries[1].text, "{y:i}Italic");
+    }
+
+    #[test]
+    fn test_microdvd_requires_fps() {
+        let err = parse_microdvd("{24}{48}No header", None).unwrap_err();
+        assert!(err.starts_with(FPS_REQUIRED_ERROR));
+
+        let (entries, _) = parse_microdvd("{24}{48}No header", Some(23.976)).unwrap();
+        assert_eq!(entries[0].start_time.to_ms(), 1001);
+    }
+
+    #[test]
+    fn test_microdvd_round_trip() {
+        let entries = vec![entry(1000, 2500, "One\nTwo"), entry(3000, 4000, "Three")];
+        let (parsed, fps) = parse_microdvd(&build_microdvd(&entries, 25.0).unwrap(), None).unwrap();
+        assert_eq!(fps, 25.0);
+        assert_eq!(parsed[0].text, "One\nTwo");
+        assert_eq!(parsed[0].end_time.to_ms(), 2520);
+        assert!(build_microdvd(&entries, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_sbv_round_trip() {
+        let content = "0:00:01.000,0:00:04.500\nFirst line\nsecond line\n\n0:01:05.250,0:01:06.000\nNext\n";
+        let entries = parse_sbv(content).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].end_time.to_ms(), 4500);
+        assert_eq!(entries[1].start_time.to_ms(), 65250);
+        assert_eq!(build_sbv(&entries), content);
+    }
+
+    #[test]
+    fn test_spruce_stl() {
+        let content = "$FontName = Arial\n//comment\n00:00:01:12 , 00:00:03:00 , Hello|world\n";
+        assert!(parse_spruce_stl(content, None).unwrap_err().starts_with(FPS_REQUIRED_ERROR));
+
+        let entries = parse_spruce_stl(content, Some(25.0)).unwrap();
+        assert_eq!(entries[0].start_time.to_ms(), 1480);
+        assert_eq!(entries[0].text, "Hello\nworld");
+
+        let output = build_spruce_stl(&entries, 25.0).unwrap();
+        assert!(output.contains("00:00:01:12 , 00:00:03:00 , Hello|world"));
+    }
+
+    #[test]
+    fn test_spruce_timecode_carry() {
+        let pal = FrameRate::from_fps(25.0).unwrap();
+        assert_eq!(format_spruce_timecode(&TimeStamp::from_ms(1999), pal).unwrap(), "00:00:02:00");
+
+        // 29.97 按 30 帧计数，与 SMPTE 非丢帧时间码一致
+        let ntsc = FrameRate::from_fps(29.97).unwrap();
+        let hour = parse_spruce_timecode("01:00:00:00", ntsc).unwrap();
+        assert_eq!(hour.to_ms(), 3_603_600);
+        assert_eq!(format_spruce_timecode(&hour, ntsc).unwrap(), "01:00:00:00");
+        assert!(parse_spruce_timecode("00:00:01:30", ntsc).is_err());
+    }
+}
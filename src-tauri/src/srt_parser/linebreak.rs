@@ -0,0 +1,357 @@
+//! 字幕自动换行：按显示宽度把文本重新分成不超过 N 行
+//!
+//! CJK 字符和全角标点按 2 个宽度计，其余字符按 1 个宽度计，样式标签不计宽度。
+//! 优先在句末标点、句中标点和空格处断行，CJK 字符之间也可断行，但不会拆开英文单词和数字；
+//! 闭合标点不会出现在行首，开括号不会留在行尾。多行时尽量等宽，且上行不宽于下行（正金字塔）。
+
+use serde::{Deserialize, Serialize};
+
+use super::{is_cjk, SubtitleEntry};
+
+/// 换行选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LineBreakOptions {
+    /// 每行最大显示宽度（CJK 字符按 2 计）
+    pub max_width: u32,
+    pub max_lines: u32,
+}
+
+impl Default for LineBreakOptions {
+    fn default() -> Self {
+        LineBreakOptions { max_width: 42, max_lines: 2 }
+    }
+}
+
+const SENTENCE_END: &str = "。！？!?.…";
+const CLAUSE_END: &str = "，、；：,;:—";
+/// 不能出现在行首的标点，附在前一个片段后
+const CLOSING: &str = "，。、！？：；）」』】》〉”’,.!?:;)]}…%";
+/// 不能留在行尾的标点，附在后一个片段前
+const OPENING: &str = "（「『【《〈“‘([{";
+
+/// 不可再拆分的片段：一个 CJK 字符或一个单词 / 数字，连同附着的标点和标签
+#[derive(Debug, Default)]
+struct Atom {
+    text: String,
+    width: u32,
+    /// 与前一个片段之间有空格
+    space_before: bool,
+    /// 最后一个可见字符
+    last: Option<char>,
+}
+
+fn char_width(c: char) -> u32 {
+    if is_cjk(c) || matches!(c, '\u{3000}'..='\u{303F}' | '\u{FF00}'..='\u{FFEF}' | '“' | '”' | '‘' | '’' | '…' | '—') {
+        2
+    } else {
+        1
+    }
+}
+
+fn tokenize(text: &str) -> Vec<Atom> {
+    let mut atoms: Vec<Atom> = Vec::new();
+    // 正在读取的单词（非 CJK 连续字符）
+    let mut word: Option<Atom> = None;
+    // 等待附到下一个片段前的开括号和标签
+    let mut prefix = Atom::default();
+    let mut pending_space = false;
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            atoms.extend(word.take());
+            pending_space = !atoms.is_empty() || !prefix.text.is_empty();
+            continue;
+        }
+
+        if c == '<' || c == '{' {
+            let close = if c == '<' { '>' } else { '}' };
+            let mut tag = c.to_string();
+            for t in chars.by_ref() {
+                tag.push(t);
+                if t == close {
+                    break;
+                }
+            }
+            // 紧跟在文字后的标签（如 </i>）附在前面，否则附在后面
+            if let Some(w) = word.as_mut() {
+                w.text.push_str(&tag);
+            } else if let (Some(last), false, true) = (atoms.last_mut(), pending_space, prefix.text.is_empty()) {
+                last.text.push_str(&tag);
+            } else {
+                prefix.text.push_str(&tag);
+            }
+            continue;
+        }
+
+        let width = char_width(c);
+        if CLOSING.contains(c) && prefix.text.is_empty() && (word.is_some() || !atoms.is_empty()) {
+            let target = match word.as_mut() {
+                Some(w) => w,
+                None => atoms.last_mut().unwrap(),
+            };
+            target.text.push(c);
+            target.width += width;
+            target.last = Some(c);
+            continue;
+        }
+
+        if OPENING.contains(c) {
+            atoms.extend(word.take());
+            if prefix.text.is_empty() {
+                prefix.space_before = pending_space;
+                pending_space = false;
+            }
+            prefix.text.push(c);
+            prefix.width += width;
+            continue;
+        }
+
+        if width == 2 {
+            atoms.extend(word.take());
+            let mut atom = std::mem::take(&mut prefix);
+            atom.space_before |= pending_space;
+            atom.text.push(c);
+            atom.width += width;
+            atom.last = Some(c);
+            atoms.push(atom);
+            pending_space = false;
+            continue;
+        }
+
+        let w = word.get_or_insert_with(|| {
+            let mut atom = std::mem::take(&mut prefix);
+            atom.space_before |= pending_space;
+            atom
+        });
+        pending_space = false;
+        w.text.push(c);
+        w.width += width;
+        w.last = Some(c);
+    }
+    atoms.extend(word);
+    if !prefix.text.is_empty() {
+        match atoms.last_mut() {
+            Some(last) => {
+                last.text.push_str(&prefix.text);
+                last.width += prefix.width;
+            }
+            None => atoms.push(prefix),
+        }
+    }
+    if let Some(first) = atoms.first_mut() {
+        first.space_before = false;
+    }
+    atoms
+}
+
+/// 在第 index 个片段前断行的代价：句末标点处最低，CJK 字符之间最高
+fn break_penalty(atoms: &[Atom], index: usize) -> f64 {
+    match atoms[index - 1].last {
+        Some(c) if SENTENCE_END.contains(c) => 0.0,
+        Some(c) if CLAUSE_END.contains(c) => 10.0,
+        _ if atoms[index].space_before => 60.0,
+        _ => 200.0,
+    }
+}
+
+/// atoms[from..to] 组成一行时的宽度
+fn line_width(atoms: &[Atom], from: usize, to: usize) -> u32 {
+    atoms[from..to].iter().enumerate().map(|(i, a)| a.width + (i > 0 && a.space_before) as u32).sum()
+}
+
+/// 分成 lines 行的最优断点，返回 (代价, 是否有行超宽, 每行起始片段)
+fn best_breaks(atoms: &[Atom], lines: usize, max_width: u32) -> (f64, bool, Vec<usize>) {
+    let n = atoms.len();
+    let target = line_width(atoms, 0, n) as f64 / lines as f64;
+    // cost[m][j]：前 j 个片段分成 m 行的最小代价
+    let mut cost = vec![vec![f64::INFINITY; n + 1]; lines + 1];
+    let mut from = vec![vec![0usize; n + 1]; lines + 1];
+    cost[0][0] = 0.0;
+
+    for m in 1..=lines {
+        for j in m..=n {
+            for i in (m - 1)..j {
+                if cost[m - 1][i].is_infinite() {
+                    continue;
+                }
+                let width = line_width(atoms, i, j) as f64;
+                let mut c = cost[m - 1][i] + (width - target).powi(2);
+                c += (width - max_width as f64).max(0.0).powi(2) * 1000.0;
+                if i > 0 {
+                    c += break_penalty(atoms, i);
+                }
+                // 上行比平均宽时加罚，使下行更宽
+                if m < lines && width > target {
+                    c += 10.0 + (width - target) * 4.0;
+                }
+                if c < cost[m][j] {
+                    cost[m][j] = c;
+                    from[m][j] = i;
+                }
+            }
+        }
+    }
+
+    let mut starts = Vec::with_capacity(lines);
+    let mut j = n;
+    for m in (1..=lines).rev() {
+        j = from[m][j];
+        starts.push(j);
+    }
+    starts.reverse();
+    let overflow = (0..lines).any(|m| {
+        let end = starts.get(m + 1).copied().unwrap_or(n);
+        line_width(atoms, starts[m], end) > max_width
+    });
+    (cost[lines][n], overflow, starts)
+}
+
+/// 把文本（忽略原有换行）重新分成不超过 max_lines 行；放不下时仍按 max_lines 行尽量均分
+pub fn rewrap_text(text: &str, options: &LineBreakOptions) -> String {
+    let atoms = tokenize(text);
+    if atoms.is_empty() {
+        return text.trim().to_string();
+    }
+    let total = line_width(&atoms, 0, atoms.len());
+    let max_width = options.max_width.max(1);
+    let max_lines = (options.max_lines.max(1) as usize).min(atoms.len());
+    let min_lines = (total.div_ceil(max_width) as usize).clamp(1, max_lines);
+
+    let mut best: Option<(f64, bool, Vec<usize>)> = None;
+    for lines in min_lines..=max_lines {
+        let candidate = best_breaks(&atoms, lines, max_width);
+        let overflow = candidate.1;
+        if best.as_ref().is_none_or(|b| b.1 && (!overflow || candidate.0 < b.0)) {
+            best = Some(candidate);
+        }
+        if !overflow {
+            break;
+        }
+    }
+    let starts = best.map(|b| b.2).unwrap_or_else(|| vec![0]);
+    split_at(&atoms, &starts).join("\n")
+}
+
+/// 按起始片段把 atoms 拼接成若干段文本
+fn split_at(atoms: &[Atom], starts: &[usize]) -> Vec<String> {
+    let mut result = Vec::with_capacity(starts.len());
+    for (m, &start) in starts.iter().enumerate() {
+        let end = starts.get(m + 1).copied().unwrap_or(atoms.len());
+        let mut line = String::new();
+        for (i, atom) in atoms[start..end].iter().enumerate() {
+            if i > 0 && atom.space_before {
+                line.push(' ');
+            }
+            line.push_str(&atom.text);
+        }
+        result.push(line);
+    }
+    result
+}
+
+/// 显示宽度（不计样式标签）
+pub(super) fn text_width(text: &str) -> u32 {
+    let atoms = tokenize(text);
+    line_width(&atoms, 0, atoms.len())
+}
+
+/// 在句末标点处把文本分成若干句，宽度超过 max_width 的句子再按断行规则均分（0 表示不限宽度）
+pub(super) fn split_sentences(text: &str, max_width: u32) -> Vec<String> {
+    let atoms = tokenize(text);
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    for i in 0..atoms.len() {
+        let sentence_end = atoms[i].last.is_some_and(|c| SENTENCE_END.contains(c));
+        if !sentence_end && i + 1 < atoms.len() {
+            continue;
+        }
+        let sentence = &atoms[start..=i];
+        let width = line_width(sentence, 0, sentence.len());
+        let starts = if max_width > 0 && width > max_width {
+            let parts = (width.div_ceil(max_width) as usize).min(sentence.len());
+            best_breaks(sentence, parts, max_width).2
+        } else {
+            vec![0]
+        };
+        pieces.extend(split_at(sentence, &starts));
+        start = i + 1;
+    }
+    pieces
+}
+
+/// 对每条字幕重新换行，返回修改过的字幕数量
+pub fn break_lines(entries: &mut [SubtitleEntry], options: &LineBreakOptions) -> usize {
+    let mut changed = 0;
+    for entry in entries {
+        let text = rewrap_text(&entry.text, options);
+        if text != entry.text {
+            entry.text = text;
+            changed += 1;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_balanced_pyramid() {
+        let options = LineBreakOptions::default();
+        assert_eq!(rewrap_text("Short line", &options), "Short line");
+        assert_eq!(
+            rewrap_text("I told you we should have left the party before midnight, but you never listen", &options),
+            "I told you we should have left the party\nbefore midnight, but you never listen"
+        );
+        // 不拆开单词和数字，标点不出现在行首
+        let text = rewrap_text("The total came to 1,234,567.89 dollars and nobody could explain why it happened", &options);
+        assert_eq!(text, "The total came to 1,234,567.89 dollars\nand nobody could explain why it happened");
+        for line in text.lines() {
+            assert!(text_width(line) <= 42, "{}", line);
+        }
+        // 原有换行会被重排
+        assert_eq!(rewrap_text("Hello\nthere", &options), "Hello there");
+    }
+
+    #[test]
+    fn test_cjk_width_and_punctuation() {
+        let options = LineBreakOptions { max_width: 32, max_lines: 2 };
+        assert_eq!(text_width("中文ABC"), 7);
+        // 优先在逗号后断行
+        assert_eq!(
+            rewrap_text("今天我们一起去公园散步，然后在湖边吃了一顿午饭", &options),
+            "今天我们一起去公园散步，\n然后在湖边吃了一顿午饭"
+        );
+        // 没有标点时均分，下行不短于上行；英文单词不拆开
+        let text = rewrap_text("这是一段没有任何标点符号的很长的中文字幕需要自动换行Whisper", &options);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(text_width(lines[0]) <= text_width(lines[1]));
+        assert!(lines[1].ends_with("Whisper"));
+        // 引号跟随文字
+        assert_eq!(
+            rewrap_text("他说：“我们明天八点集合”，大家都同意了", &options),
+            "他说：“我们明天八点集合”，\n大家都同意了"
+        );
+        // 标签不计宽度
+        assert_eq!(rewrap_text("<i>Hello</i>\nthere", &options), "<i>Hello</i> there");
+        assert_eq!(text_width("<i>你好</i>"), 4);
+    }
+
+    #[test]
+    fn test_break_lines_counts_changes() {
+        let mut entries = vec![
+            SubtitleEntry { id: 1, text: "Fine".to_string(), ..Default::default() },
+            SubtitleEntry {
+                id: 2,
+                text: "This sentence is definitely going to be longer than forty two characters".to_string(),
+                ..Default::default()
+            },
+        ];
+        assert_eq!(break_lines(&mut entries, &LineBreakOptions::default()), 1);
+        assert_eq!(entries[1].text.lines().count(), 2);
+    }
+}
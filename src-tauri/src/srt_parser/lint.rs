@@ -0,0 +1,321 @@
+//! 字幕质量检查：按交付规范（Netflix、BBC 或自定义配置）检查时间轴和文本
+//!
+//! 时间重叠和非正时长为错误，其余规则为警告；阈值为 0 表示不检查该项。
+//! 含 CJK 字符的行按 CJK 限制检查，其中的半角字符（拉丁字母、数字、空格等）按半个字计算。
+
+use serde::{Deserialize, Serialize};
+
+use super::{is_cjk, strip_tags, SubtitleEntry};
+
+/// 检查规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LintRule {
+    Overlap,
+    InvalidDuration,
+    MinDuration,
+    MaxDuration,
+    MinGap,
+    ReadingSpeed,
+    LineLength,
+    LineCount,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+}
+
+/// 一条检查结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintFinding {
+    pub entry_id: u32,
+    pub rule: LintRule,
+    pub severity: LintSeverity,
+    pub message: String,
+    /// 相关的另一条字幕（重叠、间隔过短时为下一条字幕）
+    pub related_id: Option<u32>,
+}
+
+/// 时间重叠（与前端 TimeConflict 一致）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeConflict {
+    pub entry_id: u32,
+    pub conflict_with_id: u32,
+    /// 重叠时长（毫秒）
+    pub overlap_duration: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintReport {
+    pub findings: Vec<LintFinding>,
+    pub conflicts: Vec<TimeConflict>,
+}
+
+/// 检查配置（阈值为 0 表示不检查）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LintProfile {
+    pub name: String,
+    pub min_duration_ms: u64,
+    pub max_duration_ms: u64,
+    /// 相邻字幕的最小间隔
+    pub min_gap_ms: u64,
+    /// 每秒最多字符数
+    pub max_cps: f64,
+    pub max_cjk_cps: f64,
+    /// 每行最多字符数
+    pub max_chars_per_line: u32,
+    pub max_cjk_chars_per_line: u32,
+    pub max_lines: u32,
+}
+
+impl Default for LintProfile {
+    fn default() -> Self {
+        LintProfile {
+            name: "Netflix".to_string(),
+            min_duration_ms: 833,
+            max_duration_ms: 7000,
+            min_gap_ms: 83,
+            max_cps: 20.0,
+            max_cjk_cps: 9.0,
+            max_chars_per_line: 42,
+            max_cjk_chars_per_line: 16,
+            max_lines: 2,
+        }
+    }
+}
+
+/// 内置检查配置：Netflix（Timed Text Style Guide）和 BBC（Subtitle Guidelines）
+pub fn builtin_lint_profiles() -> Vec<LintProfile> {
+    vec![
+        LintProfile::default(),
+        LintProfile {
+            name: "BBC".to_string(),
+            min_duration_ms: 1000,
+            max_duration_ms: 8000,
+            min_gap_ms: 80,
+            max_cps: 17.0,
+            max_chars_per_line: 37,
+            ..LintProfile::default()
+        },
+    ]
+}
+
+/// 文本的可见行（去掉样式标签和首尾空白）
+fn visible_lines(text: &str) -> Vec<String> {
+    strip_tags(text)
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+/// 文本长度：含 CJK 字符时半角字符按 0.5 计，返回 (长度, 是否按 CJK 计)
+fn text_length(text: &str) -> (f64, bool) {
+    let cjk = text.chars().any(is_cjk);
+    let length = text
+        .chars()
+        .map(|c| if cjk && !is_cjk(c) && c.is_ascii() { 0.5 } else { 1.0 })
+        .sum();
+    (length, cjk)
+}
+
+fn finding(entry: &SubtitleEntry, rule: LintRule, severity: LintSeverity, message: String) -> LintFinding {
+    LintFinding { entry_id: entry.id, rule, severity, message, related_id: None }
+}
+
+/// 检查单条字幕的时长和文本
+fn lint_entry(entry: &SubtitleEntry, profile: &LintProfile, findings: &mut Vec<LintFinding>) {
+    let start = entry.start_time.to_ms();
+    let end = entry.end_time.to_ms();
+    let lines = visible_lines(&entry.text);
+
+    if end <= start {
+        findings.push(finding(entry, LintRule::InvalidDuration, LintSeverity::Error, "结束时间不晚于开始时间".to_string()));
+    } else {
+        let duration = end - start;
+        if profile.min_duration_ms > 0 && duration < profile.min_duration_ms {
+            findings.push(finding(
+                entry,
+                LintRule::MinDuration,
+                LintSeverity::Warning,
+                format!("时长 {} ms 短于 {} ms", duration, profile.min_duration_ms),
+            ));
+        }
+        if profile.max_duration_ms > 0 && duration > profile.max_duration_ms {
+            findings.push(finding(
+                entry,
+                LintRule::MaxDuration,
+                LintSeverity::Warning,
+                format!("时长 {} ms 超过 {} ms", duration, profile.max_duration_ms),
+            ));
+        }
+
+        let (length, cjk) = text_length(&lines.concat());
+        let max_cps = if cjk { profile.max_cjk_cps } else { profile.max_cps };
+        let cps = length * 1000.0 / duration as f64;
+        if max_cps > 0.0 && cps > max_cps {
+            findings.push(finding(
+                entry,
+                LintRule::ReadingSpeed,
+                LintSeverity::Warning,
+                format!("阅读速度 {:.1} 字/秒 超过 {}", cps, max_cps),
+            ));
+        }
+    }
+
+    if profile.max_lines > 0 && lines.len() > profile.max_lines as usize {
+        findings.push(finding(
+            entry,
+            LintRule::LineCount,
+            LintSeverity::Warning,
+            format!("共 {} 行，超过 {} 行", lines.len(), profile.max_lines),
+        ));
+    }
+    for (index, line) in lines.iter().enumerate() {
+        let (length, cjk) = text_length(line);
+        let limit = if cjk { profile.max_cjk_chars_per_line } else { profile.max_chars_per_line };
+        if limit > 0 && length > limit as f64 {
+            findings.push(finding(
+                entry,
+                LintRule::LineLength,
+                LintSeverity::Warning,
+                format!("第 {} 行长度 {} 超过 {}", index + 1, length, limit),
+            ));
+        }
+    }
+}
+
+/// 按配置检查字幕，结果按字幕顺序排列；时间重叠同时以 TimeConflict 返回
+pub fn lint_subtitles(entries: &[SubtitleEntry], profile: &LintProfile) -> LintReport {
+    let mut report = LintReport::default();
+    for entry in entries {
+        lint_entry(entry, profile, &mut report.findings);
+    }
+
+    // 按开始时间排序后与所有尚未结束的字幕比较，多条字幕互相重叠时每一对都会报告；
+    // 没有重叠时与此前结束最晚的字幕比较间隔
+    let mut sorted: Vec<&SubtitleEntry> = entries.iter().collect();
+    sorted.sort_by_key(|e| e.start_time.to_ms());
+    let mut active: Vec<&SubtitleEntry> = Vec::new();
+    let mut latest: Option<&SubtitleEntry> = None;
+    for entry in sorted {
+        let start = entry.start_time.to_ms();
+        active.retain(|previous| previous.end_time.to_ms() > start);
+        for previous in &active {
+            let overlap = previous.end_time.to_ms().min(entry.end_time.to_ms()).saturating_sub(start);
+            report.conflicts.push(TimeConflict {
+                entry_id: previous.id,
+                conflict_with_id: entry.id,
+                overlap_duration: overlap,
+            });
+            report.findings.push(LintFinding {
+                related_id: Some(entry.id),
+                ..finding(previous, LintRule::Overlap, LintSeverity::Error, format!("与字幕 {} 重叠 {} ms", entry.id, overlap))
+            });
+        }
+        if let Some(previous) = latest.filter(|_| active.is_empty()) {
+            let previous_end = previous.end_time.to_ms();
+            if profile.min_gap_ms > 0 && start - previous_end < profile.min_gap_ms {
+                report.findings.push(LintFinding {
+                    related_id: Some(entry.id),
+                    ..finding(
+                        previous,
+                        LintRule::MinGap,
+                        LintSeverity::Warning,
+                        format!("与字幕 {} 间隔 {} ms，少于 {} ms", entry.id, start - previous_end, profile.min_gap_ms),
+                    )
+                });
+            }
+        }
+        active.push(entry);
+        if latest.is_none_or(|l| entry.end_time > l.end_time) {
+            latest = Some(entry);
+        }
+    }
+
+    let order: std::collections::HashMap<u32, usize> = entries.iter().enumerate().map(|(i, e)| (e.id, i)).collect();
+    report.findings.sort_by_key(|f| order.get(&f.entry_id).copied().unwrap_or(usize::MAX));
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srt_parser::TimeStamp;
+
+    fn entry(id: u32, start: u64, end: u64, text: &str) -> SubtitleEntry {
+        SubtitleEntry {
+            id,
+            start_time: TimeStamp::from_ms(start),
+            end_time: TimeStamp::from_ms(end),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn rules(report: &LintReport, id: u32) -> Vec<LintRule> {
+        report.findings.iter().filter(|f| f.entry_id == id).map(|f| f.rule).collect()
+    }
+
+    #[test]
+    fn test_timing_rules() {
+        let entries = vec![
+            entry(1, 0, 10_000, "A long cue"),
+            entry(2, 2000, 4000, "Inside the first"),
+            entry(3, 10_040, 10_500, "Too close!!"),
+            entry(4, 12_000, 12_000, "Zero"),
+        ];
+        let report = lint_subtitles(&entries, &LintProfile::default());
+
+        assert_eq!(report.conflicts, vec![TimeConflict { entry_id: 1, conflict_with_id: 2, overlap_duration: 2000 }]);
+        assert_eq!(rules(&report, 1), vec![LintRule::MaxDuration, LintRule::Overlap, LintRule::MinGap]);
+        assert_eq!(rules(&report, 3), vec![LintRule::MinDuration, LintRule::ReadingSpeed]);
+        assert_eq!(rules(&report, 4), vec![LintRule::InvalidDuration]);
+        assert_eq!(report.findings.iter().find(|f| f.rule == LintRule::Overlap).unwrap().severity, LintSeverity::Error);
+    }
+
+    #[test]
+    fn test_overlap_reports_every_active_pair() {
+        let entries = vec![
+            entry(1, 0, 5000, "First"),
+            entry(2, 1000, 4000, "Second"),
+            entry(3, 2000, 6000, "Third"),
+        ];
+        let report = lint_subtitles(&entries, &LintProfile::default());
+        assert_eq!(
+            report.conflicts,
+            vec![
+                TimeConflict { entry_id: 1, conflict_with_id: 2, overlap_duration: 3000 },
+                TimeConflict { entry_id: 1, conflict_with_id: 3, overlap_duration: 3000 },
+                TimeConflict { entry_id: 2, conflict_with_id: 3, overlap_duration: 2000 },
+            ]
+        );
+        assert_eq!(rules(&report, 2), vec![LintRule::Overlap]);
+    }
+
+    #[test]
+    fn test_text_rules_with_cjk_width() {
+        let entries = vec![
+            // 16 个汉字 + 4 个半角字符 = 18，超过 16
+            entry(1, 0, 5000, "这是一个用来测试中文字幕每行长度ABCD"),
+            // 16 个汉字刚好不超
+            entry(2, 6000, 11_000, "这是一个用来测试中文字幕每行长度"),
+            entry(3, 12_000, 17_000, "<i>This line is comfortably under the limit</i>\nSecond\nThird"),
+        ];
+        let report = lint_subtitles(&entries, &LintProfile::default());
+        assert_eq!(rules(&report, 1), vec![LintRule::LineLength]);
+        assert!(rules(&report, 2).is_empty());
+        assert_eq!(rules(&report, 3), vec![LintRule::LineCount]);
+
+        let bbc = &builtin_lint_profiles()[1];
+        let report = lint_subtitles(&entries[2..], bbc);
+        assert_eq!(rules(&report, 3), vec![LintRule::LineCount, LintRule::LineLength]);
+    }
+}
@@ -0,0 +1,377 @@
+//! LRC 歌词读写
+//!
+//! 支持 `[mm:ss.xx]` 行时间标签（一行可有多个）、`[ar:]` / `[ti:]` / `[offset:]` 等元数据标签，
+//! 以及增强格式中 `<mm:ss.xx>` 逐字时间。LRC 只记录每行的开始时间，结束时间取下一行的开始时间。
+
+use serde::{Deserialize, Serialize};
+
+use crate::file_safety::write_atomic;
+use super::{is_cjk, SubtitleEntry, TimeStamp};
+
+/// 最后一行没有后续时间可参考时使用的时长
+const DEFAULT_LAST_LINE_MS: u64 = 5000;
+
+/// 增强 LRC 中的一个逐字时间片段
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LrcWord {
+    pub time: TimeStamp,
+    /// 片段文本（保留词后的空格）
+    pub text: String,
+}
+
+/// LRC 元数据标签（`[ar:]`、`[ti:]`、`[al:]`、`[by:]` 等，按原顺序）
+/// `[offset:]` 在读取时已应用到时间上，不再保留
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LrcHeader {
+    pub tags: Vec<(String, String)>,
+}
+
+/// LRC 导出选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LrcExportOptions {
+    /// 输出增强格式的逐字时间；没有逐字时间的字幕按字符数均分时长
+    pub word_level: bool,
+    /// 字幕结束后与下一条之间有空隙时，写入空的时间行清屏
+    pub end_markers: bool,
+}
+
+impl Default for LrcExportOptions {
+    fn default() -> Self {
+        LrcExportOptions {
+            word_level: false,
+            end_markers: true,
+        }
+    }
+}
+
+/// 解析 LRC 时间：mm:ss、mm:ss.xx、mm:ss.xxx（分钟可超过 59），返回毫秒
+fn parse_lrc_time(s: &str) -> Option<u64> {
+    let (minutes, rest) = s.trim().split_once(':')?;
+    let (seconds, fraction) = match rest.split_once(['.', ':']) {
+        Some((sec, frac)) => (sec, frac),
+        None => (rest, ""),
+    };
+    if minutes.is_empty() || seconds.is_empty() || fraction.len() > 3 {
+        return None;
+    }
+    let all_digits = |v: &str| v.chars().all(|c| c.is_ascii_digit());
+    if !all_digits(minutes) || !all_digits(seconds) || !all_digits(fraction) {
+        return None;
+    }
+
+    let ms = if fraction.is_empty() { 0 } else { format!("{:0<3}", fraction).parse::<u64>().ok()? };
+    Some((minutes.parse::<u64>().ok()? * 60 + seconds.parse::<u64>().ok()?) * 1000 + ms)
+}
+
+/// 格式化为 mm:ss.xx
+fn format_lrc_time(ms: u64) -> String {
+    let centiseconds = (ms + 5) / 10;
+    format!(
+        "{:02}:{:02}.{:02}",
+        centiseconds / 6000,
+        (centiseconds / 100) % 60,
+        centiseconds % 100
+    )
+}
+
+/// 解析增强格式的行内容，返回 (纯文本, 逐字时间(毫秒), 行尾结束时间)
+fn parse_enhanced_text(text: &str) -> (String, Vec<(u64, String)>, Option<u64>) {
+    let mut words: Vec<(u64, String)> = Vec::new();
+    let mut leading = String::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find('<') {
+        let Some(close) = rest[open..].find('>').map(|i| open + i) else {
+            break;
+        };
+        let Some(time) = parse_lrc_time(&rest[open + 1..close]) else {
+            // 不是时间标签（例如文本中的 `<`），按普通文本处理
+            let target = words.last_mut().map(|w| &mut w.1).unwrap_or(&mut leading);
+            target.push_str(&rest[..close + 1]);
+            rest = &rest[close + 1..];
+            continue;
+        };
+        let target = words.last_mut().map(|w| &mut w.1).unwrap_or(&mut leading);
+        target.push_str(&rest[..open]);
+        words.push((time, String::new()));
+        rest = &rest[close + 1..];
+    }
+    match words.last_mut() {
+        Some(word) => word.1.push_str(rest),
+        None => leading.push_str(rest),
+    }
+
+    // 末尾不带文本的时间标签表示该行结束时间
+    let end = match words.last() {
+        Some((time, text)) if text.trim().is_empty() && words.len() > 1 => Some(*time),
+        _ => None,
+    };
+    if end.is_some() {
+        words.pop();
+    }
+
+    let plain = format!("{}{}", leading, words.iter().map(|w| w.1.as_str()).collect::<String>());
+    (plain.trim().to_string(), words, end)
+}
+
+struct LrcLine {
+    start: u64,
+    end: Option<u64>,
+    text: String,
+    words: Vec<(u64, String)>,
+}
+
+/// 解析 LRC 内容，返回元数据和字幕条目
+pub fn parse_lrc(content: &str) -> Result<(LrcHeader, Vec<SubtitleEntry>), String> {
+    let content = super::normalize_line_endings(content);
+    let mut header = LrcHeader::default();
+    let mut offset: i64 = 0;
+    let mut lines: Vec<LrcLine> = Vec::new();
+
+    for raw in content.lines() {
+        let mut rest = raw.trim();
+        let mut times: Vec<u64> = Vec::new();
+
+        while let Some(inner) = rest.strip_prefix('[') {
+            let Some(close) = inner.find(']') else { break };
+            let tag = &inner[..close];
+            if let Some(time) = parse_lrc_time(tag) {
+                times.push(time);
+            } else if let Some((key, value)) = tag.split_once(':').filter(|_| times.is_empty()) {
+                let key = key.trim();
+                if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphabetic()) {
+                    break;
+                }
+                if key.eq_ignore_ascii_case("offset") {
+                    offset = value.trim().parse().map_err(|_| format!("Invalid LRC offset: {}", value))?;
+                } else {
+                    header.tags.push((key.to_string(), value.trim().to_string()));
+                }
+            } else {
+                break;
+            }
+            rest = &inner[close + 1..];
+        }
+
+        if times.is_empty() {
+            continue;
+        }
+
+        let (text, words, end) = parse_enhanced_text(rest);
+        for &time in &times {
+            // 同一行有多个时间标签时，逐字时间随行时间一起平移
+            let shift = |t: u64| (t + time).saturating_sub(times[0]);
+            lines.push(LrcLine {
+                start: time,
+                end: end.map(shift),
+                text: text.clone(),
+                words: words.iter().map(|(t, w)| (shift(*t), w.clone())).collect(),
+            });
+        }
+    }
+
+    if lines.is_empty() && !content.trim().is_empty() && header.tags.is_empty() {
+        return Err("No timed lyrics found in LRC file".to_string());
+    }
+
+    // 正的 offset 表示歌词提前显示
+    let apply_offset = |t: u64| (t as i64 - offset).max(0) as u64;
+    for line in &mut lines {
+        line.start = apply_offset(line.start);
+        line.end = line.end.map(apply_offset);
+        for word in &mut line.words {
+            word.0 = apply_offset(word.0);
+        }
+    }
+    lines.sort_by_key(|l| l.start);
+
+    let mut entries = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        // 空的时间行只用于结束上一行
+        if line.text.is_empty() {
+            continue;
+        }
+        let next_start = lines[index + 1..].iter().map(|l| l.start).find(|&t| t > line.start);
+        let end = line
+            .end
+            .or(next_start)
+            .unwrap_or(line.start + DEFAULT_LAST_LINE_MS)
+            .max(line.start);
+
+        entries.push(SubtitleEntry {
+            id: entries.len() as u32 + 1,
+            start_time: TimeStamp::from_ms(line.start),
+            end_time: TimeStamp::from_ms(end),
+            text: line.text.clone(),
+            lrc_words: (!line.words.is_empty()).then(|| {
+                line.words
+                    .iter()
+                    .map(|(t, w)| LrcWord { time: TimeStamp::from_ms(*t), text: w.clone() })
+                    .collect()
+            }),
+            ..Default::default()
+        });
+    }
+
+    Ok((header, entries))
+}
+
+/// 把文本切分为逐字片段：CJK 字符单独成段，其他文字按空格分词（片段保留其后的空格和标点）
+fn split_words(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        let starts_new = is_cjk(c) || (current.ends_with(char::is_whitespace) && !c.is_whitespace());
+        if starts_new && !current.is_empty() && current.chars().any(|ch| !ch.is_whitespace()) {
+            words.push(std::mem::take(&mut current));
+        }
+        // CJK 字符后紧跟的标点不会开始新片段，归入该字
+        current.push(c);
+    }
+    if !current.is_empty() {
+        match words.last_mut() {
+            Some(last) if current.trim().is_empty() => last.push_str(&current),
+            _ => words.push(current),
+        }
+    }
+    words
+}
+
+/// 按字符数把字幕时长分配给各个片段
+fn interpolate_words(entry: &SubtitleEntry, text: &str) -> Vec<(u64, String)> {
+    let words = split_words(text);
+    let weight = |w: &String| w.chars().filter(|c| !c.is_whitespace()).count().max(1) as u64;
+    let total: u64 = words.iter().map(weight).sum::<u64>().max(1);
+    let start = entry.start_time.to_ms();
+    let duration = entry.end_time.to_ms().saturating_sub(start);
+
+    let mut elapsed = 0;
+    words
+        .into_iter()
+        .map(|w| {
+            let time = start + duration * elapsed / total;
+            elapsed += weight(&w);
+            (time, w)
+        })
+        .collect()
+}
+
+/// 生成 LRC 内容
+pub fn build_lrc(entries: &[SubtitleEntry], options: &LrcExportOptions, header: Option<&LrcHeader>) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    if let Some(header) = header {
+        lines.extend(header.tags.iter().map(|(key, value)| format!("[{}:{}]", key, value)));
+    }
+
+    for (index, entry) in entries.iter().enumerate() {
+        let start = entry.start_time.to_ms();
+        let end = entry.end_time.to_ms();
+        let text = entry.text.replace('\n', " ");
+        let mut line = format!("[{}]", format_lrc_time(start));
+
+        if options.word_level {
+            // 文本改动后原逐字时间已不对应，改为按字符数重新分配
+            let words = match &entry.lrc_words {
+                Some(words) if words.iter().map(|w| w.text.as_str()).collect::<String>().trim() == text => {
+                    words.iter().map(|w| (w.time.to_ms(), w.text.clone())).collect()
+                }
+                _ => interpolate_words(entry, &text),
+            };
+            for (time, word) in words {
+                line.push_str(&format!("<{}>{}", format_lrc_time(time), word));
+            }
+            line.push_str(&format!("<{}>", format_lrc_time(end)));
+        } else {
+            line.push_str(&text);
+        }
+        lines.push(line);
+
+        let next_start = entries.get(index + 1).map(|e| e.start_time.to_ms());
+        if options.end_markers && next_start.is_none_or(|next| next > end) {
+            lines.push(format!("[{}]", format_lrc_time(end)));
+        }
+    }
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+/// Export to LRC lyrics
+/// header: 原 LRC 文件的元数据标签
+pub fn export_to_lrc(
+    file_path: &str,
+    entries: &[SubtitleEntry],
+    options: &LrcExportOptions,
+    header: Option<&LrcHeader>,
+) -> Result<(), String> {
+    let content = build_lrc(entries, options, header);
+
+    write_atomic(file_path, content)
+        .map_err(|e| format!("Failed to write LRC file: {}", e))?;
+
+    println!("Successfully exported {} subtitles to LRC: {}", entries.len(), file_path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lrc_line_level() {
+        let content = "[ti:Song]\n[ar:Artist]\n[offset:500]\n[00:12.00]First line\n[00:15.50][01:02.25]Chorus\n[00:20.00]\n[00:21.10]Last";
+        let (header, entries) = parse_lrc(content).unwrap();
+        assert_eq!(header.tags, vec![
+            ("ti".to_string(), "Song".to_string()),
+            ("ar".to_string(), "Artist".to_string()),
+        ]);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].start_time.to_ms(), 11500);
+        assert_eq!(entries[0].end_time.to_ms(), 15000);
+        // 空时间行结束上一行
+        assert_eq!(entries[1].text, "Chorus");
+        assert_eq!(entries[1].end_time.to_ms(), 19500);
+        assert_eq!(entries[2].start_time.to_ms(), 20600);
+        assert_eq!(entries[3].text, "Chorus");
+        assert_eq!(entries[3].end_time.to_ms(), 61750 + DEFAULT_LAST_LINE_MS);
+    }
+
+    #[test]
+    fn test_parse_enhanced_lrc() {
+        let (_, entries) = parse_lrc("[00:01.00]<00:01.00>Hello <00:01.50>world<00:02.20>\n[00:03.00]<00:03.00>Next").unwrap();
+        assert_eq!(entries[0].text, "Hello world");
+        assert_eq!(entries[0].end_time.to_ms(), 2200);
+        let words = entries[0].lrc_words.as_ref().unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[1].time.to_ms(), 1500);
+        assert_eq!(words[1].text, "world");
+        assert_eq!(entries[1].end_time.to_ms(), 3000 + DEFAULT_LAST_LINE_MS);
+    }
+
+    #[test]
+    fn test_lrc_round_trip() {
+        let content = "[ar:Artist]\n[00:01.00]<00:01.00>Hello <00:01.50>world<00:02.20>\n[00:02.20]\n[00:03.00]<00:03.00>Next<00:04.00>\n[00:04.00]\n";
+        let (header, entries) = parse_lrc(content).unwrap();
+        let options = LrcExportOptions { word_level: true, end_markers: true };
+        assert_eq!(build_lrc(&entries, &options, Some(&header)), content);
+    }
+
+    #[test]
+    fn test_word_level_export_interpolates() {
+        let entry = SubtitleEntry {
+            start_time: TimeStamp::from_ms(0),
+            end_time: TimeStamp::from_ms(4000),
+            text: "你好 ab".to_string(),
+            ..Default::default()
+        };
+        let options = LrcExportOptions { word_level: true, end_markers: false };
+        assert_eq!(
+            build_lrc(&[entry], &options, None),
+            "[00:00.00]<00:00.00>你<00:01.00>好 <00:02.00>ab<00:04.00>\n"
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! 时间线标记导出：CMX3600 EDL（DaVinci Resolve 标记格式）和 xmeml（Premiere / Resolve 可导入的 FCP7 XML）
+//!
+//! 每条字幕对应一个标记，时间码为录制起始时间码（如 01:00:00:00）加上字幕的帧位置。
+//! 起始时间码写为 `01:00:00;00` 时按丢帧时间码输出（仅 29.97 / 59.94）。
+
+use serde::{Deserialize, Serialize};
+
+use crate::file_safety::write_atomic;
+use super::timecode::FrameRate;
+use super::{escape_xml, SubtitleEntry};
+
+/// 标记导出选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MarkerExportOptions {
+    /// 帧率，如 23.976、25、29.97
+    pub fps: f64,
+    /// 时间线起始时间码 HH:MM:SS:FF
+    pub start_timecode: String,
+    /// EDL 标题 / 序列名称
+    pub title: String,
+    /// Resolve 标记颜色，如 Blue、Red、Green
+    pub color: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for MarkerExportOptions {
+    fn default() -> Self {
+        MarkerExportOptions {
+            fps: 25.0,
+            start_timecode: "01:00:00:00".to_string(),
+            title: "VoSub Subtitles".to_string(),
+            color: "Blue".to_string(),
+            width: 1920,
+            height: 1080,
+        }
+    }
+}
+
+/// 起始时间码的帧之前为 `;` 或 `.` 时使用丢帧时间码
+fn is_drop_frame(timecode: &str) -> bool {
+    timecode.contains(';') || timecode.contains('.')
+}
+
+/// 标记名称：合并为单行
+fn marker_text(text: &str) -> String {
+    text.lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<_>>().join(" ")
+}
+
+/// 生成 CMX3600 EDL 标记（DaVinci Resolve 可通过“导入时间线标记”读取）
+pub fn build_edl_markers(entries: &[SubtitleEntry], options: &MarkerExportOptions) -> Result<String, String> {
+    let rate = FrameRate::from_fps(options.fps)?;
+    let record_start = rate.parse_timecode(&options.start_timecode)?;
+    let drop_frame = is_drop_frame(&options.start_timecode);
+
+    let mut content = format!(
+        "TITLE: {}\nFCM: {}\n\n",
+        marker_text(&options.title),
+        if drop_frame { "DROP FRAME" } else { "NON-DROP FRAME" }
+    );
+    for (index, entry) in entries.iter().enumerate() {
+        let start = record_start + entry.start_time.to_frames(rate);
+        let duration = entry.end_time.to_frames(rate).saturating_sub(entry.start_time.to_frames(rate)).max(1);
+        let record_in = rate.format_timecode(start, drop_frame)?;
+        let record_out = rate.format_timecode(start + 1, drop_frame)?;
+        content.push_str(&format!(
+            "{:03}  001      V     C        {} {} {} {}  \n |C:ResolveColor{} |M:{} |D:{}\n\n",
+            index + 1,
+            record_in, record_out, record_in, record_out,
+            options.color,
+            marker_text(&entry.text),
+            duration
+        ));
+    }
+    Ok(content)
+}
+
+/// 生成带标记的 xmeml 序列（Premiere Pro / DaVinci Resolve 可导入）
+pub fn build_xmeml_markers(entries: &[SubtitleEntry], options: &MarkerExportOptions) -> Result<String, String> {
+    let frame_rate = FrameRate::from_fps(options.fps)?;
+    let record_start = frame_rate.parse_timecode(&options.start_timecode)?;
+    let drop_frame = is_drop_frame(&options.start_timecode);
+    let ntsc = if frame_rate.denominator == 1001 { "TRUE" } else { "FALSE" };
+    let rate = format!("<rate><timebase>{}</timebase><ntsc>{}</ntsc></rate>", frame_rate.timecode_base(), ntsc);
+    let duration = entries.last().map(|e| e.end_time.to_frames(frame_rate)).unwrap_or(0);
+    let title = escape_xml(&options.title);
+
+    let mut content = format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE xmeml>
+<xmeml version="4">
+  <sequence id="sequence-1">
+    <name>{}</name>
+    <duration>{}</duration>
+    {}
+    <timecode>
+      {}
+      <string>{}</string>
+      <frame>{}</frame>
+      <displayformat>{}</displayformat>
+    </timecode>
+    <media>
+      <video>
+        <format>
+          <samplecharacteristics>
+            {}
+            <width>{}</width>
+            <height>{}</height>
+          </samplecharacteristics>
+        </format>
+        <track/>
+      </video>
+    </media>
+"#, title, duration, rate, rate, frame_rate.format_timecode(record_start, drop_frame)?, record_start,
+        if drop_frame { "DF" } else { "NDF" }, rate, options.width, options.height);
+
+    // 标记的 in / out 以序列开头为 0 计数
+    for entry in entries {
+        let text = escape_xml(&marker_text(&entry.text));
+        content.push_str(&format!(
+            "    <marker>\n      <comment>{}</comment>\n      <name>{}</name>\n      <in>{}</in>\n      <out>{}</out>\n    </marker>\n",
+            text,
+            text,
+            entry.start_time.to_frames(frame_rate),
+            entry.end_time.to_frames(frame_rate)
+        ));
+    }
+    content.push_str("  </sequence>\n</xmeml>\n");
+    Ok(content)
+}
+
+/// Export subtitles as CMX3600 EDL markers
+pub fn export_to_edl(file_path: &str, entries: &[SubtitleEntry], options: &MarkerExportOptions) -> Result<(), String> {
+    let content = build_edl_markers(entries, options)?;
+
+    write_atomic(file_path, content).map_err(|e| format!("Failed to write EDL file: {}", e))
+}
+
+/// Export subtitles as xmeml sequence markers
+pub fn export_to_xmeml(file_path: &str, entries: &[SubtitleEntry], options: &MarkerExportOptions) -> Result<(), String> {
+    let content = build_xmeml_markers(entries, options)?;
+
+    write_atomic(file_path, content).map_err(|e| format!("Failed to write XML file: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srt_parser::TimeStamp;
+
+    fn entries() -> Vec<SubtitleEntry> {
+        vec![
+            SubtitleEntry {
+                id: 1,
+                start_time: TimeStamp::from_ms(5480),
+                end_time: TimeStamp::from_ms(7000),
+                text: "Hello\nworld".to_string(),
+                ..Default::default()
+            },
+            SubtitleEntry {
+                id: 2,
+                start_time: TimeStamp::from_ms(3_600_000),
+                end_time: TimeStamp::from_ms(3_601_000),
+                text: "<an hour> & later".to_string(),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_edl_markers_with_record_offset() {
+        let content = build_edl_markers(&entries(), &MarkerExportOptions::default()).unwrap();
+        assert!(content.starts_with("TITLE: VoSub Subtitles\nFCM: NON-DROP FRAME\n\n"));
+        assert!(content.contains("001  001      V     C        01:00:05:12 01:00:05:13 01:00:05:12 01:00:05:13  \n |C:ResolveColorBlue |M:Hello world |D:38\n"));
+        assert!(content.contains("002  001      V     C        02:00:00:00 02:00:00:01"));
+
+        let options = MarkerExportOptions { start_timecode: "00:59:60:00".to_string(), ..Default::default() };
+        assert!(build_edl_markers(&entries(), &options).is_err());
+    }
+
+    #[test]
+    fn test_xmeml_markers() {
+        let options = MarkerExportOptions { fps: 29.97, ..Default::default() };
+        let content = build_xmeml_markers(&entries(), &options).unwrap();
+        assert!(content.contains("<rate><timebase>30</timebase><ntsc>TRUE</ntsc></rate>"));
+        assert!(content.contains("<string>01:00:00:00</string>\n      <frame>108000</frame>"));
+        assert!(content.contains("<name>Hello world</name>\n      <in>164</in>\n      <out>210</out>"));
+        assert!(content.contains("<comment>&lt;an hour&gt; &amp; later</comment>"));
+
+        let options = MarkerExportOptions { start_timecode: "01:00:00;00".to_string(), ..options };
+        let edl = build_edl_markers(&entries(), &options).unwrap();
+        assert!(edl.contains("FCM: DROP FRAME"));
+        assert!(edl.contains("01:00:05;14 01:00:05;15"));
+        assert!(roxmltree::Document::parse_with_options(
+            &content,
+            roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() }
+        ).is_ok());
+    }
+}
@@ -0,0 +1,640 @@
+//! Scenarist SCC (CEA-608) 闭路字幕读写
+//!
+//! 导出使用 CC1 通道的 pop-on 模式：先把字幕载入非显示内存（RCL、ENM、PAC、文本），
+//! 到开始时间发送 EOC 翻转显示，到结束时间发送 EDM 清屏。时间码为 29.97 fps 丢帧时间码，
+//! 每个字节对占用一帧。CEA-608 的限制（每行 32 个字符、最多 4 行、608 字符集）不满足时
+//! 返回全部违规项，不会截断或替换文本。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::file_safety::write_atomic;
+use super::timecode::FrameRate;
+use super::{strip_tags, ParseDiagnostic, SubtitleEntry, TimeStamp};
+
+const SCC_HEADER: &str = "Scenarist_SCC V1.0";
+const MAX_COLUMNS: usize = 32;
+const MAX_ROWS: usize = 4;
+/// 最底部的行号（608 共 15 行）
+const BOTTOM_ROW: usize = 15;
+
+// CC1 控制码（不含校验位）
+const RCL: (u8, u8) = (0x14, 0x20);
+const BS: (u8, u8) = (0x14, 0x21);
+const RDC: (u8, u8) = (0x14, 0x29);
+const EDM: (u8, u8) = (0x14, 0x2C);
+const CR: (u8, u8) = (0x14, 0x2D);
+const ENM: (u8, u8) = (0x14, 0x2E);
+const EOC: (u8, u8) = (0x14, 0x2F);
+
+/// 各行 PAC 的第一个字节和第二个字节的基准值
+const PAC_ROWS: [(u8, u8); 15] = [
+    (0x11, 0x40), (0x11, 0x60), (0x12, 0x40), (0x12, 0x60), (0x15, 0x40),
+    (0x15, 0x60), (0x16, 0x40), (0x16, 0x60), (0x17, 0x40), (0x17, 0x60),
+    (0x10, 0x40), (0x13, 0x40), (0x13, 0x60), (0x14, 0x40), (0x14, 0x60),
+];
+
+/// 特殊字符（0x11 0x30-0x3F），0x39 为透明空格
+const SPECIAL_CHARS: &str = "®°½¿™¢£♪à\u{A0}èâêîôû";
+/// 扩展字符（0x12 / 0x13 0x20-0x3F），发送前先发送一个基本字符作为不支持扩展字符的解码器的后备
+const EXTENDED_CHARS: [(u8, &str, &str); 2] = [
+    (0x12, "ÁÉÓÚÜü‘¡*’—©℠•“”ÀÂÇÈÊËëÎÏïÔÙùÛ«»", "AEOUUu'!.'-cs.\"\"AACEEEeIIiOUuU\"\""),
+    (0x13, "ÃãÍÌìÒòÕõ{}\\^_|~ÄäÖöß¥¤¦ÅåØø┌┐└┘", "AaIIiOoOo()/'-!-AaOosYo!AaOo++++"),
+];
+
+/// 单条字幕违反 CEA-608 限制的问题
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptionViolation {
+    pub entry_id: u32,
+    pub issue: String,
+}
+
+/// 加奇校验位
+fn with_parity(byte: u8) -> u8 {
+    let byte = byte & 0x7F;
+    if byte.count_ones().is_multiple_of(2) { byte | 0x80 } else { byte }
+}
+
+fn has_valid_parity(byte: u8) -> bool {
+    byte.count_ones() % 2 == 1
+}
+
+/// 基本字符集中与 ASCII 不同的位置
+fn basic_char_code(c: char) -> Option<u8> {
+    match c {
+        'á' => Some(0x2A),
+        'é' => Some(0x5C),
+        'í' => Some(0x5E),
+        'ó' => Some(0x5F),
+        'ú' => Some(0x60),
+        'ç' => Some(0x7B),
+        '÷' => Some(0x7C),
+        'Ñ' => Some(0x7D),
+        'ñ' => Some(0x7E),
+        '█' => Some(0x7F),
+        '*' | '\\' | '^' | '_' | '`' | '{' | '|' | '}' | '~' => None,
+        ' '..='\x7E' => Some(c as u8),
+        _ => None,
+    }
+}
+
+fn basic_char(code: u8) -> char {
+    match code {
+        0x2A => 'á',
+        0x5C => 'é',
+        0x5E => 'í',
+        0x5F => 'ó',
+        0x60 => 'ú',
+        0x7B => 'ç',
+        0x7C => '÷',
+        0x7D => 'Ñ',
+        0x7E => 'ñ',
+        0x7F => '█',
+        _ => code as char,
+    }
+}
+
+/// 608 中字符的编码方式
+enum CharCode {
+    Basic(u8),
+    Special(u8),
+    /// (第一个字节, 第二个字节, 后备基本字符)
+    Extended(u8, u8, u8),
+}
+
+fn char_code(c: char) -> Option<CharCode> {
+    if let Some(code) = basic_char_code(c) {
+        return Some(CharCode::Basic(code));
+    }
+    if let Some(index) = SPECIAL_CHARS.chars().position(|ch| ch == c) {
+        return Some(CharCode::Special(0x30 + index as u8));
+    }
+    EXTENDED_CHARS.iter().find_map(|(first, chars, fallbacks)| {
+        let index = chars.chars().position(|ch| ch == c)?;
+        let fallback = fallbacks.chars().nth(index)? as u8;
+        Some(CharCode::Extended(*first, 0x20 + index as u8, fallback))
+    })
+}
+
+fn caption_lines(entry: &SubtitleEntry) -> Vec<String> {
+    strip_tags(&entry.text)
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+/// 检查字幕是否满足 CEA-608 限制，返回全部违规项
+pub fn validate_cea608(entries: &[SubtitleEntry]) -> Vec<CaptionViolation> {
+    let mut violations = Vec::new();
+    let mut report = |entry: &SubtitleEntry, issue: String| {
+        violations.push(CaptionViolation { entry_id: entry.id, issue });
+    };
+
+    // 按开始时间排序后与所有尚未结束的字幕比较，输入未排序或多条字幕同时重叠时也能全部发现
+    let mut overlaps: Vec<Vec<u32>> = vec![Vec::new(); entries.len()];
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by_key(|&i| entries[i].start_time.to_ms());
+    let mut active: Vec<usize> = Vec::new();
+    for index in order {
+        let start = entries[index].start_time.to_ms();
+        active.retain(|&previous| entries[previous].end_time.to_ms() > start);
+        overlaps[index].extend(active.iter().map(|&previous| entries[previous].id));
+        active.push(index);
+    }
+
+    for (index, entry) in entries.iter().enumerate() {
+        let lines = caption_lines(entry);
+        if lines.len() > MAX_ROWS {
+            report(entry, format!("共 {} 行，超过 {} 行的限制", lines.len(), MAX_ROWS));
+        }
+        for (line_index, line) in lines.iter().enumerate() {
+            let count = line.chars().count();
+            if count > MAX_COLUMNS {
+                report(entry, format!("第 {} 行有 {} 个字符，超过 {} 个字符的限制", line_index + 1, count, MAX_COLUMNS));
+            }
+            let unsupported: String = line.chars().filter(|&c| char_code(c).is_none()).collect();
+            if !unsupported.is_empty() {
+                report(entry, format!("第 {} 行包含 608 字符集以外的字符「{}」", line_index + 1, unsupported));
+            }
+        }
+        if entry.end_time.to_ms() <= entry.start_time.to_ms() {
+            report(entry, "结束时间不晚于开始时间".to_string());
+        }
+        for previous_id in &overlaps[index] {
+            report(entry, format!("与字幕 {} 时间重叠，pop-on 字幕同一时间只能显示一条", previous_id));
+        }
+    }
+    violations
+}
+
+// ============ 时间码 ============
+
+/// SCC 固定为 29.97 fps
+const NTSC: FrameRate = FrameRate { numerator: 30_000, denominator: 1001 };
+
+fn ms_to_frames(ms: u64) -> u64 {
+    NTSC.frames_from_ms(ms)
+}
+
+fn frames_to_ms(frames: u64) -> u64 {
+    NTSC.ms_from_frames(frames)
+}
+
+// ============ 导出 ============
+
+#[derive(Default)]
+struct PairWriter {
+    pairs: Vec<(u8, u8)>,
+    pending: Option<u8>,
+}
+
+impl PairWriter {
+    fn flush(&mut self) {
+        if let Some(byte) = self.pending.take() {
+            self.pairs.push((byte, 0x00));
+        }
+    }
+
+    /// 控制码和特殊字符要求从字节对边界开始，并重复发送一次
+    fn control(&mut self, code: (u8, u8)) {
+        self.flush();
+        self.pairs.push(code);
+        self.pairs.push(code);
+    }
+
+    fn char(&mut self, code: u8) {
+        match self.pending.take() {
+            Some(first) => self.pairs.push((first, code)),
+            None => self.pending = Some(code),
+        }
+    }
+
+    fn finish(mut self) -> Vec<(u8, u8)> {
+        self.flush();
+        self.pairs
+    }
+}
+
+/// 生成一条字幕载入非显示内存的字节对（不含 EOC）
+fn load_caption(lines: &[String]) -> Vec<(u8, u8)> {
+    let mut writer = PairWriter::default();
+    writer.control(RCL);
+    writer.control(ENM);
+
+    let first_row = BOTTOM_ROW + 1 - lines.len();
+    for (index, line) in lines.iter().enumerate() {
+        // 居中：PAC 以 4 列为单位缩进，剩余列用 Tab Offset 补齐
+        let column = (MAX_COLUMNS - line.chars().count().min(MAX_COLUMNS)) / 2;
+        let (first, base) = PAC_ROWS[first_row + index - 1];
+        writer.control((first, base + 0x10 + (column / 4 * 2) as u8));
+        if !column.is_multiple_of(4) {
+            writer.control((0x17, 0x20 + (column % 4) as u8));
+        }
+
+        for c in line.chars() {
+            match char_code(c) {
+                Some(CharCode::Basic(code)) => writer.char(code),
+                Some(CharCode::Special(code)) => writer.control((0x11, code)),
+                Some(CharCode::Extended(first, second, fallback)) => {
+                    writer.char(fallback);
+                    writer.control((first, second));
+                }
+                None => {}
+            }
+        }
+    }
+    writer.finish()
+}
+
+fn format_pairs(pairs: &[(u8, u8)]) -> String {
+    pairs
+        .iter()
+        .map(|(a, b)| format!("{:02x}{:02x}", with_parity(*a), with_parity(*b)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 检查 CEA-608 限制，有违规时返回全部问题（每行一条，带字幕 ID）
+pub fn check_cea608(entries: &[SubtitleEntry]) -> Result<(), String> {
+    let violations = validate_cea608(entries);
+    if violations.is_empty() {
+        return Ok(());
+    }
+    Err(violations
+        .iter()
+        .map(|v| format!("字幕 {}: {}", v.entry_id, v.issue))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// 生成 SCC 文件内容，有违反 CEA-608 限制的字幕时返回全部问题
+pub fn build_scc(entries: &[SubtitleEntry]) -> Result<String, String> {
+    check_cea608(entries)?;
+
+    // (开始帧, 字节对)，按发送顺序排列
+    let mut events: Vec<(u64, Vec<(u8, u8)>)> = Vec::new();
+    let mut cursor = 0u64;
+    let mut pending_clear: Option<u64> = None;
+
+    for (index, entry) in entries.iter().enumerate() {
+        let display = ms_to_frames(entry.start_time.to_ms());
+        let end = ms_to_frames(entry.end_time.to_ms());
+        let load = load_caption(&caption_lines(entry));
+        let load_len = load.len() as u64;
+        let mut loaded = false;
+
+        if let Some(clear) = pending_clear.take() {
+            if clear + 2 + load_len > display {
+                // 清屏和载入时间冲突：先载入（非显示内存此时已空闲），再清屏
+                let at = cursor.max(clear.saturating_sub(load_len));
+                events.push((at, load.clone()));
+                cursor = at + load_len;
+                loaded = true;
+            }
+            let at = cursor.max(clear);
+            events.push((at, vec![EDM, EDM]));
+            cursor = at + 2;
+        }
+        if !loaded {
+            let at = cursor.max(display.saturating_sub(load_len));
+            events.push((at, load));
+            cursor = at + load_len;
+        }
+
+        let at = cursor.max(display);
+        events.push((at, vec![EOC, EOC]));
+        cursor = at + 2;
+
+        // 下一条紧接着开始时不需要清屏，EOC 会直接替换显示内容
+        let next_display = entries.get(index + 1).map(|e| ms_to_frames(e.start_time.to_ms()));
+        if next_display.is_none_or(|next| next > end) {
+            pending_clear = Some(end.max(cursor));
+        }
+    }
+    if let Some(clear) = pending_clear {
+        events.push((clear.max(cursor), vec![EDM, EDM]));
+    }
+
+    // 时间上连续的事件合并为一行
+    let mut lines: Vec<(u64, Vec<(u8, u8)>)> = Vec::new();
+    for (frame, pairs) in events {
+        match lines.last_mut() {
+            Some((start, existing)) if *start + existing.len() as u64 == frame => existing.extend(pairs),
+            _ => lines.push((frame, pairs)),
+        }
+    }
+
+    let mut content = format!("{}\n\n", SCC_HEADER);
+    for (frame, pairs) in lines {
+        content.push_str(&format!("{}\t{}\n\n", NTSC.format_timecode(frame, true)?, format_pairs(&pairs)));
+    }
+    Ok(content)
+}
+
+/// Export to Scenarist SCC (CEA-608 pop-on, 29.97 drop-frame)
+pub fn export_to_scc(file_path: &str, entries: &[SubtitleEntry]) -> Result<(), String> {
+    let content = build_scc(entries)?;
+
+    write_atomic(file_path, content)
+        .map_err(|e| format!("Failed to write SCC file: {}", e))?;
+
+    println!("Successfully exported {} subtitles to SCC: {}", entries.len(), file_path);
+    Ok(())
+}
+
+// ============ 导入 ============
+
+/// 字幕内存：行号 -> 32 列字符
+type CaptionMemory = BTreeMap<usize, Vec<char>>;
+
+fn memory_text(memory: &CaptionMemory) -> String {
+    memory
+        .values()
+        .map(|row| row.iter().collect::<String>().trim().to_string())
+        .filter(|row| !row.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 简化的 CC1 解码器：pop-on 写入非显示内存，paint-on / roll-up 直接写入显示内存
+struct Decoder {
+    displayed: CaptionMemory,
+    non_displayed: CaptionMemory,
+    pop_on: bool,
+    row: usize,
+    column: usize,
+    /// 当前显示内容的开始帧和文本
+    showing: Option<(u64, String)>,
+    entries: Vec<SubtitleEntry>,
+    diagnostics: Vec<ParseDiagnostic>,
+}
+
+impl Decoder {
+    fn memory(&mut self) -> &mut CaptionMemory {
+        if self.pop_on { &mut self.non_displayed } else { &mut self.displayed }
+    }
+
+    fn write(&mut self, c: char, line: usize) {
+        let (row, column) = (self.row, self.column);
+        if column >= MAX_COLUMNS {
+            self.diagnostics.push(ParseDiagnostic {
+                line,
+                block: 0,
+                issue: format!("第 {} 行超过 {} 列，字符「{}」被丢弃", row, MAX_COLUMNS, c),
+                auto_fix: None,
+            });
+            return;
+        }
+        let cells = self.memory().entry(row).or_insert_with(|| vec![' '; MAX_COLUMNS]);
+        cells[column] = c;
+        self.column += 1;
+    }
+
+    /// 显示内容变化：结束当前字幕，开始新字幕
+    fn display_changed(&mut self, frame: u64) {
+        let text = memory_text(&self.displayed);
+        if self.showing.as_ref().is_some_and(|(_, current)| *current == text) {
+            return;
+        }
+        if let Some((start, current)) = self.showing.take() {
+            self.entries.push(SubtitleEntry {
+                id: self.entries.len() as u32 + 1,
+                start_time: TimeStamp::from_ms(frames_to_ms(start)),
+                end_time: TimeStamp::from_ms(frames_to_ms(frame)),
+                text: current,
+                ..Default::default()
+            });
+        }
+        if !text.is_empty() {
+            self.showing = Some((frame, text));
+        }
+    }
+
+    fn control(&mut self, (first, second): (u8, u8), frame: u64, line: usize) {
+        // 0x15 为第二场的杂项控制码，按 0x14 处理
+        let first = if first == 0x15 && (0x20..=0x2F).contains(&second) { 0x14 } else { first };
+        match (first, second) {
+            code if code == RCL => self.pop_on = true,
+            code if code == RDC => self.pop_on = false,
+            (0x14, 0x25..=0x27) => self.pop_on = false,
+            code if code == ENM => self.non_displayed.clear(),
+            code if code == EDM => {
+                self.displayed.clear();
+                self.display_changed(frame);
+            }
+            code if code == EOC => {
+                std::mem::swap(&mut self.displayed, &mut self.non_displayed);
+                self.pop_on = true;
+                self.display_changed(frame);
+            }
+            code if code == CR => {
+                self.row = (self.row + 1).min(BOTTOM_ROW);
+                self.column = 0;
+            }
+            code if code == BS => {
+                self.column = self.column.saturating_sub(1);
+                let (row, column) = (self.row, self.column);
+                if let Some(cells) = self.memory().get_mut(&row) {
+                    cells[column] = ' ';
+                }
+            }
+            (0x17, 0x21..=0x23) => self.column += (second - 0x20) as usize,
+            (0x11, 0x20..=0x2F) => self.write(' ', line),
+            (0x11, 0x30..=0x3F) => {
+                let c = SPECIAL_CHARS.chars().nth((second - 0x30) as usize).unwrap_or(' ');
+                self.write(if c == '\u{A0}' { ' ' } else { c }, line);
+            }
+            (0x12 | 0x13, 0x20..=0x3F) => {
+                // 扩展字符替换前面的后备字符
+                self.column = self.column.saturating_sub(1);
+                let chars = if first == 0x12 { EXTENDED_CHARS[0].1 } else { EXTENDED_CHARS[1].1 };
+                let c = chars.chars().nth((second - 0x20) as usize).unwrap_or(' ');
+                self.write(c, line);
+            }
+            (0x10..=0x17, 0x40..=0x7F) => {
+                let row_base = second & 0x60;
+                if let Some(row) = PAC_ROWS.iter().position(|&(f, b)| f == first && b == row_base) {
+                    self.row = row + 1;
+                    let attribute = second & 0x1F;
+                    self.column = if attribute >= 0x10 { ((attribute - 0x10) / 2 * 4) as usize } else { 0 };
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(mut self, frame: u64) -> (Vec<SubtitleEntry>, Vec<ParseDiagnostic>) {
+        if self.showing.is_some() {
+            self.diagnostics.push(ParseDiagnostic {
+                line: 0,
+                block: self.entries.len() + 1,
+                issue: "最后一条字幕没有清屏指令，结束时间取文件最后一帧".to_string(),
+                auto_fix: Some("使用最后一帧作为结束时间".to_string()),
+            });
+            self.displayed.clear();
+            self.display_changed(frame);
+        }
+        (self.entries, self.diagnostics)
+    }
+}
+
+/// 解析 SCC 文件（CC1 通道），返回字幕和发现的问题（用于检查合作方交付的文件）
+pub fn parse_scc(content: &str) -> Result<(Vec<SubtitleEntry>, Vec<ParseDiagnostic>), String> {
+    let content = super::normalize_line_endings(content);
+    let mut lines = content.lines().enumerate();
+    if lines.next().is_none_or(|(_, l)| l.trim() != SCC_HEADER) {
+        return Err("Missing Scenarist_SCC V1.0 header".to_string());
+    }
+
+    let mut decoder = Decoder {
+        displayed: CaptionMemory::new(),
+        non_displayed: CaptionMemory::new(),
+        pop_on: true,
+        row: BOTTOM_ROW,
+        column: 0,
+        showing: None,
+        entries: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+    let mut last_frame = 0;
+    let mut last_control: Option<(u8, u8)> = None;
+    let mut channel_one = true;
+
+    for (index, text) in lines {
+        let line_no = index + 1;
+        let Some((timecode, data)) = text.trim().split_once(char::is_whitespace) else {
+            continue;
+        };
+        let mut frame = NTSC
+            .parse_timecode(timecode)
+            .map_err(|e| format!("Invalid SCC timecode on line {}: {}", line_no, e))?;
+        if frame < last_frame {
+            decoder.diagnostics.push(ParseDiagnostic {
+                line: line_no,
+                block: 0,
+                issue: format!("时间码 {} 早于上一行的结束位置", timecode),
+                auto_fix: None,
+            });
+        }
+
+        for word in data.split_whitespace() {
+            let value = u16::from_str_radix(word, 16).map_err(|_| format!("Invalid SCC data on line {}: {}", line_no, word))?;
+            let (a, b) = ((value >> 8) as u8, value as u8);
+            if !has_valid_parity(a) || !has_valid_parity(b) {
+                decoder.diagnostics.push(ParseDiagnostic {
+                    line: line_no,
+                    block: 0,
+                    issue: format!("字节对 {} 校验位错误", word),
+                    auto_fix: None,
+                });
+            }
+            let (a, b) = (a & 0x7F, b & 0x7F);
+
+            if (0x10..=0x1F).contains(&a) {
+                // 控制码重复发送时只执行一次
+                if last_control == Some((a, b)) {
+                    last_control = None;
+                } else {
+                    last_control = Some((a, b));
+                    channel_one = a < 0x18;
+                    if channel_one {
+                        decoder.control((a, b), frame, line_no);
+                    }
+                }
+            } else {
+                last_control = None;
+                if channel_one {
+                    for byte in [a, b].into_iter().filter(|&byte| byte >= 0x20) {
+                        decoder.write(basic_char(byte), line_no);
+                    }
+                }
+            }
+            frame += 1;
+        }
+        last_frame = frame;
+    }
+
+    Ok(decoder.finish(last_frame))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u32, start_ms: u64, end_ms: u64, text: &str) -> SubtitleEntry {
+        SubtitleEntry {
+            id,
+            start_time: TimeStamp::from_ms(start_ms),
+            end_time: TimeStamp::from_ms(end_ms),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_drop_frame_timecode() {
+        assert_eq!(NTSC.format_timecode(1800, true).unwrap(), "00:01:00;02");
+        assert_eq!(NTSC.format_timecode(17_982, true).unwrap(), "00:10:00;00");
+        assert_eq!(NTSC.parse_timecode("00:01:00;02").unwrap(), 1800);
+        assert_eq!(NTSC.parse_timecode("01:00:00;00").unwrap(), 107_892);
+        assert_eq!(NTSC.parse_timecode("00:00:01:00").unwrap(), 30);
+    }
+
+    #[test]
+    fn test_validate_reports_entry_ids() {
+        let entries = vec![
+            entry(3, 0, 1000, "This line is much longer than thirty-two chars"),
+            entry(4, 1000, 2000, "1\n2\n3\n4\n5"),
+            entry(5, 2000, 3000, "中文 ~"),
+        ];
+        let violations = validate_cea608(&entries);
+        assert_eq!(violations.len(), 3);
+        assert_eq!(violations[0].entry_id, 3);
+        assert_eq!(violations[1].entry_id, 4);
+        assert_eq!(violations[2].entry_id, 5);
+        assert!(violations[2].issue.contains("中文"));
+        assert!(build_scc(&entries).unwrap_err().contains("字幕 3"));
+    }
+
+    #[test]
+    fn test_validate_overlap_with_every_active_cue() {
+        // 字幕 1 覆盖字幕 2 和 3，输入顺序未排序
+        let entries = vec![
+            entry(3, 3000, 4000, "C"),
+            entry(1, 0, 5000, "A"),
+            entry(2, 1000, 2000, "B"),
+        ];
+        let violations = validate_cea608(&entries);
+        let overlaps: Vec<(u32, &str)> = violations.iter().map(|v| (v.entry_id, v.issue.as_str())).collect();
+        assert_eq!(overlaps.len(), 2);
+        assert_eq!(overlaps[0].0, 3);
+        assert!(overlaps[0].1.contains("字幕 1"));
+        assert_eq!(overlaps[1].0, 2);
+        assert!(overlaps[1].1.contains("字幕 1"));
+    }
+
+    #[test]
+    fn test_scc_round_trip() {
+        let entries = vec![
+            entry(1, 1000, 3000, "Hello, world!"),
+            entry(2, 3000, 5000, "<i>Second</i> caption\nwith ♪ and Ünïcode"),
+            entry(3, 8000, 9500, "Café"),
+        ];
+        let content = build_scc(&entries).unwrap();
+        assert!(content.starts_with("Scenarist_SCC V1.0\n\n"));
+        assert!(content.contains("9420 9420 94ae 94ae"));
+
+        let (parsed, diagnostics) = parse_scc(&content).unwrap();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[0].text, "Hello, world!");
+        assert_eq!(parsed[1].text, "Second caption\nwith ♪ and Ünïcode");
+        assert_eq!(parsed[2].text, "Café");
+        for (original, decoded) in entries.iter().zip(&parsed) {
+            assert!(original.start_time.to_ms().abs_diff(decoded.start_time.to_ms()) <= 34);
+            assert!(original.end_time.to_ms().abs_diff(decoded.end_time.to_ms()) <= 34);
+        }
+    }
+}
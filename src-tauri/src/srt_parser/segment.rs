@@ -0,0 +1,374 @@
+//! 长字幕分割与碎片合并
+//!
+//! 分割：在句末标点处断开，超过最大宽度的句子再按断行规则均分；
+//! 有逐字时间（lrcWords）时按词的时间分配，否则按字符数比例分配原字幕的时长。
+//! 合并：把字符过少的碎片与间隔足够小的相邻字幕合并。
+//! 两者都可只处理选中的字幕，处理后按顺序重新编号。
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::linebreak::{split_sentences, text_width};
+use super::{is_cjk, LrcWord, SubtitleEntry, TimeStamp};
+
+/// 分割 / 合并选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SegmentOptions {
+    /// 每条字幕的最大显示宽度（CJK 按 2 计），0 表示只在句末标点处分割
+    pub max_width: u32,
+    /// 有效字符（字母、数字、汉字）少于该值的字幕视为碎片
+    pub min_chars: usize,
+    /// 与相邻字幕的间隔不超过该值时才合并
+    pub max_gap_ms: u64,
+}
+
+impl Default for SegmentOptions {
+    fn default() -> Self {
+        SegmentOptions { max_width: 84, min_chars: 4, max_gap_ms: 500 }
+    }
+}
+
+/// 有效字符数，用于按比例分配时长
+fn char_count(text: &str) -> usize {
+    text.chars().filter(|c| c.is_alphanumeric()).count()
+}
+
+fn compact(text: &str) -> Vec<char> {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// 逐字时间与文本一致时，返回每段的开始时间和所属的词
+fn word_times(entry: &SubtitleEntry, pieces: &[String]) -> Option<Vec<(u64, Vec<LrcWord>)>> {
+    let words = entry.lrc_words.as_ref().filter(|w| !w.is_empty())?;
+    let word_text: String = words.iter().map(|w| w.text.as_str()).collect();
+    if compact(&word_text) != compact(&entry.text) {
+        return None;
+    }
+
+    // 每个非空白字符所属的词
+    let mut owner = Vec::new();
+    for (index, word) in words.iter().enumerate() {
+        owner.extend(std::iter::repeat_n(index, compact(&word.text).len()));
+    }
+    let mut result = Vec::with_capacity(pieces.len());
+    let mut offset = 0;
+    for piece in pieces {
+        let first_word = *owner.get(offset)?;
+        offset += compact(piece).len();
+        let next_word = owner.get(offset).copied().unwrap_or(words.len());
+        result.push((words[first_word].time.to_ms(), words[first_word..next_word.max(first_word)].to_vec()));
+    }
+    Some(result)
+}
+
+/// 在分割点关闭尚未闭合的 HTML 式标签（`<i>`、`<font color=..>`、WebVTT `<c.class>` 等），
+/// 并在下一段开头重新打开，使每段的标签各自配对
+fn balance_tags(pieces: &[String]) -> Vec<String> {
+    // (标签名, 原始开标签)
+    let mut open: Vec<(String, String)> = Vec::new();
+    pieces
+        .iter()
+        .map(|piece| {
+            let mut text: String = open.iter().map(|(_, tag)| tag.as_str()).collect();
+            text.push_str(piece);
+            let mut rest = piece.as_str();
+            while let Some(start) = rest.find('<') {
+                let Some(len) = rest[start..].find('>') else { break };
+                let inner = &rest[start + 1..start + len];
+                let tag = &rest[start..=start + len];
+                rest = &rest[start + len + 1..];
+                if let Some(name) = inner.strip_prefix('/') {
+                    if let Some(pos) = open.iter().rposition(|(n, _)| n == name.trim()) {
+                        open.remove(pos);
+                    }
+                } else if !inner.ends_with('/') {
+                    // WebVTT 的时间戳标签 <00:00:01.000> 不是成对标签
+                    let name = inner.split(|c: char| c.is_whitespace() || c == '.').next().unwrap_or("");
+                    if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                        open.push((name.to_string(), tag.to_string()));
+                    }
+                }
+            }
+            for (name, _) in open.iter().rev() {
+                text.push_str(&format!("</{}>", name));
+            }
+            text
+        })
+        .collect()
+}
+
+/// 分割一条字幕；无需分割时返回 None
+fn split_entry(entry: &SubtitleEntry, options: &SegmentOptions) -> Option<Vec<SubtitleEntry>> {
+    let pieces = split_sentences(&entry.text, options.max_width);
+    if pieces.len() < 2 {
+        return None;
+    }
+
+    let start = entry.start_time.to_ms();
+    let end = entry.end_time.to_ms().max(start);
+    let timed = word_times(entry, &pieces);
+    let starts: Vec<u64> = match &timed {
+        Some(times) => {
+            let mut last = start;
+            times
+                .iter()
+                .enumerate()
+                .map(|(i, (ms, _))| {
+                    last = if i == 0 { start } else { (*ms).clamp(last, end) };
+                    last
+                })
+                .collect()
+        }
+        None => {
+            // 按字符数比例分配，累计后取整避免误差积累
+            let weights: Vec<usize> = pieces.iter().map(|p| char_count(p).max(1)).collect();
+            let total: usize = weights.iter().sum();
+            let mut before = 0;
+            weights
+                .iter()
+                .map(|w| {
+                    let ms = start + (end - start) * before as u64 / total as u64;
+                    before += w;
+                    ms
+                })
+                .collect()
+        }
+    };
+
+    let result = balance_tags(&pieces)
+        .into_iter()
+        .enumerate()
+        .map(|(i, text)| {
+            let mut piece = entry.clone();
+            piece.start_time = TimeStamp::from_ms(starts[i]);
+            piece.end_time = TimeStamp::from_ms(starts.get(i + 1).copied().unwrap_or(end));
+            piece.text = text;
+            piece.lrc_words = timed.as_ref().map(|t| t[i].1.clone());
+            // WebVTT 标识符和 NOTE 只保留在第一段
+            if let (true, Some(cue)) = (i > 0, piece.vtt_cue.as_mut()) {
+                cue.identifier = None;
+                cue.notes_before.clear();
+            }
+            piece
+        })
+        .collect();
+    Some(result)
+}
+
+fn renumber(entries: &mut [SubtitleEntry]) {
+    for (i, entry) in entries.iter_mut().enumerate() {
+        entry.id = i as u32 + 1;
+    }
+}
+
+/// 分割 ids 中的字幕（None 表示全部），返回重新编号后的字幕和被分割的条数
+pub fn split_long_entries(
+    entries: Vec<SubtitleEntry>,
+    ids: Option<&[u32]>,
+    options: &SegmentOptions,
+) -> (Vec<SubtitleEntry>, usize) {
+    let selected: Option<HashSet<u32>> = ids.map(|ids| ids.iter().copied().collect());
+    let mut result = Vec::with_capacity(entries.len());
+    let mut count = 0;
+    for entry in entries {
+        let pieces = selected
+            .as_ref()
+            .is_none_or(|s| s.contains(&entry.id))
+            .then(|| split_entry(&entry, options))
+            .flatten();
+        match pieces {
+            Some(pieces) => {
+                result.extend(pieces);
+                count += 1;
+            }
+            None => result.push(entry),
+        }
+    }
+    renumber(&mut result);
+    (result, count)
+}
+
+/// 拼接两段文本：CJK 之间不加空格
+fn join_text(a: &str, b: &str) -> String {
+    let a = a.trim_end();
+    let b = b.trim_start();
+    let cjk_boundary = a.chars().last().is_some_and(is_cjk) || b.chars().next().is_some_and(is_cjk);
+    if a.is_empty() || b.is_empty() || cjk_boundary {
+        format!("{}{}", a, b)
+    } else {
+        format!("{} {}", a, b)
+    }
+}
+
+fn merge_into(target: &mut SubtitleEntry, other: SubtitleEntry, other_is_after: bool) {
+    let (first, second) = if other_is_after { (target.clone(), other) } else { (other, target.clone()) };
+    target.text = join_text(&first.text.replace('\n', " "), &second.text.replace('\n', " "));
+    target.start_time = TimeStamp::from_ms(first.start_time.to_ms().min(second.start_time.to_ms()));
+    target.end_time = TimeStamp::from_ms(first.end_time.to_ms().max(second.end_time.to_ms()));
+    target.lrc_words = match (first.lrc_words, second.lrc_words) {
+        (Some(mut a), Some(b)) => {
+            a.extend(b);
+            Some(a)
+        }
+        _ => None,
+    };
+}
+
+/// 把碎片并入前一条（间隔更小时并入后一条），合并后宽度不超过 max_width；
+/// ids 不为 None 时只合并选中的字幕。返回重新编号后的字幕和合并的次数
+pub fn merge_fragments(
+    entries: Vec<SubtitleEntry>,
+    ids: Option<&[u32]>,
+    options: &SegmentOptions,
+) -> (Vec<SubtitleEntry>, usize) {
+    let selected: Option<HashSet<u32>> = ids.map(|ids| ids.iter().copied().collect());
+    let is_selected = |e: &SubtitleEntry| selected.as_ref().is_none_or(|s| s.contains(&e.id));
+    let fits = |a: &SubtitleEntry, b: &SubtitleEntry| {
+        options.max_width == 0 || text_width(&join_text(&a.text, &b.text)) <= options.max_width
+    };
+    let is_fragment = |e: &SubtitleEntry| char_count(&e.text) < options.min_chars;
+
+    let mut result: Vec<SubtitleEntry> = Vec::with_capacity(entries.len());
+    let mut count = 0;
+    let mut iter = entries.into_iter().peekable();
+    while let Some(entry) = iter.next() {
+        if !is_selected(&entry) || !is_fragment(&entry) {
+            result.push(entry);
+            continue;
+        }
+
+        let start = entry.start_time.to_ms();
+        let end = entry.end_time.to_ms();
+        let previous_gap = result
+            .last()
+            .filter(|p| is_selected(p) && fits(p, &entry))
+            .map(|p| start.saturating_sub(p.end_time.to_ms()))
+            .filter(|gap| *gap <= options.max_gap_ms);
+        let next_gap = iter
+            .peek()
+            .filter(|n| is_selected(n) && fits(&entry, n))
+            .map(|n| n.start_time.to_ms().saturating_sub(end))
+            .filter(|gap| *gap <= options.max_gap_ms);
+
+        match (previous_gap, next_gap) {
+            (Some(p), n) if n.is_none_or(|n| p <= n) => {
+                merge_into(result.last_mut().unwrap(), entry, true);
+                count += 1;
+            }
+            (_, Some(_)) => {
+                let mut next = iter.next().unwrap();
+                merge_into(&mut next, entry, false);
+                result.push(next);
+                count += 1;
+            }
+            _ => result.push(entry),
+        }
+    }
+    renumber(&mut result);
+    (result, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u32, start: u64, end: u64, text: &str) -> SubtitleEntry {
+        SubtitleEntry {
+            id,
+            start_time: TimeStamp::from_ms(start),
+            end_time: TimeStamp::from_ms(end),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn summary(entries: &[SubtitleEntry]) -> Vec<(u32, u64, u64, &str)> {
+        entries.iter().map(|e| (e.id, e.start_time.to_ms(), e.end_time.to_ms(), e.text.as_str())).collect()
+    }
+
+    #[test]
+    fn test_split_by_sentence_and_proportion() {
+        let entries = vec![
+            entry(1, 0, 12_000, "Hello there. This is a longer second sentence!\nOK?"),
+            entry(2, 12_000, 15_000, "我们走吧。好的"),
+            entry(3, 15_000, 16_000, "Single sentence"),
+        ];
+        let (result, count) = split_long_entries(entries.clone(), None, &SegmentOptions::default());
+        assert_eq!(count, 2);
+        // 字符数 10 / 27 / 2，按比例分配 12 秒
+        assert_eq!(
+            summary(&result),
+            vec![
+                (1, 0, 3076, "Hello there."),
+                (2, 3076, 11_384, "This is a longer second sentence!"),
+                (3, 11_384, 12_000, "OK?"),
+                (4, 12_000, 14_000, "我们走吧。"),
+                (5, 14_000, 15_000, "好的"),
+                (6, 15_000, 16_000, "Single sentence"),
+            ]
+        );
+
+        // 只分割选中的字幕；超宽的句子按宽度再分
+        let options = SegmentOptions { max_width: 20, ..Default::default() };
+        let (result, count) = split_long_entries(entries, Some(&[1]), &options);
+        assert_eq!(count, 1);
+        let texts: Vec<&str> = result.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, vec!["Hello there.", "This is a longer", "second sentence!", "OK?", "我们走吧。好的", "Single sentence"]);
+    }
+
+    #[test]
+    fn test_split_with_word_times() {
+        let mut long = entry(1, 1000, 5000, "");
+        let words = [(1000, "我们"), (1400, "走吧。"), (3000, "好的")];
+        long.lrc_words = Some(words.iter().map(|&(ms, text)| LrcWord { time: TimeStamp::from_ms(ms), text: text.to_string() }).collect());
+        long.text = "我们走吧。好的".to_string();
+
+        let (result, _) = split_long_entries(vec![long], None, &SegmentOptions::default());
+        assert_eq!(summary(&result), vec![(1, 1000, 3000, "我们走吧。"), (2, 3000, 5000, "好的")]);
+        assert_eq!(result[0].lrc_words.as_ref().unwrap().len(), 2);
+        assert_eq!(result[1].lrc_words.as_ref().unwrap()[0].text, "好的");
+    }
+
+    #[test]
+    fn test_split_keeps_tags_balanced() {
+        let entries = vec![
+            entry(1, 0, 4000, "<i>Hello there. How are you?</i>"),
+            entry(2, 4000, 8000, "<font color=\"red\">Stop. <b>Now. Go</b></font>"),
+        ];
+        let (result, _) = split_long_entries(entries, None, &SegmentOptions::default());
+        let texts: Vec<&str> = result.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "<i>Hello there.</i>",
+                "<i>How are you?</i>",
+                "<font color=\"red\">Stop.</font>",
+                "<font color=\"red\"><b>Now.</b></font>",
+                "<font color=\"red\"><b>Go</b></font>",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_fragments() {
+        let entries = vec![
+            entry(1, 0, 2000, "I think so"),
+            entry(2, 2100, 2400, "too."),
+            entry(3, 5000, 5300, "Oh!"),
+            entry(4, 5350, 7000, "那是什么"),
+            entry(5, 9000, 9200, "嗯"),
+        ];
+        let (result, count) = merge_fragments(entries.clone(), None, &SegmentOptions::default());
+        assert_eq!(count, 2);
+        assert_eq!(
+            summary(&result),
+            vec![(1, 0, 2400, "I think so too."), (2, 5000, 7000, "Oh!那是什么"), (3, 9000, 9200, "嗯")]
+        );
+
+        let (result, count) = merge_fragments(entries, Some(&[1, 2]), &SegmentOptions::default());
+        assert_eq!(count, 1);
+        assert_eq!(result.len(), 4);
+    }
+}
@@ -0,0 +1,167 @@
+//! 帧率与 SMPTE 时间码
+//!
+//! NTSC 帧率（23.976 / 29.97 / 59.94 等）使用 1001 为分母的精确时基，帧数与毫秒互转都按有理数计算。
+//! 时间码按整数帧率计数（29.97 按 30 计数），丢帧时间码（`HH:MM:SS;FF`）每分钟跳过前 2 帧（59.94 为 4 帧），
+//! 逢 10 分钟不跳。
+
+use serde::{Deserialize, Serialize};
+
+/// 有理数帧率：numerator / denominator 帧每秒，如 30000/1001、25/1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameRate {
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+pub(super) fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+impl FrameRate {
+    /// 由帧率数值得到精确时基：23.976、29.97、59.94 等识别为 N×1000/1001
+    pub fn from_fps(fps: f64) -> Result<Self, String> {
+        if !(fps.is_finite() && fps > 0.0) {
+            return Err(format!("Invalid frame rate: {}", fps));
+        }
+        for nominal in [24u64, 30, 48, 60, 120] {
+            if (fps - nominal as f64 * 1000.0 / 1001.0).abs() < 0.01 {
+                return Ok(FrameRate { numerator: nominal * 1000, denominator: 1001 });
+            }
+        }
+        if (fps - fps.round()).abs() < 0.001 {
+            return Ok(FrameRate { numerator: fps.round() as u64, denominator: 1 });
+        }
+        let numerator = (fps * 1000.0).round() as u64;
+        let divisor = gcd(numerator, 1000);
+        Ok(FrameRate { numerator: numerator / divisor, denominator: 1000 / divisor })
+    }
+
+    pub fn fps(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// 时间码计数使用的整数帧率（29.97 为 30）
+    pub fn timecode_base(&self) -> u64 {
+        self.fps().round().max(1.0) as u64
+    }
+
+    /// 是否为可使用丢帧时间码的 29.97 / 59.94
+    pub fn supports_drop_frame(&self) -> bool {
+        self.denominator == 1001 && (self.numerator == 30_000 || self.numerator == 60_000)
+    }
+
+    /// 毫秒换算为最接近的帧号
+    pub fn frames_from_ms(&self, ms: u64) -> u64 {
+        let frames = (ms as u128 * self.numerator as u128 * 2 + self.denominator as u128 * 1000)
+            / (self.denominator as u128 * 2000);
+        frames.min(u64::MAX as u128) as u64
+    }
+
+    /// 帧号换算为最接近的毫秒
+    pub fn ms_from_frames(&self, frames: u64) -> u64 {
+        let ms = (frames as u128 * self.denominator as u128 * 2000 + self.numerator as u128)
+            / (self.numerator as u128 * 2);
+        ms.min(u64::MAX as u128) as u64
+    }
+
+    /// 丢帧时间码每分钟跳过的帧号数量（29.97 为 2，59.94 为 4）
+    fn dropped_per_minute(&self) -> u64 {
+        self.timecode_base() / 15
+    }
+
+    /// 帧数格式化为时间码：非丢帧 `HH:MM:SS:FF`，丢帧 `HH:MM:SS;FF`
+    pub fn format_timecode(&self, frames: u64, drop_frame: bool) -> Result<String, String> {
+        if drop_frame && !self.supports_drop_frame() {
+            return Err(format!("丢帧时间码仅适用于 29.97 / 59.94 fps，当前帧率为 {}", self.fps()));
+        }
+        let base = self.timecode_base();
+        let number = if drop_frame {
+            let drop = self.dropped_per_minute();
+            let per_ten_minutes = base * 600 - drop * 9;
+            let per_minute = base * 60 - drop;
+            let remainder = frames % per_ten_minutes;
+            let skipped = if remainder < drop { 0 } else { drop * ((remainder - drop) / per_minute) };
+            frames + drop * 9 * (frames / per_ten_minutes) + skipped
+        } else {
+            frames
+        };
+        Ok(format!(
+            "{:02}:{:02}:{:02}{}{:02}",
+            number / (base * 3600),
+            (number / (base * 60)) % 60,
+            (number / base) % 60,
+            if drop_frame { ';' } else { ':' },
+            number % base
+        ))
+    }
+
+    /// 解析时间码为帧数：帧之前为 `;` 或 `.` 时按丢帧时间码解析
+    pub fn parse_timecode(&self, timecode: &str) -> Result<u64, String> {
+        let invalid = || format!("Invalid timecode: {}", timecode);
+        let timecode = timecode.trim();
+        let drop_frame = timecode.contains(';') || timecode.contains('.');
+        let parts: Vec<u64> = timecode
+            .split([':', ';', '.'])
+            .map(|p| p.parse::<u64>().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let [h, m, s, f] = parts.as_slice() else {
+            return Err(invalid());
+        };
+        let base = self.timecode_base();
+        if *m >= 60 || *s >= 60 || *f >= base {
+            return Err(invalid());
+        }
+        let nominal = ((h * 60 + m) * 60 + s) * base + f;
+        if !drop_frame {
+            return Ok(nominal);
+        }
+        if !self.supports_drop_frame() {
+            return Err(format!("丢帧时间码仅适用于 29.97 / 59.94 fps: {}", timecode));
+        }
+        let drop = self.dropped_per_minute();
+        if *s == 0 && *f < drop && m % 10 != 0 {
+            return Err(format!("丢帧时间码中不存在该帧号: {}", timecode));
+        }
+        let total_minutes = h * 60 + m;
+        Ok(nominal - drop * (total_minutes - total_minutes / 10))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_frame_round_trip() {
+        let ntsc = FrameRate::from_fps(29.97).unwrap();
+        assert_eq!(ntsc.format_timecode(1800, true).unwrap(), "00:01:00;02");
+        assert_eq!(ntsc.format_timecode(17_982, true).unwrap(), "00:10:00;00");
+        assert_eq!(ntsc.format_timecode(107_892, true).unwrap(), "01:00:00;00");
+        assert_eq!(ntsc.parse_timecode("01:00:00;00").unwrap(), 107_892);
+        assert_eq!(ntsc.parse_timecode("00:00:01:00").unwrap(), 30);
+        assert!(ntsc.parse_timecode("00:01:00;01").is_err());
+
+        let double = FrameRate::from_fps(59.94).unwrap();
+        assert_eq!(double.format_timecode(3600, true).unwrap(), "00:01:00;04");
+        for frames in [0, 1799, 1800, 17_981, 17_982, 215_784, 1_000_003] {
+            let timecode = ntsc.format_timecode(frames, true).unwrap();
+            assert_eq!(ntsc.parse_timecode(&timecode).unwrap(), frames, "{}", timecode);
+            let timecode = double.format_timecode(frames, true).unwrap();
+            assert_eq!(double.parse_timecode(&timecode).unwrap(), frames, "{}", timecode);
+        }
+
+        let pal = FrameRate::from_fps(25.0).unwrap();
+        assert_eq!(pal.format_timecode(90_000, false).unwrap(), "01:00:00:00");
+        assert!(pal.format_timecode(0, true).is_err());
+        assert!(pal.parse_timecode("00:00:00:25").is_err());
+    }
+
+    #[test]
+    fn test_frames_and_ms() {
+        let ntsc = FrameRate::from_fps(29.97).unwrap();
+        assert_eq!(ntsc.frames_from_ms(3_600_000), 107_892);
+        assert_eq!(ntsc.ms_from_frames(107_892), 3_599_996);
+        assert_eq!(ntsc.ms_from_frames(1), 33);
+        assert_eq!(FrameRate::from_fps(25.0).unwrap().frames_from_ms(u64::MAX), u64::MAX / 40);
+    }
+}
@@ -0,0 +1,151 @@
+//! 批量调整字幕时间：区间平移、按锚点线性缩放、波纹插入 / 删除时间、对齐到帧
+//!
+//! 所有操作都只修改时间，不改变字幕顺序和 ID；时间小于 0 时截断为 0。
+
+use super::timecode::FrameRate;
+use super::{SubtitleEntry, TimeStamp};
+
+/// 对每条字幕的开始和结束时间（毫秒）以及 LRC 逐字时间应用同一个映射
+fn map_times(entries: &mut [SubtitleEntry], map: impl Fn(u64) -> u64) {
+    for entry in entries {
+        entry.start_time = TimeStamp::from_ms(map(entry.start_time.to_ms()));
+        entry.end_time = TimeStamp::from_ms(map(entry.end_time.to_ms()));
+        for word in entry.lrc_words.iter_mut().flatten() {
+            word.time = TimeStamp::from_ms(map(word.time.to_ms()));
+        }
+    }
+}
+
+/// 平移 ID 在 [from_id, to_id] 内的字幕（None 表示不限），如把第 120 条之后的字幕整体推后 1.2 秒
+pub fn shift_entries(entries: &mut [SubtitleEntry], offset_ms: i64, from_id: Option<u32>, to_id: Option<u32>) {
+    for entry in entries.iter_mut() {
+        if from_id.is_none_or(|id| entry.id >= id) && to_id.is_none_or(|id| entry.id <= id) {
+            map_times(std::slice::from_mut(entry), |ms| ms.saturating_add_signed(offset_ms));
+        }
+    }
+}
+
+/// 以 anchor_ms 为不动点线性缩放时间：t' = anchor + (t - anchor) × factor
+/// 25 fps 素材按 23.976 fps 播放时 factor 为 25 / 23.976
+pub fn scale_entries(entries: &mut [SubtitleEntry], factor: f64, anchor_ms: u64) -> Result<(), String> {
+    if !(factor.is_finite() && factor > 0.0) {
+        return Err(format!("Invalid scale factor: {}", factor));
+    }
+    map_times(entries, |ms| {
+        let scaled = anchor_ms as f64 + (ms as f64 - anchor_ms as f64) * factor;
+        scaled.max(0.0).round() as u64
+    });
+    Ok(())
+}
+
+/// 在 at_ms 处插入 duration_ms 的空白：之后的时间全部推后，跨过该点的字幕被拉长
+pub fn ripple_insert(entries: &mut [SubtitleEntry], at_ms: u64, duration_ms: u64) {
+    map_times(entries, |ms| if ms >= at_ms { ms.saturating_add(duration_ms) } else { ms });
+}
+
+/// 删除 [start_ms, end_ms) 这段时间：之后的时间全部提前，
+/// 完全落在区间内的字幕被移除，部分重叠的字幕被截短
+pub fn ripple_delete(entries: &mut Vec<SubtitleEntry>, start_ms: u64, end_ms: u64) -> Result<(), String> {
+    if end_ms <= start_ms {
+        return Err(format!("Invalid time range: {} - {}", start_ms, end_ms));
+    }
+    let removed = end_ms - start_ms;
+    entries.retain(|e| e.start_time.to_ms() < start_ms || e.end_time.to_ms() > end_ms);
+    map_times(entries, |ms| {
+        if ms >= end_ms {
+            ms - removed
+        } else {
+            ms.min(start_ms)
+        }
+    });
+    Ok(())
+}
+
+/// 把开始和结束时间对齐到最近的帧边界，至少保留一帧时长
+pub fn snap_to_frames(entries: &mut [SubtitleEntry], fps: f64) -> Result<(), String> {
+    let rate = FrameRate::from_fps(fps)?;
+    for entry in entries {
+        let start = rate.frames_from_ms(entry.start_time.to_ms());
+        let end = rate.frames_from_ms(entry.end_time.to_ms()).max(start + 1);
+        entry.start_time = TimeStamp::from_ms(rate.ms_from_frames(start));
+        entry.end_time = TimeStamp::from_ms(rate.ms_from_frames(end));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srt_parser::LrcWord;
+
+    fn entries(times: &[(u64, u64)]) -> Vec<SubtitleEntry> {
+        times
+            .iter()
+            .enumerate()
+            .map(|(i, &(start, end))| SubtitleEntry {
+                id: i as u32 + 1,
+                start_time: TimeStamp::from_ms(start),
+                end_time: TimeStamp::from_ms(end),
+                text: format!("line {}", i + 1),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn times(entries: &[SubtitleEntry]) -> Vec<(u64, u64)> {
+        entries.iter().map(|e| (e.start_time.to_ms(), e.end_time.to_ms())).collect()
+    }
+
+    #[test]
+    fn test_shift_range() {
+        let mut list = entries(&[(0, 1000), (2000, 3000), (4000, 5000)]);
+        shift_entries(&mut list, 1200, Some(2), None);
+        assert_eq!(times(&list), vec![(0, 1000), (3200, 4200), (5200, 6200)]);
+
+        shift_entries(&mut list, -500, None, Some(1));
+        assert_eq!(times(&list), vec![(0, 500), (3200, 4200), (5200, 6200)]);
+    }
+
+    #[test]
+    fn test_shift_moves_lrc_word_times() {
+        let mut list = entries(&[(1000, 3000)]);
+        list[0].lrc_words = Some(vec![
+            LrcWord { time: TimeStamp::from_ms(1000), text: "Hello ".to_string() },
+            LrcWord { time: TimeStamp::from_ms(1800), text: "world".to_string() },
+        ]);
+        shift_entries(&mut list, 500, None, None);
+        assert_eq!(times(&list), vec![(1500, 3500)]);
+        let words: Vec<u64> = list[0].lrc_words.iter().flatten().map(|w| w.time.to_ms()).collect();
+        assert_eq!(words, vec![1500, 2300]);
+    }
+
+    #[test]
+    fn test_scale_with_anchor() {
+        let mut list = entries(&[(10_000, 12_000), (60_000, 61_000)]);
+        scale_entries(&mut list, 25.0 / 23.976, 10_000).unwrap();
+        assert_eq!(times(&list), vec![(10_000, 12_085), (62_135, 63_178)]);
+        assert!(scale_entries(&mut list, 0.0, 0).is_err());
+    }
+
+    #[test]
+    fn test_ripple_insert_and_delete() {
+        let mut list = entries(&[(0, 1000), (1500, 2500), (3000, 4000), (5000, 6000)]);
+        ripple_insert(&mut list, 2000, 500);
+        assert_eq!(times(&list), vec![(0, 1000), (1500, 3000), (3500, 4500), (5500, 6500)]);
+
+        ripple_delete(&mut list, 2500, 4600).unwrap();
+        assert_eq!(list.iter().map(|e| e.id).collect::<Vec<_>>(), vec![1, 2, 4]);
+        assert_eq!(times(&list), vec![(0, 1000), (1500, 2500), (3400, 4400)]);
+        assert!(ripple_delete(&mut list, 10, 10).is_err());
+    }
+
+    #[test]
+    fn test_snap_to_frames() {
+        let mut list = entries(&[(1010, 1015), (2000, 3333)]);
+        snap_to_frames(&mut list, 25.0).unwrap();
+        assert_eq!(times(&list), vec![(1000, 1040), (2000, 3320)]);
+
+        snap_to_frames(&mut list, 29.97).unwrap();
+        assert_eq!(times(&list), vec![(1001, 1034), (2002, 3337)]);
+    }
+}
@@ -0,0 +1,687 @@
+//! TTML / IMSC1 (DFXP) 读写
+//!
+//! 读取时支持 clock-time（含帧）与 offset-time（h/m/s/ms/f/t）时间表达式，
+//! 并处理 `ttp:tickRate`、`ttp:frameRate`、`ttp:frameRateMultiplier` 参数以及
+//! body/div 上的时间偏移；样式与区域定义保存在 `TtmlHeader` 中。
+//! 导出的文件遵循 IMSC1 Text Profile，写出后会再做一次约束校验。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::file_safety::write_atomic;
+use super::timecode::{gcd, FrameRate};
+use super::{escape_xml, SubtitleEntry, TimeStamp};
+
+const TTML_NS: &str = "http://www.w3.org/ns/ttml";
+const TTP_NS: &str = "http://www.w3.org/ns/ttml#parameter";
+const TTS_NS: &str = "http://www.w3.org/ns/ttml#styling";
+const XML_NS: &str = "http://www.w3.org/XML/1998/namespace";
+const IMSC1_TEXT_PROFILE: &str = "http://www.w3.org/ns/ttml/profile/imsc1/text";
+
+/// 样式或区域定义：xml:id + 原始的 tts:* 属性（不含前缀，如 `fontFamily`）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TtmlDefinition {
+    pub id: String,
+    pub attributes: Vec<(String, String)>,
+}
+
+/// 单个 `<p>` 的 TTML 专有属性
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TtmlCueMeta {
+    pub region: Option<String>,
+    pub style: Option<String>,
+    /// `<p>` 上的行内 tts:* 属性
+    pub attributes: Vec<(String, String)>,
+}
+
+/// TTML 文件头：语言、时间参数、样式与区域
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TtmlHeader {
+    pub language: String,
+    pub frame_rate: Option<f64>,
+    pub tick_rate: Option<u64>,
+    pub styles: Vec<TtmlDefinition>,
+    pub regions: Vec<TtmlDefinition>,
+}
+
+/// TTML 导出选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TtmlExportOptions {
+    /// xml:lang，例如 "en"、"zh-Hans"
+    pub language: String,
+    /// 设置后使用 tick 时间表达式（如 `10000000t`），否则使用 HH:MM:SS.mmm
+    pub tick_rate: Option<u64>,
+    pub font_family: String,
+    /// 相对于单元格高度的百分比
+    pub font_size: String,
+    pub color: String,
+    pub background_color: String,
+    /// left / center / right / start / end
+    pub text_align: String,
+    /// 区域位置和尺寸（百分比）
+    pub region_origin: String,
+    pub region_extent: String,
+    /// before / center / after
+    pub display_align: String,
+}
+
+impl Default for TtmlExportOptions {
+    fn default() -> Self {
+        TtmlExportOptions {
+            language: "en".to_string(),
+            tick_rate: None,
+            font_family: "proportionalSansSerif".to_string(),
+            font_size: "100%".to_string(),
+            color: "white".to_string(),
+            background_color: "rgba(0,0,0,0.6)".to_string(),
+            text_align: "center".to_string(),
+            region_origin: "10% 10%".to_string(),
+            region_extent: "80% 80%".to_string(),
+            display_align: "after".to_string(),
+        }
+    }
+}
+
+/// 未声明 `ttp:frameRate` 时按规范使用的帧率
+const DEFAULT_FRAME_RATE: f64 = 30.0;
+
+/// 时间表达式解析所需的文档参数
+#[derive(Debug, Clone, Copy)]
+struct TimingParams {
+    frame_rate: Option<f64>,
+    sub_frame_rate: f64,
+    tick_rate: Option<u64>,
+}
+
+impl TimingParams {
+    fn effective_frame_rate(&self) -> f64 {
+        self.frame_rate.unwrap_or(DEFAULT_FRAME_RATE)
+    }
+
+    /// 未指定 tickRate 时按规范默认为 frameRate * subFrameRate，否则为 1
+    fn effective_tick_rate(&self) -> f64 {
+        match (self.tick_rate, self.frame_rate) {
+            (Some(rate), _) => rate as f64,
+            (None, Some(fps)) => fps * self.sub_frame_rate,
+            (None, None) => 1.0,
+        }
+    }
+}
+
+/// 解析 TTML 时间表达式为毫秒
+fn parse_time_expression(expr: &str, params: &TimingParams) -> Result<u64, String> {
+    let expr = expr.trim();
+    let invalid = || format!("Invalid TTML time expression: {}", expr);
+
+    // offset-time: <number><metric>
+    let metrics = [("ms", 0.001), ("h", 3600.0), ("m", 60.0), ("s", 1.0)];
+    for (suffix, scale) in metrics {
+        if let Some(value) = expr.strip_suffix(suffix) {
+            if let Ok(v) = value.parse::<f64>() {
+                return Ok((v * scale * 1000.0).round() as u64);
+            }
+        }
+    }
+    if let Some(value) = expr.strip_suffix('f') {
+        let fps = params.effective_frame_rate();
+        let v = value.parse::<f64>().map_err(|_| invalid())?;
+        return Ok((v / fps * 1000.0).round() as u64);
+    }
+    if let Some(value) = expr.strip_suffix('t') {
+        let v = value.parse::<f64>().map_err(|_| invalid())?;
+        return Ok((v / params.effective_tick_rate() * 1000.0).round() as u64);
+    }
+
+    // clock-time: HH:MM:SS(.fraction) 或 HH:MM:SS:FF(.subframes)
+    let parts: Vec<&str> = expr.split(':').collect();
+    let num = |v: &str| v.parse::<f64>().map_err(|_| invalid());
+    match parts.as_slice() {
+        [h, m, s] => Ok(((num(h)? * 3600.0 + num(m)? * 60.0 + num(s)?) * 1000.0).round() as u64),
+        [h, m, s, f] => {
+            let fps = params.effective_frame_rate();
+            let (frames, sub) = f.split_once('.').unwrap_or((f, "0"));
+            let frames = num(frames)? + num(sub)? / params.sub_frame_rate;
+            let seconds = num(h)? * 3600.0 + num(m)? * 60.0 + num(s)?;
+            Ok(((seconds + frames / fps) * 1000.0).round() as u64)
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn attribute_ns<'a>(node: &roxmltree::Node<'a, '_>, ns: &str, name: &str) -> Option<&'a str> {
+    node.attribute((ns, name))
+}
+
+/// 收集节点上的 tts:* 属性
+fn styling_attributes(node: &roxmltree::Node) -> Vec<(String, String)> {
+    node.attributes()
+        .filter(|a| a.namespace() == Some(TTS_NS))
+        .map(|a| (a.name().to_string(), a.value().to_string()))
+        .collect()
+}
+
+fn read_timing_params(root: &roxmltree::Node) -> TimingParams {
+    let frame_rate = attribute_ns(root, TTP_NS, "frameRate").and_then(|v| v.trim().parse::<f64>().ok());
+    let multiplier = attribute_ns(root, TTP_NS, "frameRateMultiplier").and_then(|v| {
+        let mut parts = v.split_whitespace().map(|p| p.parse::<f64>().ok());
+        match (parts.next().flatten(), parts.next().flatten()) {
+            (Some(n), Some(d)) if d > 0.0 => Some(n / d),
+            _ => None,
+        }
+    });
+
+    TimingParams {
+        frame_rate: frame_rate.map(|fps| fps * multiplier.unwrap_or(1.0)),
+        sub_frame_rate: attribute_ns(root, TTP_NS, "subFrameRate")
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .unwrap_or(1.0),
+        tick_rate: attribute_ns(root, TTP_NS, "tickRate").and_then(|v| v.trim().parse::<u64>().ok()),
+    }
+}
+
+/// 提取 `<p>` 内的文本：`<br/>` 转换为换行，连续空白折叠为一个空格
+fn collect_text(node: &roxmltree::Node, out: &mut String) {
+    for child in node.children() {
+        if child.is_text() {
+            let text = child.text().unwrap_or("");
+            let mut last_space = out.ends_with(' ') || out.ends_with('\n') || out.is_empty();
+            for c in text.chars() {
+                if c.is_whitespace() {
+                    if !last_space {
+                        out.push(' ');
+                        last_space = true;
+                    }
+                } else {
+                    out.push(c);
+                    last_space = false;
+                }
+            }
+        } else if child.is_element() {
+            if child.tag_name().name() == "br" {
+                while out.ends_with(' ') {
+                    out.pop();
+                }
+                out.push('\n');
+            } else {
+                collect_text(&child, out);
+            }
+        }
+    }
+}
+
+/// 解析节点的 begin/end/dur，返回相对父节点起点的 (begin, end)
+fn node_interval(
+    node: &roxmltree::Node,
+    params: &TimingParams,
+    parent_begin: u64,
+) -> Result<(Option<u64>, Option<u64>), String> {
+    let begin = node.attribute("begin").map(|v| parse_time_expression(v, params)).transpose()?;
+    let end = node.attribute("end").map(|v| parse_time_expression(v, params)).transpose()?;
+    let dur = node.attribute("dur").map(|v| parse_time_expression(v, params)).transpose()?;
+
+    let abs_begin = parent_begin + begin.unwrap_or(0);
+    let abs_end = match (end, dur) {
+        (Some(end), _) => Some(parent_begin + end),
+        (None, Some(dur)) => Some(abs_begin + dur),
+        (None, None) => None,
+    };
+    Ok((begin.map(|_| abs_begin), abs_end))
+}
+
+fn walk_body(
+    node: &roxmltree::Node,
+    params: &TimingParams,
+    parent_begin: u64,
+    parent_end: Option<u64>,
+    inherited_region: Option<&str>,
+    entries: &mut Vec<SubtitleEntry>,
+) -> Result<(), String> {
+    for child in node.children().filter(|n| n.is_element() && n.tag_name().namespace() == node.tag_name().namespace()) {
+        let (begin, end) = node_interval(&child, params, parent_begin)?;
+        let begin = begin.unwrap_or(parent_begin);
+        let end = end.or(parent_end);
+        let region = child.attribute("region").or(inherited_region);
+
+        match child.tag_name().name() {
+            "div" => walk_body(&child, params, begin, end, region, entries)?,
+            "p" => {
+                // <p> 自身没有时间时，取内部 <span> 的时间范围
+                let (begin, end) = if child.has_attribute("begin") || child.has_attribute("end") || child.has_attribute("dur") {
+                    (begin, end)
+                } else {
+                    let mut span_begin: Option<u64> = None;
+                    let mut span_end: Option<u64> = None;
+                    for span in child.descendants().filter(|n| n.is_element() && n.tag_name().name() == "span") {
+                        let (b, e) = node_interval(&span, params, begin)?;
+                        if let Some(b) = b {
+                            span_begin = Some(span_begin.map_or(b, |v| v.min(b)));
+                        }
+                        if let Some(e) = e {
+                            span_end = Some(span_end.map_or(e, |v| v.max(e)));
+                        }
+                    }
+                    (span_begin.unwrap_or(begin), span_end.or(end))
+                };
+                let end = end.ok_or_else(|| format!("<p> at position {} has no end time", child.range().start))?;
+
+                let mut text = String::new();
+                collect_text(&child, &mut text);
+                let text = text.lines().map(|l| l.trim()).collect::<Vec<_>>().join("\n");
+
+                entries.push(SubtitleEntry {
+                    id: entries.len() as u32 + 1,
+                    start_time: TimeStamp::from_ms(begin),
+                    end_time: TimeStamp::from_ms(end),
+                    text: text.trim().to_string(),
+                    ttml_cue: Some(TtmlCueMeta {
+                        region: region.map(|r| r.to_string()),
+                        style: child.attribute("style").map(|s| s.to_string()),
+                        attributes: styling_attributes(&child),
+                    }),
+                    ..Default::default()
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// 解析 TTML / DFXP 内容，返回文件头和字幕条目
+pub fn parse_ttml(content: &str) -> Result<(TtmlHeader, Vec<SubtitleEntry>), String> {
+    let doc = roxmltree::Document::parse(content).map_err(|e| format!("Invalid TTML XML: {}", e))?;
+    let root = doc.root_element();
+    if root.tag_name().name() != "tt" {
+        return Err("Root element is not <tt>".to_string());
+    }
+
+    let params = read_timing_params(&root);
+    let mut header = TtmlHeader {
+        language: attribute_ns(&root, XML_NS, "lang").unwrap_or("").to_string(),
+        frame_rate: params.frame_rate,
+        tick_rate: params.tick_rate,
+        ..Default::default()
+    };
+
+    for node in root.descendants().filter(|n| n.is_element()) {
+        let id = attribute_ns(&node, XML_NS, "id").unwrap_or("").to_string();
+        let parent = node.parent_element().map(|p| p.tag_name().name()).unwrap_or("");
+        match (node.tag_name().name(), parent) {
+            ("style", "styling") => header.styles.push(TtmlDefinition { id, attributes: styling_attributes(&node) }),
+            ("region", "layout") => header.regions.push(TtmlDefinition { id, attributes: styling_attributes(&node) }),
+            _ => {}
+        }
+    }
+
+    let mut entries = Vec::new();
+    if let Some(body) = root.children().find(|n| n.is_element() && n.tag_name().name() == "body") {
+        let (begin, end) = node_interval(&body, &params, 0)?;
+        walk_body(&body, &params, begin.unwrap_or(0), end, body.attribute("region"), &mut entries)?;
+    }
+
+    entries.sort_by_key(|e| e.start_time.to_ms());
+    for (index, entry) in entries.iter_mut().enumerate() {
+        entry.id = index as u32 + 1;
+    }
+
+    Ok((header, entries))
+}
+
+fn format_time(ts: &TimeStamp, tick_rate: Option<u64>) -> String {
+    match tick_rate {
+        Some(rate) => format!("{}t", ts.to_ms() * rate / 1000),
+        None => ts.to_vtt_string(),
+    }
+}
+
+fn format_definition(tag: &str, def: &TtmlDefinition) -> String {
+    let attributes: String = def
+        .attributes
+        .iter()
+        .map(|(k, v)| format!(" tts:{}=\"{}\"", k, escape_xml(v)))
+        .collect();
+    format!("      <{} xml:id=\"{}\"{}/>\n", tag, escape_xml(&def.id), attributes)
+}
+
+/// 帧率写为整数的 `ttp:frameRate`，非整数帧率（如 29.97）另写 `ttp:frameRateMultiplier`
+fn format_frame_rate(fps: f64) -> String {
+    let Ok(rate) = FrameRate::from_fps(fps) else {
+        return String::new();
+    };
+    let base = rate.timecode_base();
+    let mut attrs = format!(" ttp:frameRate=\"{}\"", base);
+    let (numerator, denominator) = (rate.numerator, base * rate.denominator);
+    if numerator != denominator {
+        let divisor = gcd(numerator, denominator);
+        attrs.push_str(&format!(" ttp:frameRateMultiplier=\"{} {}\"", numerator / divisor, denominator / divisor));
+    }
+    attrs
+}
+
+/// 生成 IMSC1 Text Profile 的 TTML 内容
+/// header: 原 TTML 文件的样式、区域、帧率和 tickRate（用于往返），样式和区域为空时根据 options 生成默认样式和底部区域
+pub fn build_ttml(entries: &[SubtitleEntry], options: &TtmlExportOptions, header: Option<&TtmlHeader>) -> String {
+    let language = header
+        .map(|h| h.language.as_str())
+        .filter(|l| !l.is_empty())
+        .unwrap_or(&options.language);
+
+    let (styles, regions) = match header.filter(|h| !h.styles.is_empty() || !h.regions.is_empty()) {
+        Some(h) => (h.styles.clone(), h.regions.clone()),
+        None => (
+            vec![TtmlDefinition {
+                id: "s1".to_string(),
+                attributes: vec![
+                    ("fontFamily".to_string(), options.font_family.clone()),
+                    ("fontSize".to_string(), options.font_size.clone()),
+                    ("color".to_string(), options.color.clone()),
+                    ("backgroundColor".to_string(), options.background_color.clone()),
+                    ("textAlign".to_string(), options.text_align.clone()),
+                ],
+            }],
+            vec![TtmlDefinition {
+                id: "bottom".to_string(),
+                attributes: vec![
+                    ("origin".to_string(), options.region_origin.clone()),
+                    ("extent".to_string(), options.region_extent.clone()),
+                    ("displayAlign".to_string(), options.display_align.clone()),
+                ],
+            }],
+        ),
+    };
+
+    // 导出选项未指定 tickRate 时沿用原文件的设置
+    let tick_rate = options.tick_rate.or(header.and_then(|h| h.tick_rate));
+
+    let mut content = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    content.push_str(&format!(
+        "<tt xmlns=\"{}\" xmlns:ttp=\"{}\" xmlns:tts=\"{}\" ttp:profile=\"{}\" ttp:timeBase=\"media\" ttp:cellResolution=\"32 15\"",
+        TTML_NS, TTP_NS, TTS_NS, IMSC1_TEXT_PROFILE
+    ));
+    if let Some(fps) = header.and_then(|h| h.frame_rate) {
+        content.push_str(&format_frame_rate(fps));
+    }
+    if let Some(rate) = tick_rate {
+        content.push_str(&format!(" ttp:tickRate=\"{}\"", rate));
+    }
+    content.push_str(&format!(" xml:lang=\"{}\">\n", escape_xml(language)));
+
+    content.push_str("  <head>\n    <styling>\n");
+    for style in &styles {
+        content.push_str(&format_definition("style", style));
+    }
+    content.push_str("    </styling>\n    <layout>\n");
+    for region in &regions {
+        content.push_str(&format_definition("region", region));
+    }
+    content.push_str("    </layout>\n  </head>\n");
+
+    let body_style = styles.first().map(|s| format!(" style=\"{}\"", escape_xml(&s.id))).unwrap_or_default();
+    let body_region = regions.first().map(|r| format!(" region=\"{}\"", escape_xml(&r.id))).unwrap_or_default();
+    content.push_str(&format!("  <body{}{}>\n    <div>\n", body_style, body_region));
+
+    for (index, entry) in entries.iter().enumerate() {
+        let mut attrs = String::new();
+        if let Some(meta) = &entry.ttml_cue {
+            if let Some(region) = &meta.region {
+                attrs.push_str(&format!(" region=\"{}\"", escape_xml(region)));
+            }
+            if let Some(style) = &meta.style {
+                attrs.push_str(&format!(" style=\"{}\"", escape_xml(style)));
+            }
+            for (k, v) in &meta.attributes {
+                attrs.push_str(&format!(" tts:{}=\"{}\"", k, escape_xml(v)));
+            }
+        }
+
+        let text = entry
+            .text
+            .lines()
+            .map(escape_xml)
+            .collect::<Vec<_>>()
+            .join("<br/>");
+        content.push_str(&format!(
+            "      <p xml:id=\"sub{}\" begin=\"{}\" end=\"{}\"{}>{}</p>\n",
+            index + 1,
+            format_time(&entry.start_time, tick_rate),
+            format_time(&entry.end_time, tick_rate),
+            attrs,
+            text
+        ));
+    }
+
+    content.push_str("    </div>\n  </body>\n</tt>\n");
+    content
+}
+
+/// 按 IMSC1 Text Profile 的约束检查 TTML 文档，返回违反的约束（为空表示通过）
+pub fn validate_imsc1_text(content: &str) -> Vec<String> {
+    let doc = match roxmltree::Document::parse(content) {
+        Ok(doc) => doc,
+        Err(e) => return vec![format!("XML 格式错误: {}", e)],
+    };
+    let root = doc.root_element();
+    let mut violations = Vec::new();
+
+    if root.tag_name().name() != "tt" || root.tag_name().namespace() != Some(TTML_NS) {
+        violations.push(format!("根元素必须是 {} 命名空间下的 <tt>", TTML_NS));
+        return violations;
+    }
+    if attribute_ns(&root, XML_NS, "lang").is_none() {
+        violations.push("<tt> 缺少 xml:lang 属性".to_string());
+    }
+    let profile = attribute_ns(&root, TTP_NS, "profile").or_else(|| attribute_ns(&root, TTP_NS, "contentProfiles"));
+    if !profile.is_some_and(|p| p.contains(IMSC1_TEXT_PROFILE)) {
+        violations.push("未声明 IMSC1 Text Profile（ttp:profile）".to_string());
+    }
+    if let Some(time_base) = attribute_ns(&root, TTP_NS, "timeBase") {
+        if time_base != "media" {
+            violations.push(format!("ttp:timeBase 只能为 media，当前为 {}", time_base));
+        }
+    }
+    if let Some(extent) = attribute_ns(&root, TTS_NS, "extent") {
+        if !extent.split_whitespace().all(|v| v.ends_with("px")) {
+            violations.push("<tt> 上的 tts:extent 必须使用 px 单位".to_string());
+        }
+    }
+
+    let params = read_timing_params(&root);
+    let region_ids: Vec<&str> = root
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "region")
+        .filter_map(|n| attribute_ns(&n, XML_NS, "id"))
+        .collect();
+    if region_ids.len() > 4 {
+        violations.push(format!("区域数量为 {}，IMSC1 最多允许 4 个", region_ids.len()));
+    }
+
+    let mut seen_ids: HashMap<&str, usize> = HashMap::new();
+    for node in root.descendants().filter(|n| n.is_element()) {
+        let name = node.tag_name().name();
+        if let Some(id) = attribute_ns(&node, XML_NS, "id") {
+            *seen_ids.entry(id).or_insert(0) += 1;
+        }
+        if name == "image" || node.attributes().any(|a| a.name() == "backgroundImage") {
+            violations.push("Text Profile 不允许包含图像".to_string());
+        }
+        if let Some(region) = node.attribute("region") {
+            if !region_ids.contains(&region) {
+                violations.push(format!("<{}> 引用了不存在的区域 {}", name, region));
+            }
+        }
+        for attr in ["begin", "end", "dur"] {
+            if let Some(value) = node.attribute(attr) {
+                let v = value.trim();
+                if v.ends_with('t') && !v.ends_with("ms") && params.tick_rate.is_none() {
+                    violations.push(format!("使用 tick 时间 {} 但未声明 ttp:tickRate", v));
+                } else if let Err(e) = parse_time_expression(v, &params) {
+                    violations.push(e);
+                }
+            }
+        }
+        if name == "p" {
+            let begin = node.attribute("begin").and_then(|v| parse_time_expression(v, &params).ok());
+            let end = node.attribute("end").and_then(|v| parse_time_expression(v, &params).ok());
+            if let (Some(b), Some(e)) = (begin, end) {
+                if e <= b {
+                    violations.push(format!("<p> 的结束时间 {} 不晚于开始时间 {}", e, b));
+                }
+            }
+        }
+    }
+    for (id, count) in seen_ids {
+        if count > 1 {
+            violations.push(format!("xml:id {} 重复 {} 次", id, count));
+        }
+    }
+
+    violations
+}
+
+/// Export to TTML (IMSC1 Text Profile)
+pub fn export_to_ttml(
+    file_path: &str,
+    entries: &[SubtitleEntry],
+    options: &TtmlExportOptions,
+    header: Option<&TtmlHeader>,
+) -> Result<(), String> {
+    let content = build_ttml(entries, options, header);
+
+    let violations = validate_imsc1_text(&content);
+    if !violations.is_empty() {
+        return Err(format!("TTML 不符合 IMSC1 Text Profile: {}", violations.join("; ")));
+    }
+
+    write_atomic(file_path, content)
+        .map_err(|e| format!("Failed to write TTML file: {}", e))?;
+
+    println!("Successfully exported {} subtitles to TTML: {}", entries.len(), file_path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(start_ms: u64, end_ms: u64, text: &str) -> SubtitleEntry {
+        SubtitleEntry {
+            id: 1,
+            start_time: TimeStamp::from_ms(start_ms),
+            end_time: TimeStamp::from_ms(end_ms),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_time_expressions() {
+        let params = TimingParams { frame_rate: Some(25.0), sub_frame_rate: 1.0, tick_rate: Some(10_000_000) };
+        assert_eq!(parse_time_expression("00:00:01.500", &params).unwrap(), 1500);
+        assert_eq!(parse_time_expression("00:00:01:12", &params).unwrap(), 1480);
+        assert_eq!(parse_time_expression("15000000t", &params).unwrap(), 1500);
+        assert_eq!(parse_time_expression("50f", &params).unwrap(), 2000);
+        assert_eq!(parse_time_expression("1.5s", &params).unwrap(), 1500);
+        assert_eq!(parse_time_expression("250ms", &params).unwrap(), 250);
+        assert_eq!(parse_time_expression("2m", &params).unwrap(), 120000);
+    }
+
+    #[test]
+    fn test_frame_times_default_to_30_fps() {
+        let params = TimingParams { frame_rate: None, sub_frame_rate: 1.0, tick_rate: None };
+        assert_eq!(parse_time_expression("00:00:01:12", &params).unwrap(), 1400);
+        assert_eq!(parse_time_expression("45f", &params).unwrap(), 1500);
+
+        let content = r#"<tt xmlns="http://www.w3.org/ns/ttml"><body><div><p begin="00:00:01:15" end="00:00:03:00">Hi</p></div></body></tt>"#;
+        let (header, entries) = parse_ttml(content).unwrap();
+        assert_eq!(header.frame_rate, None);
+        assert_eq!(entries[0].start_time.to_ms(), 1500);
+        assert_eq!(entries[0].end_time.to_ms(), 3000);
+    }
+
+    #[test]
+    fn test_parse_dfxp_with_frames_and_offsets() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tt xmlns="http://www.w3.org/ns/ttml" xmlns:ttp="http://www.w3.org/ns/ttml#parameter" xmlns:tts="http://www.w3.org/ns/ttml#styling" ttp:frameRate="30" ttp:frameRateMultiplier="1000 1001" xml:lang="ja">
+  <head>
+    <styling><style xml:id="s1" tts:color="yellow"/></styling>
+    <layout><region xml:id="top" tts:origin="10% 5%" tts:extent="80% 20%"/></layout>
+  </head>
+  <body>
+    <div begin="00:00:10.000">
+      <p begin="00:00:00:00" end="00:00:01:29" region="top" tts:fontStyle="italic">こんにちは<br/>
+        世界</p>
+      <p><span begin="2s" end="3s">Span timed</span></p>
+    </div>
+  </body>
+</tt>"#;
+        let (header, entries) = parse_ttml(content).unwrap();
+        assert_eq!(header.language, "ja");
+        assert_eq!(header.styles[0].attributes, vec![("color".to_string(), "yellow".to_string())]);
+        assert_eq!(header.regions[0].id, "top");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].start_time.to_ms(), 10000);
+        assert_eq!(entries[0].end_time.to_ms(), 11968);
+        assert_eq!(entries[0].text, "こんにちは\n世界");
+        let meta = entries[0].ttml_cue.as_ref().unwrap();
+        assert_eq!(meta.region.as_deref(), Some("top"));
+        assert_eq!(meta.attributes, vec![("fontStyle".to_string(), "italic".to_string())]);
+        assert_eq!(entries[1].start_time.to_ms(), 12000);
+        assert_eq!(entries[1].end_time.to_ms(), 13000);
+    }
+
+    #[test]
+    fn test_build_ttml_is_valid_imsc1_and_round_trips() {
+        let entries = vec![entry(1000, 2500, "Hello & <world>\nline two"), entry(3000, 4000, "Second")];
+        for tick_rate in [None, Some(10_000_000)] {
+            let options = TtmlExportOptions { language: "en-US".to_string(), tick_rate, ..Default::default() };
+            let content = build_ttml(&entries, &options, None);
+            assert!(validate_imsc1_text(&content).is_empty(), "{:?}", validate_imsc1_text(&content));
+
+            let (header, parsed) = parse_ttml(&content).unwrap();
+            assert_eq!(header.language, "en-US");
+            assert_eq!(parsed.len(), 2);
+            assert_eq!(parsed[0].text, "Hello & <world>\nline two");
+            assert_eq!(parsed[0].start_time.to_ms(), 1000);
+            assert_eq!(parsed[0].end_time.to_ms(), 2500);
+        }
+    }
+
+    #[test]
+    fn test_build_ttml_keeps_header_timing_parameters() {
+        let entries = vec![entry(1000, 2500, "Hi")];
+        let header = TtmlHeader { frame_rate: Some(30.0 * 1000.0 / 1001.0), tick_rate: Some(10_000_000), ..Default::default() };
+        let content = build_ttml(&entries, &TtmlExportOptions::default(), Some(&header));
+        assert!(content.contains(" ttp:frameRate=\"30\" ttp:frameRateMultiplier=\"1000 1001\""));
+        assert!(content.contains(" ttp:tickRate=\"10000000\""));
+        assert!(content.contains("begin=\"10000000t\""));
+        assert!(!content.contains("xmlns:ttm"));
+        assert!(validate_imsc1_text(&content).is_empty(), "{:?}", validate_imsc1_text(&content));
+
+        let (parsed_header, parsed) = parse_ttml(&content).unwrap();
+        assert_eq!(parsed_header.frame_rate, header.frame_rate);
+        assert_eq!(parsed_header.tick_rate, header.tick_rate);
+        assert_eq!(parsed[0].end_time.to_ms(), 2500);
+
+        let header = TtmlHeader { frame_rate: Some(25.0), ..Default::default() };
+        let content = build_ttml(&entries, &TtmlExportOptions::default(), Some(&header));
+        assert!(content.contains(" ttp:frameRate=\"25\""));
+        assert!(!content.contains("frameRateMultiplier"));
+        assert!(!content.contains("tickRate"));
+    }
+
+    #[test]
+    fn test_validate_imsc1_violations() {
+        let content = r#"<tt xmlns="http://www.w3.org/ns/ttml" xmlns:ttp="http://www.w3.org/ns/ttml#parameter" ttp:timeBase="smpte"><body><div><p begin="5s" end="2s" region="missing">x</p><p begin="10t" end="20t">y</p></div></body></tt>"#;
+        let violations = validate_imsc1_text(content);
+        assert!(violations.iter().any(|v| v.contains("xml:lang")));
+        assert!(violations.iter().any(|v| v.contains("Profile")));
+        assert!(violations.iter().any(|v| v.contains("timeBase")));
+        assert!(violations.iter().any(|v| v.contains("missing")));
+        assert!(violations.iter().any(|v| v.contains("tickRate")));
+        assert!(violations.iter().any(|v| v.contains("结束时间")));
+    }
+}
@@ -0,0 +1,245 @@
+//! WebVTT 读写
+//!
+//! 除了时间和文本，还保留 cue 标识符、cue 设置（`line:`、`position:`、`align:` 等）、
+//! STYLE / REGION / NOTE 块以及文本中的 `<v>` 等标签，使 VTT 文件可以原样往返。
+
+use serde::{Deserialize, Serialize};
+
+use crate::file_safety::write_atomic;
+use super::{SubtitleEntry, TimeStamp};
+
+/// 单个 cue 的 WebVTT 专有属性
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VttCueMeta {
+    /// cue 标识符（原文件中没有时为 None）
+    pub identifier: Option<String>,
+    /// 时间轴后面的 cue 设置原文，例如 `line:0 position:50% align:start`
+    pub settings: String,
+    /// 紧挨在该 cue 之前的 NOTE 块
+    pub notes_before: Vec<String>,
+}
+
+/// WebVTT 文件头：WEBVTT 行后的内容以及 STYLE / REGION / NOTE 块
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VttHeader {
+    /// `WEBVTT` 之后的文本（包括同一块中的元数据行）
+    pub header_text: String,
+    /// 第一个 cue 之前的 STYLE / REGION / NOTE 块（按原顺序）
+    pub blocks: Vec<String>,
+    /// 最后一个 cue 之后的 NOTE 块
+    pub trailing_blocks: Vec<String>,
+}
+
+/// 解析 WebVTT 时间：[HH:]MM:SS.mmm
+fn parse_vtt_time(s: &str) -> Result<TimeStamp, String> {
+    let (clock, ms) = s
+        .trim()
+        .split_once('.')
+        .ok_or_else(|| format!("Invalid VTT timestamp: {}", s))?;
+    let parse = |v: &str| v.parse::<u32>().map_err(|_| format!("Invalid VTT timestamp: {}", s));
+
+    let parts: Vec<&str> = clock.split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [h, m, sec] => (parse(h)?, parse(m)?, parse(sec)?),
+        [m, sec] => (0, parse(m)?, parse(sec)?),
+        _ => return Err(format!("Invalid VTT timestamp: {}", s)),
+    };
+    if ms.len() != 3 {
+        return Err(format!("Invalid VTT timestamp: {}", s));
+    }
+
+    Ok(TimeStamp {
+        hours,
+        minutes,
+        seconds,
+        milliseconds: parse(ms)?,
+    })
+}
+
+fn is_block_of_kind(block: &str, kind: &str) -> bool {
+    let first_line = block.lines().next().unwrap_or("");
+    first_line == kind || first_line.starts_with(&format!("{} ", kind)) || first_line.starts_with(&format!("{}\t", kind))
+}
+
+/// 解析 WebVTT 内容，返回文件头和字幕条目
+pub fn parse_vtt(content: &str) -> Result<(VttHeader, Vec<SubtitleEntry>), String> {
+    let content = super::normalize_line_endings(content);
+    let mut blocks = content.split("\n\n").map(|b| b.trim_matches('\n')).filter(|b| !b.is_empty());
+
+    let first = blocks.next().ok_or("Empty WebVTT file")?;
+    let header_text = first
+        .strip_prefix("WEBVTT")
+        .ok_or("Missing WEBVTT signature")?;
+    if header_text.starts_with(|c: char| !c.is_whitespace()) {
+        return Err("Missing WEBVTT signature".to_string());
+    }
+
+    let mut header = VttHeader {
+        header_text: header_text.to_string(),
+        ..Default::default()
+    };
+    let mut entries: Vec<SubtitleEntry> = Vec::new();
+    let mut pending_notes: Vec<String> = Vec::new();
+
+    for block in blocks {
+        if is_block_of_kind(block, "NOTE") {
+            pending_notes.push(block.to_string());
+            continue;
+        }
+        if entries.is_empty() && (is_block_of_kind(block, "STYLE") || is_block_of_kind(block, "REGION")) {
+            header.blocks.append(&mut pending_notes);
+            header.blocks.push(block.to_string());
+            continue;
+        }
+
+        let lines: Vec<&str> = block.lines().collect();
+        let (identifier, timing_index) = if lines[0].contains("-->") {
+            (None, 0)
+        } else if lines.len() > 1 && lines[1].contains("-->") {
+            (Some(lines[0].to_string()), 1)
+        } else {
+            return Err(format!("Invalid WebVTT block: {}", lines[0]));
+        };
+
+        let timing = lines[timing_index];
+        let (start, rest) = timing.split_once("-->").unwrap_or((timing, ""));
+        let rest = rest.trim_start();
+        let (end, settings) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+        if entries.is_empty() {
+            header.blocks.append(&mut pending_notes);
+        }
+
+        entries.push(SubtitleEntry {
+            id: entries.len() as u32 + 1,
+            start_time: parse_vtt_time(start)?,
+            end_time: parse_vtt_time(end)?,
+            text: lines[timing_index + 1..].join("\n"),
+            vtt_cue: Some(VttCueMeta {
+                identifier,
+                settings: settings.trim().to_string(),
+                notes_before: std::mem::take(&mut pending_notes),
+            }),
+            ..Default::default()
+        });
+    }
+
+    if entries.is_empty() {
+        header.blocks.append(&mut pending_notes);
+    } else {
+        header.trailing_blocks = pending_notes;
+    }
+
+    Ok((header, entries))
+}
+
+/// 生成 WebVTT 文件内容
+/// 带有 `VttCueMeta` 的条目使用原始标识符和 cue 设置，其他条目使用序号作为标识符
+pub fn build_vtt(entries: &[SubtitleEntry], header: Option<&VttHeader>) -> String {
+    let mut blocks: Vec<String> = Vec::new();
+
+    let header_text = header.map(|h| h.header_text.as_str()).unwrap_or("");
+    blocks.push(format!("WEBVTT{}", header_text));
+    if let Some(header) = header {
+        blocks.extend(header.blocks.iter().cloned());
+    }
+
+    for (index, entry) in entries.iter().enumerate() {
+        let mut cue = String::new();
+        match &entry.vtt_cue {
+            Some(meta) => {
+                blocks.extend(meta.notes_before.iter().cloned());
+                if let Some(identifier) = &meta.identifier {
+                    cue.push_str(identifier);
+                    cue.push('\n');
+                }
+            }
+            // Cue identifier (optional but useful)
+            None => cue.push_str(&format!("{}\n", index + 1)),
+        }
+
+        // Timestamp line (VTT uses . instead of ,)
+        cue.push_str(&format!(
+            "{} --> {}",
+            entry.start_time.to_vtt_string(),
+            entry.end_time.to_vtt_string()
+        ));
+        if let Some(settings) = entry.vtt_cue.as_ref().map(|m| m.settings.as_str()).filter(|s| !s.is_empty()) {
+            cue.push(' ');
+            cue.push_str(settings);
+        }
+        cue.push('\n');
+
+        // Subtitle text
+        cue.push_str(&entry.text);
+        blocks.push(cue);
+    }
+
+    if let Some(header) = header {
+        blocks.extend(header.trailing_blocks.iter().cloned());
+    }
+
+    let mut content = blocks.join("\n\n");
+    content.push('\n');
+    content
+}
+
+/// Export to VTT (WebVTT format)
+/// header: 原 VTT 文件的文件头（STYLE / REGION / NOTE 块），用于原样往返
+pub fn export_to_vtt(file_path: &str, entries: &[SubtitleEntry], header: Option<&VttHeader>) -> Result<(), String> {
+    let content = build_vtt(entries, header);
+
+    write_atomic(file_path, content)
+        .map_err(|e| format!("Failed to write VTT file: {}", e))?;
+
+    println!("Successfully exported {} subtitles to VTT: {}", entries.len(), file_path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "WEBVTT - Sample\nKind: captions\n\nSTYLE\n::cue {\n  color: yellow;\n}\n\nNOTE header note\n\nREGION\nid:fred\nwidth:40%\n\nintro\n00:00:01.000 --> 00:00:04.000 line:0 position:50% align:start\n<v Alice>Hello</v>\n\nNOTE\nBetween cues\n\n00:00:05.000 --> 00:00:06.500 region:fred\n<v Bob>Second</v>\nline two\n\nNOTE trailing\n";
+
+    #[test]
+    fn test_parse_vtt() {
+        let (header, entries) = parse_vtt(SAMPLE).unwrap();
+        assert_eq!(header.header_text, " - Sample\nKind: captions");
+        assert_eq!(header.blocks.len(), 3);
+        assert_eq!(header.trailing_blocks, vec!["NOTE trailing".to_string()]);
+        assert_eq!(entries.len(), 2);
+
+        let first = entries[0].vtt_cue.as_ref().unwrap();
+        assert_eq!(first.identifier.as_deref(), Some("intro"));
+        assert_eq!(first.settings, "line:0 position:50% align:start");
+        assert_eq!(entries[0].text, "<v Alice>Hello</v>");
+
+        let second = entries[1].vtt_cue.as_ref().unwrap();
+        assert_eq!(second.identifier, None);
+        assert_eq!(second.notes_before, vec!["NOTE\nBetween cues".to_string()]);
+        assert_eq!(entries[1].end_time.milliseconds, 500);
+    }
+
+    #[test]
+    fn test_vtt_round_trip_unchanged() {
+        let (header, entries) = parse_vtt(SAMPLE).unwrap();
+        assert_eq!(build_vtt(&entries, Some(&header)), SAMPLE);
+    }
+
+    #[test]
+    fn test_parse_vtt_short_timestamps() {
+        let (_, entries) = parse_vtt("WEBVTT\n\n01:02.250 --> 01:03.000\nShort").unwrap();
+        assert_eq!(entries[0].start_time.minutes, 1);
+        assert_eq!(entries[0].start_time.seconds, 2);
+        assert_eq!(entries[0].start_time.milliseconds, 250);
+    }
+
+    #[test]
+    fn test_parse_vtt_requires_signature() {
+        assert!(parse_vtt("1\n00:00:01.000 --> 00:00:02.000\nNo header").is_err());
+        assert!(parse_vtt("WEBVTTX\n\n00:00:01.000 --> 00:00:02.000\nBad").is_err());
+    }
+}
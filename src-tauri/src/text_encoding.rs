@@ -0,0 +1,180 @@
+//! 字幕文件的编码检测与按原编码写回
+//!
+//! 读取时先看 BOM，再猜测无 BOM 的 UTF-16 和合法的 UTF-8，最后交给 chardetng 判断 GBK、Big5、Shift_JIS 等旧编码；
+//! 保存时按原编码和 BOM 写回，目标编码无法表示的字符报错而不是静默替换。
+
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+/// 解码后的文本及其原始编码信息
+#[derive(Debug, Clone)]
+pub struct DecodedText {
+    pub text: String,
+    /// 编码名称（WHATWG 名称，如 "UTF-8"、"GBK"、"Big5"、"Shift_JIS"、"UTF-16LE"）
+    pub encoding: String,
+    /// 原文件是否带 BOM
+    pub has_bom: bool,
+}
+
+/// 在没有 BOM 的情况下猜测 UTF-16：ASCII 为主的文本中，每两个字节就有一个 0
+fn sniff_utf16_without_bom(bytes: &[u8]) -> Option<&'static Encoding> {
+    if bytes.len() < 4 || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let sample = &bytes[..bytes.len().min(4096)];
+    let pairs = sample.len() / 2;
+    let even_zeros = sample.iter().step_by(2).filter(|&&b| b == 0).count();
+    let odd_zeros = sample.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+
+    if odd_zeros * 10 > pairs * 4 && even_zeros * 10 < pairs {
+        Some(UTF_16LE)
+    } else if even_zeros * 10 > pairs * 4 && odd_zeros * 10 < pairs {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// 检测字节流的编码，返回编码和 BOM 长度
+pub fn detect_encoding(bytes: &[u8]) -> (&'static Encoding, usize) {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        return (encoding, bom_len);
+    }
+
+    // 先判断 UTF-16：包含大量 0 字节的内容同样是合法的 UTF-8
+    if let Some(encoding) = sniff_utf16_without_bom(bytes) {
+        return (encoding, 0);
+    }
+
+    if std::str::from_utf8(bytes).is_ok() {
+        return (UTF_8, 0);
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    (detector.guess(None, true), 0)
+}
+
+/// 自动检测编码并解码为 UTF-8 字符串
+pub fn decode_text(bytes: &[u8]) -> DecodedText {
+    let (encoding, bom_len) = detect_encoding(bytes);
+    let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+
+    DecodedText {
+        text: text.into_owned(),
+        encoding: encoding.name().to_string(),
+        has_bom: bom_len > 0,
+    }
+}
+
+/// 按编码名称（支持常见别名，如 "gb2312"、"sjis"、"utf-16"）查找编码
+pub fn lookup_encoding(label: &str) -> Result<&'static Encoding, String> {
+    let label = label.trim();
+    if label.eq_ignore_ascii_case("utf-16") {
+        return Ok(UTF_16LE);
+    }
+    Encoding::for_label(label.as_bytes()).ok_or_else(|| format!("不支持的编码: {}", label))
+}
+
+/// 将文本编码为指定编码的字节流
+/// 如果文本中有目标编码无法表示的字符，返回错误而不是静默替换
+pub fn encode_text(text: &str, encoding: &'static Encoding, with_bom: bool) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(text.len() + 3);
+
+    // encoding_rs 不提供 UTF-16 编码器，需要手动处理
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        let little_endian = encoding == UTF_16LE;
+        if with_bom {
+            bytes.extend_from_slice(if little_endian { &[0xFF, 0xFE] } else { &[0xFE, 0xFF] });
+        }
+        for unit in text.encode_utf16() {
+            let pair = if little_endian { unit.to_le_bytes() } else { unit.to_be_bytes() };
+            bytes.extend_from_slice(&pair);
+        }
+        return Ok(bytes);
+    }
+
+    if encoding == UTF_8 {
+        if with_bom {
+            bytes.extend_from_slice(&[0xEF, 0xBB, 0xBF]);
+        }
+        bytes.extend_from_slice(text.as_bytes());
+        return Ok(bytes);
+    }
+
+    let (encoded, _, had_errors) = encoding.encode(text);
+    if had_errors {
+        let unmappable: String = text
+            .chars()
+            .filter(|c| {
+                let mut buf = [0u8; 4];
+                encoding.encode(c.encode_utf8(&mut buf)).2
+            })
+            .take(10)
+            .collect();
+        return Err(format!(
+            "以下字符无法用 {} 编码保存: {}，请改用 UTF-8",
+            encoding.name(),
+            unmappable
+        ));
+    }
+    bytes.extend_from_slice(&encoded);
+    Ok(bytes)
+}
+
+/// 按指定编码写入文本文件（原子写入），encoding 为 None 时使用 UTF-8
+pub fn write_encoded_file(
+    file_path: &str,
+    text: &str,
+    encoding: Option<&str>,
+    with_bom: bool,
+) -> Result<(), String> {
+    let encoding = match encoding {
+        Some(label) => lookup_encoding(label)?,
+        None => UTF_8,
+    };
+    let bytes = encode_text(text, encoding, with_bom)?;
+    crate::file_safety::write_atomic(file_path, bytes).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_utf8_bom() {
+        let decoded = decode_text(b"\xEF\xBB\xBFhello");
+        assert_eq!(decoded.encoding, "UTF-8");
+        assert!(decoded.has_bom);
+        assert_eq!(decoded.text, "hello");
+    }
+
+    #[test]
+    fn test_gbk_round_trip() {
+        let gbk = lookup_encoding("gbk").unwrap();
+        let text = "1\n00:00:01,000 --> 00:00:02,000\n这是一段中文字幕，用来测试编码检测是否正确。\n";
+        let bytes = encode_text(text, gbk, false).unwrap();
+        let decoded = decode_text(&bytes);
+        assert_eq!(decoded.encoding, "GBK");
+        assert_eq!(decoded.text, text);
+    }
+
+    #[test]
+    fn test_utf16_round_trip() {
+        let utf16 = lookup_encoding("utf-16le").unwrap();
+        let bytes = encode_text("字幕 subtitle", utf16, true).unwrap();
+        let decoded = decode_text(&bytes);
+        assert_eq!(decoded.encoding, "UTF-16LE");
+        assert!(decoded.has_bom);
+        assert_eq!(decoded.text, "字幕 subtitle");
+
+        let without_bom = encode_text("Hello subtitle", utf16, false).unwrap();
+        assert_eq!(decode_text(&without_bom).text, "Hello subtitle");
+    }
+
+    #[test]
+    fn test_unmappable_characters() {
+        let shift_jis = lookup_encoding("shift_jis").unwrap();
+        assert!(encode_text("😀", shift_jis, false).is_err());
+    }
+}
@@ -1111,24 +1111,12 @@ pub async fn transcribe_with_whisper(
         .iter()
         .enumerate()
         .map(|(i, seg)| {
-            let start_ms = (seg.start * 1000.0) as u32;
-            let end_ms = (seg.end * 1000.0) as u32;
-            
             SubtitleEntry {
                 id: (i + 1) as u32,
-                start_time: TimeStamp {
-                    hours: start_ms / 3600000,
-                    minutes: (start_ms % 3600000) / 60000,
-                    seconds: (start_ms % 60000) / 1000,
-                    milliseconds: start_ms % 1000,
-                },
-                end_time: TimeStamp {
-                    hours: end_ms / 3600000,
-                    minutes: (end_ms % 3600000) / 60000,
-                    seconds: (end_ms % 60000) / 1000,
-                    milliseconds: end_ms % 1000,
-                },
+                start_time: TimeStamp::from_seconds(seg.start),
+                end_time: TimeStamp::from_seconds(seg.end),
                 text: seg.text.clone(),
+                ..Default::default()
             }
         })
         .collect();
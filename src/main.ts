@@ -10,6 +10,10 @@ import { listen } from '@tauri-apps/api/event'
 import { open } from '@tauri-apps/plugin-dialog'
 import { invoke } from '@tauri-apps/api/core'
 import logger, { initLogger } from './utils/logger'
+import type { AssStyle, EbuStlOptions, FcpxmlExportOptions, FcpxmlTitleStyle, FileChange, MarkerExportOptions } from './types/subtitle'
+import { handleFileChange } from './utils/fileWatcher'
+import { promptFrameRate, readSubtitleFile } from './utils/subtitleFile'
+import { isProjectFile, openProjectFile } from './utils/projectFile'
 
 if (process.env.NODE_ENV === 'development') {
   devtools.connect('http://localhost', 8098)
@@ -34,12 +38,17 @@ const globalOpenFile = async () => {
       multiple: false,
       filters: [
         {
-          name: 'SRT 字幕文件',
-          extensions: ['srt'],
+          name: '字幕文件',
+          extensions: ['srt', 'ass', 'ssa', 'vtt', 'ttml', 'dfxp', 'sub', 'sbv', 'stl', 'lrc', 'scc', 'fcpxml', 'vosub'],
         },
       ],
     })
 
+    if (selected && isProjectFile(selected)) {
+      await openProjectFile(selected, router)
+      return
+    }
+
     if (selected) {
       const loadStartTime = Date.now()
       const { useSubtitleStore } = await import('./stores/subtitle')
@@ -87,7 +96,8 @@ const globalOpenFile = async () => {
         }
       }
 
-      const srtFile = (await invoke('read_srt', { filePath: selected })) as any
+      const srtFile = await readSubtitleFile(selected)
+      if (!srtFile) return
       await store.loadSRTFile(srtFile)
 
       const loadDuration = Date.now() - loadStartTime
@@ -206,6 +216,26 @@ const globalBatchRemovePunctuation = async () => {
   }
 }
 
+// 全局批量自动换行函数
+const globalBatchBreakLines = async () => {
+  try {
+    const { useSubtitleStore } = await import('./stores/subtitle')
+    const { useConfigStore } = await import('./stores/config')
+    const store = useSubtitleStore()
+    const configStore = useConfigStore()
+    if (store.entries.length === 0) {
+      return
+    }
+    const changed = await store.breakLines(configStore.lineBreakOptions)
+    logger.info('批量自动换行', { entries: store.entries.length, changed })
+    if (changed > 0 && store.currentFilePath) {
+      await store.saveToFile()
+    }
+  } catch (error) {
+    logger.error('批量自动换行失败', { error: String(error) })
+  }
+}
+
 // 全局批量转换为大写函数
 const globalBatchToUpperCase = async () => {
   try {
@@ -328,6 +358,10 @@ const globalExportSubtitles = async (format: string) => {
         milliseconds: e.endTime.milliseconds,
       },
       text: e.text,
+      assEvent: e.assEvent,
+      vttCue: e.vttCue,
+      ttmlCue: e.ttmlCue,
+      lrcWords: e.lrcWords,
     }))
     
     // 根据格式设置文件扩展名和过滤器
@@ -336,7 +370,18 @@ const globalExportSubtitles = async (format: string) => {
       vtt: { ext: 'vtt', name: 'WebVTT 字幕文件' },
       srt: { ext: 'srt', name: 'SRT 字幕文件' },
       markdown: { ext: 'md', name: 'Markdown 文件' },
+      ass: { ext: 'ass', name: 'ASS 字幕文件' },
+      ttml: { ext: 'ttml', name: 'TTML 字幕文件' },
+      sbv: { ext: 'sbv', name: 'YouTube SBV 字幕文件' },
+      microdvd: { ext: 'sub', name: 'MicroDVD 字幕文件' },
+      stl: { ext: 'stl', name: 'Spruce STL 字幕文件' },
+      lrc: { ext: 'lrc', name: 'LRC 歌词文件' },
+      'lrc-word': { ext: 'lrc', name: 'LRC 歌词文件' },
+      'ebu-stl': { ext: 'stl', name: 'EBU STL 字幕文件' },
+      scc: { ext: 'scc', name: 'Scenarist SCC 字幕文件' },
       fcpxml: { ext: 'fcpxml', name: 'Final Cut Pro XML' },
+      edl: { ext: 'edl', name: 'CMX3600 EDL 标记' },
+      xmeml: { ext: 'xml', name: 'Premiere / Resolve XML 标记' },
     }
     
     const config = formatConfig[format]
@@ -345,24 +390,44 @@ const globalExportSubtitles = async (format: string) => {
       return
     }
     
-    // FCPXML 需要选择帧率和字幕位置
+    // FCPXML 需要选择帧率、时间码格式、分辨率、字幕位置和标题样式
     const defaultFps = configStore.defaultFcpxmlFps
     let fps = defaultFps
-    let positionX = 0
-    let positionY = -415
+    let fcpxmlOptions: FcpxmlExportOptions | null = null
     if (format === 'fcpxml') {
+      // 从 FCPXML 导入的文件默认沿用原时间线设置和样式
+      const current = store.currentFcpxml
+      const initialFps = current?.fps ?? defaultFps
+      const initialResolution = current ? `${current.width}x${current.height}` : '1920x1080'
+      // 导入的样式、内置预设、用户保存的预设
+      const builtinStyles = await invoke<FcpxmlTitleStyle[]>('get_fcpxml_title_presets')
+      const styles = [...(current?.style ? [current.style] : []), ...builtinStyles, ...configStore.fcpxmlCustomStyles]
+      const initialStyle = current?.style ?? styles.find(s => s.name === configStore.fcpxmlStyleName) ?? styles[0]
       // 创建一个 Promise 来处理用户选择
-      const result = await new Promise<{ fps: number; posX: number; posY: number } | null>((resolve) => {
+      const result = await new Promise<FcpxmlExportOptions | null>((resolve) => {
         // 创建对话框容器
         const container = document.createElement('div')
         container.className = 'export-dialog-overlay'
         const fpsOptions = [
+          { value: 23.976, label: '23.976 fps (NTSC 电影)' },
           { value: 24, label: '24 fps (电影)' },
           { value: 25, label: '25 fps (PAL)' },
-          { value: 30, label: '30 fps (NTSC)' },
+          { value: 29.97, label: '29.97 fps (NTSC)' },
+          { value: 30, label: '30 fps' },
           { value: 50, label: '50 fps' },
+          { value: 59.94, label: '59.94 fps (NTSC 高帧率)' },
           { value: 60, label: '60 fps (高帧率)' },
         ]
+        const resolutionOptions = [
+          { value: '1280x720', label: '1280 × 720 (720p)' },
+          { value: '1920x1080', label: '1920 × 1080 (1080p)' },
+          { value: '3840x2160', label: '3840 × 2160 (4K UHD)' },
+          { value: '4096x2160', label: '4096 × 2160 (DCI 4K)' },
+          { value: '1080x1920', label: '1080 × 1920 (竖屏)' },
+        ]
+        if (!resolutionOptions.some(opt => opt.value === initialResolution)) {
+          resolutionOptions.push({ value: initialResolution, label: initialResolution.replace('x', ' × ') })
+        }
         container.innerHTML = `
           <div class="export-dialog-backdrop"></div>
           <div class="export-dialog-content">
@@ -374,24 +439,112 @@ const globalExportSubtitles = async (format: string) => {
               <div class="export-form-row">
                 <label class="export-form-label">帧率</label>
                 <select id="fcpxml-fps" class="export-select">
-                  ${fpsOptions.map(opt => `<option value="${opt.value}"${opt.value === defaultFps ? ' selected' : ''}>${opt.label}</option>`).join('')}
+                  ${fpsOptions.map(opt => `<option value="${opt.value}"${Math.abs(opt.value - initialFps) < 0.01 ? ' selected' : ''}>${opt.label}</option>`).join('')}
+                </select>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">时间码</label>
+                <select id="fcpxml-tc-format" class="export-select">
+                  <option value="NDF"${current?.dropFrame ? '' : ' selected'}>NDF (非丢帧)</option>
+                  <option value="DF"${current?.dropFrame ? ' selected' : ''}>DF (丢帧，仅 29.97 / 59.94)</option>
+                </select>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">分辨率</label>
+                <select id="fcpxml-resolution" class="export-select">
+                  ${resolutionOptions.map(opt => `<option value="${opt.value}"${opt.value === initialResolution ? ' selected' : ''}>${opt.label}</option>`).join('')}
                 </select>
               </div>
+              <div class="export-form-row">
+                <label class="export-form-label">导出为</label>
+                <select id="fcpxml-mode" class="export-select">
+                  <option value="titles"${current?.mode === 'captions' ? '' : ' selected'}>标题 (title)</option>
+                  <option value="captions"${current?.mode === 'captions' ? ' selected' : ''}>字幕 (caption)</option>
+                </select>
+              </div>
+              <div class="export-form-row fcpxml-caption-row">
+                <label class="export-form-label">字幕格式 / 语言</label>
+                <div class="export-position-inputs">
+                  <select id="fcpxml-caption-format" class="export-select">
+                    ${['ITT', 'CEA608', 'SRT'].map(f => `<option value="${f}"${f === (current?.captionFormat ?? 'ITT') ? ' selected' : ''}>${f === 'CEA608' ? 'CEA-608' : f}</option>`).join('')}
+                  </select>
+                  <div class="export-input-group">
+                    <input type="text" id="fcpxml-language" placeholder="en" class="export-input" />
+                  </div>
+                </div>
+              </div>
               <div class="export-form-row">
                 <label class="export-form-label">字幕位置</label>
                 <div class="export-position-inputs">
                   <div class="export-input-group">
                     <span class="export-input-prefix">X</span>
-                    <input type="number" id="fcpxml-pos-x" value="0" class="export-input" />
+                    <input type="number" id="fcpxml-pos-x" value="${current?.positionX ?? 0}" class="export-input" />
                     <span class="export-input-suffix">px</span>
                   </div>
                   <div class="export-input-group">
                     <span class="export-input-prefix">Y</span>
-                    <input type="number" id="fcpxml-pos-y" value="-415" class="export-input" />
+                    <input type="number" id="fcpxml-pos-y" value="${current?.positionY ?? -415}" class="export-input" />
                     <span class="export-input-suffix">px</span>
                   </div>
                 </div>
               </div>
+              <div class="export-form-row">
+                <label class="export-form-label">标题样式</label>
+                <select id="fcpxml-style" class="export-select"></select>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">字体 / 字重</label>
+                <div class="export-position-inputs">
+                  <div class="export-input-group">
+                    <input type="text" id="fcpxml-font" class="export-input" />
+                  </div>
+                  <div class="export-input-group">
+                    <input type="text" id="fcpxml-font-face" class="export-input" />
+                  </div>
+                </div>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">字号 / 行距</label>
+                <div class="export-position-inputs">
+                  <div class="export-input-group">
+                    <input type="number" id="fcpxml-font-size" min="1" class="export-input" />
+                    <span class="export-input-suffix">pt</span>
+                  </div>
+                  <div class="export-input-group">
+                    <input type="number" id="fcpxml-line-spacing" class="export-input" />
+                  </div>
+                </div>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">文字颜色 / 描边颜色</label>
+                <div class="export-position-inputs">
+                  <input type="color" id="fcpxml-font-color" class="export-color" />
+                  <input type="color" id="fcpxml-stroke-color" class="export-color" />
+                </div>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">对齐</label>
+                <select id="fcpxml-alignment" class="export-select">
+                  <option value="left">左对齐</option>
+                  <option value="center">居中</option>
+                  <option value="right">右对齐</option>
+                </select>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">标题效果 UID</label>
+                <div class="export-input-group">
+                  <input type="text" id="fcpxml-effect-uid" class="export-input" />
+                </div>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">另存为预设</label>
+                <div class="export-position-inputs">
+                  <div class="export-input-group">
+                    <input type="text" id="fcpxml-style-name" placeholder="预设名称" class="export-input" />
+                  </div>
+                  <button id="fcpxml-save-style" class="export-btn export-btn-cancel" type="button">保存</button>
+                </div>
+              </div>
             </div>
             <div class="export-dialog-footer">
               <button class="export-btn export-btn-cancel" type="button">取消</button>
@@ -401,14 +554,106 @@ const globalExportSubtitles = async (format: string) => {
         `
         document.body.appendChild(container)
         
-        const getValues = () => {
+        const styleSelect = container.querySelector('#fcpxml-style') as HTMLSelectElement
+        const fontInput = container.querySelector('#fcpxml-font') as HTMLInputElement
+        const fontFaceInput = container.querySelector('#fcpxml-font-face') as HTMLInputElement
+        const fontSizeInput = container.querySelector('#fcpxml-font-size') as HTMLInputElement
+        const lineSpacingInput = container.querySelector('#fcpxml-line-spacing') as HTMLInputElement
+        const fontColorInput = container.querySelector('#fcpxml-font-color') as HTMLInputElement
+        const strokeColorInput = container.querySelector('#fcpxml-stroke-color') as HTMLInputElement
+        const alignmentSelect = container.querySelector('#fcpxml-alignment') as HTMLSelectElement
+        const effectUidInput = container.querySelector('#fcpxml-effect-uid') as HTMLInputElement
+        const styleNameInput = container.querySelector('#fcpxml-style-name') as HTMLInputElement
+        const modeSelect = container.querySelector('#fcpxml-mode') as HTMLSelectElement
+        const captionFormatSelect = container.querySelector('#fcpxml-caption-format') as HTMLSelectElement
+        const languageInput = container.querySelector('#fcpxml-language') as HTMLInputElement
+        languageInput.value = current?.language ?? 'en'
+        
+        // 字幕格式和语言只用于 caption，标题样式只用于 title
+        const updateModeRows = () => {
+          const captions = modeSelect.value === 'captions'
+          container.querySelectorAll<HTMLElement>('.fcpxml-caption-row').forEach(row => {
+            row.style.display = captions ? '' : 'none'
+          })
+        }
+        modeSelect.addEventListener('change', updateModeRows)
+        updateModeRows()
+        
+        // 预设名称由用户输入，用 Option 填充避免拼接 HTML
+        const renderStyles = (selected: FcpxmlTitleStyle) => {
+          styleSelect.replaceChildren(...styles.map((s, i) => new Option(s.name, String(i), false, s === selected)))
+        }
+        const fillStyle = (style: FcpxmlTitleStyle) => {
+          fontInput.value = style.font
+          fontFaceInput.value = style.fontFace
+          fontSizeInput.value = String(style.fontSize)
+          lineSpacingInput.value = String(style.lineSpacing)
+          fontColorInput.value = style.fontColor.slice(0, 7)
+          strokeColorInput.value = style.strokeColor.slice(0, 7)
+          alignmentSelect.value = style.alignment
+          effectUidInput.value = style.effectUid
+        }
+        // 以所选预设为基础，颜色保留预设中的透明度
+        const currentStyle = (): FcpxmlTitleStyle => {
+          const base = styles[Number(styleSelect.value)] ?? styles[0]
+          return {
+            ...base,
+            font: fontInput.value.trim() || base.font,
+            fontFace: fontFaceInput.value.trim() || base.fontFace,
+            fontSize: parseFloat(fontSizeInput.value) || base.fontSize,
+            lineSpacing: parseFloat(lineSpacingInput.value) || 0,
+            fontColor: fontColorInput.value + base.fontColor.slice(7),
+            strokeColor: strokeColorInput.value + base.strokeColor.slice(7),
+            alignment: alignmentSelect.value as FcpxmlTitleStyle['alignment'],
+            effectUid: effectUidInput.value.trim() || base.effectUid,
+          }
+        }
+        renderStyles(initialStyle)
+        fillStyle(initialStyle)
+        styleSelect.addEventListener('change', () => fillStyle(styles[Number(styleSelect.value)]))
+        
+        container.querySelector('#fcpxml-save-style')?.addEventListener('click', () => {
+          const name = styleNameInput.value.trim()
+          if (!name) {
+            ElMessage.warning('请输入预设名称')
+            return
+          }
+          if (builtinStyles.some(s => s.name === name)) {
+            ElMessage.warning('不能覆盖内置预设')
+            return
+          }
+          const style = { ...currentStyle(), name }
+          configStore.saveFcpxmlStyle(style)
+          const index = styles.findIndex(s => s.name === name)
+          if (index >= 0) {
+            styles.splice(index, 1, style)
+          } else {
+            styles.push(style)
+          }
+          renderStyles(style)
+          styleNameInput.value = ''
+          ElMessage.success(`已保存预设「${name}」`)
+        })
+        
+        const getValues = (): FcpxmlExportOptions => {
           const fpsSelect = container.querySelector('#fcpxml-fps') as HTMLSelectElement
+          const tcFormatSelect = container.querySelector('#fcpxml-tc-format') as HTMLSelectElement
+          const resolutionSelect = container.querySelector('#fcpxml-resolution') as HTMLSelectElement
           const posXInput = container.querySelector('#fcpxml-pos-x') as HTMLInputElement
           const posYInput = container.querySelector('#fcpxml-pos-y') as HTMLInputElement
+          const [width, height] = (resolutionSelect?.value || '1920x1080').split('x').map(Number)
           return {
-            fps: parseInt(fpsSelect?.value || '25'),
-            posX: parseInt(posXInput?.value || '0'),
-            posY: parseInt(posYInput?.value || '-415'),
+            fps: parseFloat(fpsSelect?.value || '25'),
+            dropFrame: tcFormatSelect?.value === 'DF',
+            width,
+            height,
+            positionX: parseInt(posXInput?.value || '0'),
+            positionY: parseInt(posYInput?.value || '-415'),
+            version: current?.version,
+            style: currentStyle(),
+            mode: modeSelect.value as FcpxmlExportOptions['mode'],
+            captionFormat: captionFormatSelect.value as FcpxmlExportOptions['captionFormat'],
+            language: languageInput.value.trim() || 'en',
           }
         }
         
@@ -448,11 +693,387 @@ const globalExportSubtitles = async (format: string) => {
       if (result === null) {
         return // 用户取消
       }
-      fps = result.fps
-      positionX = result.posX
-      positionY = result.posY
+      fcpxmlOptions = result
+      configStore.fcpxmlStyleName = result.style?.name ?? ''
+      configStore.saveExportSettings()
     }
     
+    // ASS 可配置额外的样式，与原文件的样式一起写入（同名时覆盖）
+    if (format === 'ass') {
+      const confirmed = await new Promise<boolean>((resolve) => {
+        const container = document.createElement('div')
+        container.className = 'export-dialog-overlay'
+        const alignmentOptions = [
+          { value: 1, label: '左下' }, { value: 2, label: '底部居中' }, { value: 3, label: '右下' },
+          { value: 4, label: '左中' }, { value: 5, label: '居中' }, { value: 6, label: '右中' },
+          { value: 7, label: '左上' }, { value: 8, label: '顶部居中' }, { value: 9, label: '右上' },
+        ]
+        container.innerHTML = `
+          <div class="export-dialog-backdrop"></div>
+          <div class="export-dialog-content">
+            <div class="export-dialog-header">
+              <span class="export-dialog-title">导出 ASS</span>
+              <button class="export-dialog-close" type="button">×</button>
+            </div>
+            <div class="export-dialog-body">
+              <div class="export-form-row">
+                <label class="export-form-label">附加样式</label>
+                <select id="ass-style-select" class="export-select"></select>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">样式名称</label>
+                <div class="export-input-group">
+                  <input type="text" id="ass-style-name" placeholder="同名时覆盖原文件样式" class="export-input" />
+                </div>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">字体 / 字号</label>
+                <div class="export-input-group">
+                  <input type="text" id="ass-style-font" class="export-input" />
+                  <input type="number" id="ass-style-size" min="1" class="export-input" />
+                </div>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">文字 / 描边颜色</label>
+                <div class="export-input-group">
+                  <input type="color" id="ass-style-primary" class="export-input" />
+                  <input type="color" id="ass-style-outline-colour" class="export-input" />
+                </div>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">描边 / 阴影</label>
+                <div class="export-input-group">
+                  <input type="number" id="ass-style-outline" min="0" step="0.5" class="export-input" />
+                  <input type="number" id="ass-style-shadow" min="0" step="0.5" class="export-input" />
+                </div>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">对齐 / 垂直边距</label>
+                <div class="export-input-group">
+                  <select id="ass-style-alignment" class="export-select">
+                    ${alignmentOptions.map(opt => `<option value="${opt.value}">${opt.label}</option>`).join('')}
+                  </select>
+                  <input type="number" id="ass-style-margin-v" min="0" class="export-input" />
+                </div>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">字形</label>
+                <div class="export-input-group">
+                  <label><input type="checkbox" id="ass-style-bold" /> 粗体</label>
+                  <label><input type="checkbox" id="ass-style-italic" /> 斜体</label>
+                </div>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label"></label>
+                <div class="export-input-group">
+                  <button id="ass-style-save" class="export-btn" type="button">保存样式</button>
+                  <button id="ass-style-delete" class="export-btn" type="button">删除样式</button>
+                </div>
+              </div>
+            </div>
+            <div class="export-dialog-footer">
+              <button class="export-btn export-btn-cancel" type="button">取消</button>
+              <button class="export-btn export-btn-confirm" type="button">导出</button>
+            </div>
+          </div>
+        `
+        document.body.appendChild(container)
+
+        const input = (id: string) => container.querySelector(`#${id}`) as HTMLInputElement
+        const styleSelect = container.querySelector('#ass-style-select') as HTMLSelectElement
+        const alignmentSelect = container.querySelector('#ass-style-alignment') as HTMLSelectElement
+        // 新样式以原文件的 Default 样式为基础
+        const baseStyle: AssStyle = store.currentAssHeader?.styles.find(s => s.name === 'Default') ?? {
+          name: 'Default', fontname: 'Arial', fontsize: 48,
+          primaryColour: '&H00FFFFFF', secondaryColour: '&H000000FF', outlineColour: '&H00000000', backColour: '&H80000000',
+          bold: false, italic: false, underline: false, strikeOut: false,
+          scaleX: 100, scaleY: 100, spacing: 0, angle: 0, borderStyle: 1, outline: 2, shadow: 1,
+          alignment: 2, marginL: 20, marginR: 20, marginV: 40, encoding: 1,
+        }
+        // ASS 颜色为 &HAABBGGRR，颜色选择器为 #RRGGBB
+        const assToHex = (colour: string) => {
+          const bgr = colour.replace(/^&H/i, '').padStart(8, '0').slice(-6)
+          return `#${bgr.slice(4, 6)}${bgr.slice(2, 4)}${bgr.slice(0, 2)}`
+        }
+        const hexToAss = (hex: string, original: string) => {
+          const alpha = original.replace(/^&H/i, '').padStart(8, '0').slice(0, 2)
+          return `&H${alpha}${hex.slice(5, 7)}${hex.slice(3, 5)}${hex.slice(1, 3)}`.toUpperCase()
+        }
+
+        // 样式名称由用户输入，用 Option 填充避免拼接 HTML
+        const renderStyles = (selected: string) => {
+          styleSelect.replaceChildren(
+            new Option('新样式', '', false, selected === ''),
+            ...configStore.assCustomStyles.map(s => new Option(s.name, s.name, false, s.name === selected)),
+          )
+        }
+        const selectedStyle = () => configStore.assCustomStyles.find(s => s.name === styleSelect.value) ?? baseStyle
+        const fillStyle = (style: AssStyle) => {
+          input('ass-style-name').value = styleSelect.value ? style.name : ''
+          input('ass-style-font').value = style.fontname
+          input('ass-style-size').value = String(style.fontsize)
+          input('ass-style-primary').value = assToHex(style.primaryColour)
+          input('ass-style-outline-colour').value = assToHex(style.outlineColour)
+          input('ass-style-outline').value = String(style.outline)
+          input('ass-style-shadow').value = String(style.shadow)
+          alignmentSelect.value = String(style.alignment)
+          input('ass-style-margin-v').value = String(style.marginV)
+          input('ass-style-bold').checked = style.bold
+          input('ass-style-italic').checked = style.italic
+        }
+        const currentStyle = (name: string): AssStyle => {
+          const base = selectedStyle()
+          return {
+            ...base,
+            name,
+            fontname: input('ass-style-font').value.trim() || base.fontname,
+            fontsize: parseFloat(input('ass-style-size').value) || base.fontsize,
+            primaryColour: hexToAss(input('ass-style-primary').value, base.primaryColour),
+            outlineColour: hexToAss(input('ass-style-outline-colour').value, base.outlineColour),
+            outline: parseFloat(input('ass-style-outline').value) || 0,
+            shadow: parseFloat(input('ass-style-shadow').value) || 0,
+            alignment: parseInt(alignmentSelect.value) || 2,
+            marginV: parseInt(input('ass-style-margin-v').value) || 0,
+            bold: input('ass-style-bold').checked,
+            italic: input('ass-style-italic').checked,
+          }
+        }
+        renderStyles('')
+        fillStyle(baseStyle)
+        styleSelect.addEventListener('change', () => fillStyle(selectedStyle()))
+
+        container.querySelector('#ass-style-save')?.addEventListener('click', () => {
+          const name = input('ass-style-name').value.trim()
+          if (!name || name.includes(',')) {
+            ElMessage.warning('请输入不含逗号的样式名称')
+            return
+          }
+          configStore.saveAssStyle(currentStyle(name))
+          renderStyles(name)
+          ElMessage.success(`已保存样式「${name}」`)
+        })
+        container.querySelector('#ass-style-delete')?.addEventListener('click', () => {
+          if (!styleSelect.value) return
+          configStore.deleteAssStyle(styleSelect.value)
+          renderStyles('')
+          fillStyle(baseStyle)
+        })
+
+        const close = (value: boolean) => {
+          document.removeEventListener('keydown', handleKeydown)
+          document.body.removeChild(container)
+          resolve(value)
+        }
+        const handleKeydown = (e: KeyboardEvent) => {
+          if (e.key === 'Escape') close(false)
+        }
+        container.querySelector('.export-btn-cancel')?.addEventListener('click', () => close(false))
+        container.querySelector('.export-dialog-close')?.addEventListener('click', () => close(false))
+        container.querySelector('.export-dialog-backdrop')?.addEventListener('click', () => close(false))
+        container.querySelector('.export-btn-confirm')?.addEventListener('click', () => close(true))
+        document.addEventListener('keydown', handleKeydown)
+      })
+
+      if (!confirmed) {
+        return // 用户取消
+      }
+    }
+
+    // EBU STL 需要选择帧率、显示标准、字符表和每行最多字符数（默认沿用原文件的 GSI 信息）
+    let ebuStlOptions: EbuStlOptions | null = null
+    if (format === 'ebu-stl') {
+      const current = store.currentEbuStl
+      ebuStlOptions = await new Promise<EbuStlOptions | null>((resolve) => {
+        const container = document.createElement('div')
+        container.className = 'export-dialog-overlay'
+        const fpsOptions = [
+          { value: 25, label: '25 fps (STL25.01)' },
+          { value: 29.97, label: '29.97 fps (STL30.01)' },
+          { value: 30, label: '30 fps (STL30.01)' },
+        ]
+        const displayOptions = [
+          { value: '0', label: '开放字幕' },
+          { value: '1', label: '图文电视 Level 1' },
+          { value: '2', label: '图文电视 Level 2' },
+        ]
+        const tableOptions = [
+          { value: '00', label: '拉丁 (ISO 6937)' },
+          { value: '01', label: '拉丁/西里尔 (ISO 8859-5)' },
+          { value: '02', label: '拉丁/阿拉伯 (ISO 8859-6)' },
+          { value: '03', label: '拉丁/希腊 (ISO 8859-7)' },
+          { value: '04', label: '拉丁/希伯来 (ISO 8859-8)' },
+        ]
+        const currentFps = current?.frameRate ?? 25
+        const currentDisplay = current?.displayStandard ?? '1'
+        const currentTable = current?.characterTable ?? '00'
+        container.innerHTML = `
+          <div class="export-dialog-backdrop"></div>
+          <div class="export-dialog-content">
+            <div class="export-dialog-header">
+              <span class="export-dialog-title">导出 EBU STL</span>
+              <button class="export-dialog-close" type="button">×</button>
+            </div>
+            <div class="export-dialog-body">
+              <div class="export-form-row">
+                <label class="export-form-label">帧率</label>
+                <select id="ebu-stl-fps" class="export-select">
+                  ${fpsOptions.map(opt => `<option value="${opt.value}"${Math.abs(opt.value - currentFps) < 0.01 ? ' selected' : ''}>${opt.label}</option>`).join('')}
+                </select>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">显示标准</label>
+                <select id="ebu-stl-display" class="export-select">
+                  ${displayOptions.map(opt => `<option value="${opt.value}"${opt.value === currentDisplay ? ' selected' : ''}>${opt.label}</option>`).join('')}
+                </select>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">字符表</label>
+                <select id="ebu-stl-table" class="export-select">
+                  ${tableOptions.map(opt => `<option value="${opt.value}"${opt.value === currentTable ? ' selected' : ''}>${opt.label}</option>`).join('')}
+                </select>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">每行最多字符</label>
+                <div class="export-input-group">
+                  <input type="number" id="ebu-stl-row-length" value="${current?.maxRowLength ?? 40}" min="1" max="99" class="export-input" />
+                </div>
+              </div>
+            </div>
+            <div class="export-dialog-footer">
+              <button class="export-btn export-btn-cancel" type="button">取消</button>
+              <button class="export-btn export-btn-confirm" type="button">导出</button>
+            </div>
+          </div>
+        `
+        document.body.appendChild(container)
+
+        const getValues = (): EbuStlOptions => {
+          const fpsSelect = container.querySelector('#ebu-stl-fps') as HTMLSelectElement
+          const displaySelect = container.querySelector('#ebu-stl-display') as HTMLSelectElement
+          const tableSelect = container.querySelector('#ebu-stl-table') as HTMLSelectElement
+          const rowLengthInput = container.querySelector('#ebu-stl-row-length') as HTMLInputElement
+          return {
+            ...(current ?? {}),
+            frameRate: parseFloat(fpsSelect?.value || '25'),
+            displayStandard: displaySelect?.value || '1',
+            characterTable: tableSelect?.value || '00',
+            maxRowLength: parseInt(rowLengthInput?.value || '40'),
+          }
+        }
+
+        const close = (value: EbuStlOptions | null) => {
+          document.removeEventListener('keydown', handleKeydown)
+          document.body.removeChild(container)
+          resolve(value)
+        }
+        const handleKeydown = (e: KeyboardEvent) => {
+          if (e.key === 'Escape') close(null)
+        }
+        container.querySelector('.export-btn-cancel')?.addEventListener('click', () => close(null))
+        container.querySelector('.export-dialog-close')?.addEventListener('click', () => close(null))
+        container.querySelector('.export-dialog-backdrop')?.addEventListener('click', () => close(null))
+        container.querySelector('.export-btn-confirm')?.addEventListener('click', () => close(getValues()))
+        document.addEventListener('keydown', handleKeydown)
+      })
+
+      if (ebuStlOptions === null) {
+        return // 用户取消
+      }
+    }
+
+    // EDL / XML 标记需要选择帧率、时间线起始时间码和标记颜色
+    let markerOptions: MarkerExportOptions | null = null
+    if (format === 'edl' || format === 'xmeml') {
+      markerOptions = await new Promise<MarkerExportOptions | null>((resolve) => {
+        const container = document.createElement('div')
+        container.className = 'export-dialog-overlay'
+        const fpsOptions = [23.976, 24, 25, 29.97, 30, 50, 59.94, 60]
+        const colorOptions = [
+          { value: 'Blue', label: '蓝色' },
+          { value: 'Cyan', label: '青色' },
+          { value: 'Green', label: '绿色' },
+          { value: 'Yellow', label: '黄色' },
+          { value: 'Red', label: '红色' },
+          { value: 'Pink', label: '粉色' },
+          { value: 'Purple', label: '紫色' },
+        ]
+        const initialFps = store.currentFrameRate ?? defaultFps
+        container.innerHTML = `
+          <div class="export-dialog-backdrop"></div>
+          <div class="export-dialog-content">
+            <div class="export-dialog-header">
+              <span class="export-dialog-title">${format === 'edl' ? '导出 EDL 标记' : '导出 XML 标记'}</span>
+              <button class="export-dialog-close" type="button">×</button>
+            </div>
+            <div class="export-dialog-body">
+              <div class="export-form-row">
+                <label class="export-form-label">帧率</label>
+                <select id="marker-fps" class="export-select">
+                  ${fpsOptions.map(value => `<option value="${value}"${Math.abs(value - initialFps) < 0.01 ? ' selected' : ''}>${value} fps</option>`).join('')}
+                </select>
+              </div>
+              <div class="export-form-row">
+                <label class="export-form-label">起始时间码</label>
+                <div class="export-input-group">
+                  <input type="text" id="marker-start-tc" value="01:00:00:00" placeholder="HH:MM:SS:FF" class="export-input" />
+                </div>
+              </div>
+              ${format === 'edl' ? `
+              <div class="export-form-row">
+                <label class="export-form-label">标记颜色</label>
+                <select id="marker-color" class="export-select">
+                  ${colorOptions.map(opt => `<option value="${opt.value}">${opt.label}</option>`).join('')}
+                </select>
+              </div>` : ''}
+            </div>
+            <div class="export-dialog-footer">
+              <button class="export-btn export-btn-cancel" type="button">取消</button>
+              <button class="export-btn export-btn-confirm" type="button">导出</button>
+            </div>
+          </div>
+        `
+        document.body.appendChild(container)
+
+        const getValues = (): MarkerExportOptions => {
+          const fpsSelect = container.querySelector('#marker-fps') as HTMLSelectElement
+          const startInput = container.querySelector('#marker-start-tc') as HTMLInputElement
+          const colorSelect = container.querySelector('#marker-color') as HTMLSelectElement | null
+          return {
+            fps: parseFloat(fpsSelect?.value || '25'),
+            startTimecode: startInput?.value.trim() || '01:00:00:00',
+            color: colorSelect?.value || 'Blue',
+          }
+        }
+
+        const close = (value: MarkerExportOptions | null) => {
+          document.removeEventListener('keydown', handleKeydown)
+          document.body.removeChild(container)
+          resolve(value)
+        }
+        const handleKeydown = (e: KeyboardEvent) => {
+          if (e.key === 'Escape') close(null)
+        }
+        container.querySelector('.export-btn-cancel')?.addEventListener('click', () => close(null))
+        container.querySelector('.export-dialog-close')?.addEventListener('click', () => close(null))
+        container.querySelector('.export-dialog-backdrop')?.addEventListener('click', () => close(null))
+        container.querySelector('.export-btn-confirm')?.addEventListener('click', () => close(getValues()))
+        document.addEventListener('keydown', handleKeydown)
+      })
+
+      if (markerOptions === null) {
+        return // 用户取消
+      }
+    }
+
+    // MicroDVD / Spruce STL 以帧计时，需要帧率（默认沿用原文件帧率）
+    if (format === 'microdvd' || format === 'stl') {
+      const frameRate = await promptFrameRate('导出帧率', store.currentFrameRate ?? defaultFps)
+      if (frameRate === null) {
+        return // 用户取消
+      }
+      fps = frameRate
+    }
+
     // 获取默认文件名
     const currentFileName = store.currentFilePath?.split('/').pop()?.replace('.srt', '') || 'subtitles'
     
@@ -466,15 +1087,36 @@ const globalExportSubtitles = async (format: string) => {
     
     // 调用对应的导出命令
     if (format === 'fcpxml') {
-      await invoke('export_fcpxml', { filePath, entries, fps, positionX, positionY })
+      await invoke('export_fcpxml', { filePath, entries, options: fcpxmlOptions })
     } else if (format === 'txt') {
       await invoke('export_txt', { filePath, entries })
     } else if (format === 'vtt') {
-      await invoke('export_vtt', { filePath, entries })
+      await invoke('export_vtt', { filePath, entries, header: store.currentVttHeader })
     } else if (format === 'srt') {
-      await invoke('write_srt', { filePath, entries })
+      await invoke('write_srt', { filePath, entries, srtOptions: configStore.srtWriteOptions })
     } else if (format === 'markdown') {
       await invoke('export_markdown', { filePath, entries })
+    } else if (format === 'ass') {
+      await invoke('export_ass', { filePath, entries, header: store.currentAssHeader, styles: configStore.assCustomStyles })
+    } else if (format === 'ttml') {
+      await invoke('export_ttml', { filePath, entries, header: store.currentTtmlHeader })
+    } else if (format === 'sbv') {
+      await invoke('export_sbv', { filePath, entries })
+    } else if (format === 'microdvd') {
+      await invoke('export_microdvd', { filePath, entries, fps })
+    } else if (format === 'stl') {
+      await invoke('export_spruce_stl', { filePath, entries, fps })
+    } else if (format === 'ebu-stl') {
+      await invoke('export_ebu_stl', { filePath, entries, options: ebuStlOptions })
+    } else if (format === 'scc') {
+      await invoke('export_scc', { filePath, entries })
+    } else if (format === 'edl') {
+      await invoke('export_edl_markers', { filePath, entries, options: { ...markerOptions, title: currentFileName } })
+    } else if (format === 'xmeml') {
+      await invoke('export_xmeml_markers', { filePath, entries, options: { ...markerOptions, title: currentFileName } })
+    } else if (format === 'lrc' || format === 'lrc-word') {
+      const options = { wordLevel: format === 'lrc-word', endMarkers: true }
+      await invoke('export_lrc', { filePath, entries, options, header: store.currentLrcHeader })
     }
     
     ElMessage.success(`已导出为 ${config.ext.toUpperCase()} 格式`)
@@ -505,7 +1147,18 @@ const globalShowExportDialog = async () => {
       { value: 'vtt', label: 'VTT', desc: 'WebVTT 字幕' },
       { value: 'srt', label: 'SRT', desc: 'SRT 字幕' },
       { value: 'markdown', label: 'Markdown', desc: '带时间戳的文档' },
+      { value: 'ass', label: 'ASS', desc: 'Advanced SubStation Alpha' },
+      { value: 'ttml', label: 'TTML', desc: 'IMSC1 流媒体/广播交付' },
+      { value: 'sbv', label: 'SBV', desc: 'YouTube 字幕' },
+      { value: 'microdvd', label: 'MicroDVD', desc: '以帧计时的 .sub 字幕' },
+      { value: 'stl', label: 'Spruce STL', desc: 'DVD Studio Pro 字幕' },
+      { value: 'lrc', label: 'LRC', desc: '逐行歌词' },
+      { value: 'lrc-word', label: 'LRC（逐字）', desc: '增强格式逐字歌词' },
       { value: 'fcpxml', label: 'FCPXML', desc: 'Final Cut Pro' },
+      { value: 'ebu-stl', label: 'EBU STL', desc: 'Tech 3264 欧洲广播交付' },
+      { value: 'scc', label: 'SCC', desc: 'CEA-608 美国广播交付' },
+      { value: 'edl', label: 'EDL 标记', desc: 'DaVinci Resolve 时间线标记' },
+      { value: 'xmeml', label: 'XML 标记', desc: 'Premiere / Resolve 序列标记' },
     ]
     
     const defaultFormat = configStore.defaultExportFormat
@@ -590,8 +1243,13 @@ const globalOpenRecentFile = async (index: number) => {
 
     const recentFile = configStore.recentFiles[index]
     if (!recentFile) return
+    if (isProjectFile(recentFile.path)) {
+      await openProjectFile(recentFile.path, router)
+      return
+    }
 
-    const srtFile = (await invoke('read_srt', { filePath: recentFile.path })) as any
+    const srtFile = await readSubtitleFile(recentFile.path)
+    if (!srtFile) return
     await subtitleStore.loadSRTFile(srtFile)
 
     const loadDuration = Date.now() - loadStartTime
@@ -639,6 +1297,7 @@ const updateRecentFilesMenu = async () => {
 ;(window as any).__globalBatchAddCJKSpaces = globalBatchAddCJKSpaces
 ;(window as any).__globalBatchRemoveHTML = globalBatchRemoveHTML
 ;(window as any).__globalBatchRemovePunctuation = globalBatchRemovePunctuation
+;(window as any).__globalBatchBreakLines = globalBatchBreakLines
 ;(window as any).__globalBatchToUpperCase = globalBatchToUpperCase
 ;(window as any).__globalBatchToLowerCase = globalBatchToLowerCase
 ;(window as any).__globalBatchToCapitalize = globalBatchToCapitalize
@@ -657,6 +1316,11 @@ listen<void>('menu:open-file', async () => {
   }
 }).catch(() => { })
 
+// 已打开的字幕或音频文件在外部被修改、删除或重命名
+listen<FileChange>('file-changed', async (event) => {
+  await handleFileChange(event.payload)
+}).catch(() => { })
+
 listen<void>('menu:save', async () => {
   // 触发全局回调函数（由各页面注册）
   if ((window as any).__handleMenuSave && typeof (window as any).__handleMenuSave === 'function') {
@@ -693,6 +1357,11 @@ const openFileFromAssociation = async (filePath: string) => {
   logger.info('通过文件关联打开文件', { path: filePath })
   
   try {
+    if (isProjectFile(filePath)) {
+      await openProjectFile(filePath, router)
+      return
+    }
+
     const loadStartTime = Date.now()
     const { useSubtitleStore } = await import('./stores/subtitle')
     const { useConfigStore } = await import('./stores/config')
@@ -739,7 +1408,8 @@ const openFileFromAssociation = async (filePath: string) => {
       }
     }
 
-    const srtFile = (await invoke('read_srt', { filePath: filePath })) as any
+    const srtFile = await readSubtitleFile(filePath)
+    if (!srtFile) return
     await store.loadSRTFile(srtFile)
 
     const loadDuration = Date.now() - loadStartTime
@@ -205,6 +205,11 @@ export const useAudioStore = defineStore('audio', () => {
       if (tab.audio.currentTime > 0) {
         howl.seek(tab.audio.currentTime)
       }
+
+      // 波形因音频文件在外部被修改而清除时重新生成
+      if (!tab.audio.waveform && !isGeneratingWaveform.value) {
+        generateWaveform(tab.audio.filePath).catch(() => {})
+      }
     } catch (error) {
       logger.error('切换音频失败', { tabId, error: String(error) })
       currentHowl.value = null
@@ -285,7 +290,9 @@ export const useAudioStore = defineStore('audio', () => {
     
     // 更新当前 tab 的音频状态
     if (tabManager.activeTab) {
+      const oldFilePath = tabManager.activeTab.audio.filePath
       tabManager.activeTab.audio.filePath = file.path
+      if (oldFilePath !== file.path) tabManager.releaseFiles([oldFilePath])
       tabManager.activeTab.audio.fileName = file.name
       tabManager.activeTab.audio.format = file.format
       tabManager.activeTab.audio.waveform = null
@@ -472,7 +479,9 @@ export const useAudioStore = defineStore('audio', () => {
 
     // 清除当前 tab 的音频状态
     if (tabManager.activeTab) {
+      const oldFilePath = tabManager.activeTab.audio.filePath
       tabManager.activeTab.audio.filePath = null
+      tabManager.releaseFiles([oldFilePath])
       tabManager.activeTab.audio.fileName = null
       tabManager.activeTab.audio.format = null
       tabManager.activeTab.audio.waveform = null
@@ -501,6 +510,20 @@ export const useAudioStore = defineStore('audio', () => {
     unloadAudio()
   }
 
+  // 音频文件在外部被修改：清除相关 tab 的缓存和波形，当前 tab 立即重新加载，其他 tab 切换时重新生成
+  const reloadChangedAudio = async (filePath: string) => {
+    const affected = tabManager.tabs.filter(tab => tab.audio.filePath === filePath)
+    for (const tab of affected) {
+      if (tab.id === tabManager.activeTabId && tab.audio.fileName) {
+        await loadAudio({ name: tab.audio.fileName, path: filePath, duration: 0, format: tab.audio.format || 'mp3' })
+      } else {
+        removeTabCache(tab.id)
+        tab.audio.waveform = null
+      }
+    }
+    logger.info('音频文件已在外部修改，重新加载', { path: filePath, tabs: affected.length })
+  }
+
   // 当 tab 关闭时，清理对应的缓存
   const removeTabCache = (tabId: string) => {
     const cached = howlCache.get(tabId)
@@ -586,6 +609,7 @@ export const useAudioStore = defineStore('audio', () => {
     // 方法
     loadAudio,
     unloadAudio,
+    reloadChangedAudio,
     togglePlay,
     play,
     pause,
@@ -1,6 +1,6 @@
 import { defineStore } from 'pinia'
 import { ref, computed } from 'vue'
-import type { EditorConfig, KeyBinding } from '@/types/subtitle'
+import type { AssStyle, EditorConfig, FcpxmlTitleStyle, KeyBinding, LineBreakOptions, LintProfile, SrtWriteOptions } from '@/types/subtitle'
 import logger from '@/utils/logger'
 
 // 最近文件项
@@ -42,6 +42,21 @@ export const useConfigStore = defineStore('config', () => {
   // 导出设置
   const defaultExportFormat = ref<string>('txt') // 默认导出格式
   const defaultFcpxmlFps = ref<number>(30) // FCPXML 默认帧率
+  const fcpxmlCustomStyles = ref<FcpxmlTitleStyle[]>([]) // 用户保存的 FCPXML 标题样式
+  const fcpxmlStyleName = ref<string>('') // 上次导出 FCPXML 使用的样式预设
+  const assCustomStyles = ref<AssStyle[]>([]) // 导出 ASS 时追加的样式（同名时覆盖原文件中的样式）
+  const srtWriteOptions = ref<SrtWriteOptions>({ renumber: true, lineEnding: 'lf', withBom: null, finalNewline: false }) // 保存和导出 SRT 的格式
+
+  // 自动换行设置
+  const lineBreakAfterTranscription = ref<boolean>(false) // 转录完成后自动换行
+  const lineBreakOptions = ref<LineBreakOptions>({ maxWidth: 42, maxLines: 2 }) // 中文按双宽计，42 约为 21 个汉字
+
+  // 质量检查设置
+  const lintProfileName = ref<string>('Netflix') // 当前使用的检查配置
+  const lintCustomProfiles = ref<LintProfile[]>([]) // 用户保存的检查配置
+
+  // 文件备份设置
+  const backupCount = ref<number>(10) // 覆盖文件前保留的备份数量，0 表示不备份
 
   // 🎄 圣诞彩蛋设置
   const showChristmasSnow = ref<boolean>(true) // 是否显示飘雪效果
@@ -100,6 +115,10 @@ export const useConfigStore = defineStore('config', () => {
     localStorage.setItem('vosub-export', JSON.stringify({
       format: defaultExportFormat.value,
       fcpxmlFps: defaultFcpxmlFps.value,
+      fcpxmlCustomStyles: fcpxmlCustomStyles.value,
+      fcpxmlStyleName: fcpxmlStyleName.value,
+      assCustomStyles: assCustomStyles.value,
+      srtWriteOptions: srtWriteOptions.value,
       showChristmasSnow: showChristmasSnow.value,
     }))
   }
@@ -112,6 +131,10 @@ export const useConfigStore = defineStore('config', () => {
         const parsed = JSON.parse(saved)
         if (parsed.format) defaultExportFormat.value = parsed.format
         if (parsed.fcpxmlFps) defaultFcpxmlFps.value = parsed.fcpxmlFps
+        if (Array.isArray(parsed.fcpxmlCustomStyles)) fcpxmlCustomStyles.value = parsed.fcpxmlCustomStyles
+        if (typeof parsed.fcpxmlStyleName === 'string') fcpxmlStyleName.value = parsed.fcpxmlStyleName
+        if (Array.isArray(parsed.assCustomStyles)) assCustomStyles.value = parsed.assCustomStyles
+        if (parsed.srtWriteOptions) srtWriteOptions.value = { ...srtWriteOptions.value, ...parsed.srtWriteOptions }
         if (typeof parsed.showChristmasSnow === 'boolean') showChristmasSnow.value = parsed.showChristmasSnow
       } catch (e) {
         // ignore
@@ -119,6 +142,121 @@ export const useConfigStore = defineStore('config', () => {
     }
   }
 
+  // 保存 FCPXML 标题样式预设（同名时覆盖）
+  const saveFcpxmlStyle = (style: FcpxmlTitleStyle) => {
+    const index = fcpxmlCustomStyles.value.findIndex(s => s.name === style.name)
+    if (index >= 0) {
+      fcpxmlCustomStyles.value.splice(index, 1, style)
+    } else {
+      fcpxmlCustomStyles.value.push(style)
+    }
+    saveExportSettings()
+  }
+
+  // 删除 FCPXML 标题样式预设
+  const deleteFcpxmlStyle = (name: string) => {
+    fcpxmlCustomStyles.value = fcpxmlCustomStyles.value.filter(s => s.name !== name)
+    saveExportSettings()
+  }
+
+  // 保存 ASS 样式（同名时覆盖）
+  const saveAssStyle = (style: AssStyle) => {
+    const index = assCustomStyles.value.findIndex(s => s.name === style.name)
+    if (index >= 0) {
+      assCustomStyles.value.splice(index, 1, style)
+    } else {
+      assCustomStyles.value.push(style)
+    }
+    saveExportSettings()
+  }
+
+  // 删除 ASS 样式
+  const deleteAssStyle = (name: string) => {
+    assCustomStyles.value = assCustomStyles.value.filter(s => s.name !== name)
+    saveExportSettings()
+  }
+
+  // 保存自动换行设置
+  const saveLineBreakSettings = () => {
+    localStorage.setItem('vosub-linebreak', JSON.stringify({
+      afterTranscription: lineBreakAfterTranscription.value,
+      options: lineBreakOptions.value,
+    }))
+  }
+
+  // 加载自动换行设置
+  const loadLineBreakSettings = () => {
+    const saved = localStorage.getItem('vosub-linebreak')
+    if (saved) {
+      try {
+        const parsed = JSON.parse(saved)
+        if (typeof parsed.afterTranscription === 'boolean') lineBreakAfterTranscription.value = parsed.afterTranscription
+        if (parsed.options) lineBreakOptions.value = { ...lineBreakOptions.value, ...parsed.options }
+      } catch (e) {
+        // ignore
+      }
+    }
+  }
+
+  // 保存文件备份设置
+  const saveBackupSettings = () => {
+    localStorage.setItem('vosub-backup', JSON.stringify({ backupCount: backupCount.value }))
+  }
+
+  // 加载文件备份设置
+  const loadBackupSettings = () => {
+    const saved = localStorage.getItem('vosub-backup')
+    if (saved) {
+      try {
+        const parsed = JSON.parse(saved)
+        if (typeof parsed.backupCount === 'number') backupCount.value = parsed.backupCount
+      } catch (e) {
+        // ignore
+      }
+    }
+  }
+
+  // 保存质量检查设置
+  const saveLintSettings = () => {
+    localStorage.setItem('vosub-lint', JSON.stringify({
+      profileName: lintProfileName.value,
+      customProfiles: lintCustomProfiles.value,
+    }))
+  }
+
+  // 加载质量检查设置
+  const loadLintSettings = () => {
+    const saved = localStorage.getItem('vosub-lint')
+    if (saved) {
+      try {
+        const parsed = JSON.parse(saved)
+        if (typeof parsed.profileName === 'string') lintProfileName.value = parsed.profileName
+        if (Array.isArray(parsed.customProfiles)) lintCustomProfiles.value = parsed.customProfiles
+      } catch (e) {
+        // ignore
+      }
+    }
+  }
+
+  // 保存检查配置（同名时覆盖）并设为当前配置
+  const saveLintProfile = (profile: LintProfile) => {
+    const index = lintCustomProfiles.value.findIndex(p => p.name === profile.name)
+    if (index >= 0) {
+      lintCustomProfiles.value.splice(index, 1, profile)
+    } else {
+      lintCustomProfiles.value.push(profile)
+    }
+    lintProfileName.value = profile.name
+    saveLintSettings()
+  }
+
+  // 删除检查配置
+  const deleteLintProfile = (name: string) => {
+    lintCustomProfiles.value = lintCustomProfiles.value.filter(p => p.name !== name)
+    if (lintProfileName.value === name) lintProfileName.value = 'Netflix'
+    saveLintSettings()
+  }
+
   // 保存更新设置
   const saveUpdateSettings = () => {
     localStorage.setItem('vosub-update', JSON.stringify({
@@ -266,6 +404,9 @@ export const useConfigStore = defineStore('config', () => {
   loadPunctuation()
   loadWhisperSettings()
   loadExportSettings()
+  loadLineBreakSettings()
+  loadBackupSettings()
+  loadLintSettings()
   loadUpdateSettings()
 
   // 检测平台
@@ -307,6 +448,15 @@ export const useConfigStore = defineStore('config', () => {
     fireredPreserveCase,
     defaultExportFormat,
     defaultFcpxmlFps,
+    fcpxmlCustomStyles,
+    fcpxmlStyleName,
+    assCustomStyles,
+    srtWriteOptions,
+    lineBreakAfterTranscription,
+    lineBreakOptions,
+    lintProfileName,
+    lintCustomProfiles,
+    backupCount,
     showChristmasSnow,
     skippedVersion,
     lastUpdateCheck,
@@ -321,6 +471,15 @@ export const useConfigStore = defineStore('config', () => {
     saveWhisperSettings,
     saveExportSettings,
     loadExportSettings,
+    saveFcpxmlStyle,
+    deleteFcpxmlStyle,
+    saveAssStyle,
+    deleteAssStyle,
+    saveLineBreakSettings,
+    saveBackupSettings,
+    saveLintSettings,
+    saveLintProfile,
+    deleteLintProfile,
     skipVersion,
     recordUpdateCheck,
   }
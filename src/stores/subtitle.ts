@@ -1,12 +1,26 @@
 import { defineStore } from 'pinia'
+import { ElMessage } from 'element-plus'
 import { computed } from 'vue'
 import type {
   SubtitleEntry,
   SRTFile,
   TimeConflict,
   HistoryAction,
+  LintProfile,
+  LintReport,
+  LineBreakOptions,
+  SegmentOptions,
+  AutoFixOptions,
+  AutoFixResult,
   TimeStamp,
+  SubtitleFileFormat,
+  AutosaveJournal,
+  JournalTab,
+  MergeResult,
 } from '@/types/subtitle'
+import type { EditorTab } from '@/types/tab'
+import type { CorrectionEntry, CorrectionEntryWithChoice } from '@/types/correction'
+import type { CorrectionDecision, ProjectTrack, ReviewStatus } from '@/types/project'
 import { HistoryActionType } from '@/types/subtitle'
 import { timeStampToMs } from '@/utils/time'
 import { useConfigStore } from '@/stores/config'
@@ -21,6 +35,23 @@ export interface SplitSegment {
   text: string
 }
 
+// 后端不保留校正建议、审校信息等前端字段，按时间和文本从原字幕找回
+const restoreFrontendFields = (previousEntries: SubtitleEntry[], nextEntries: SubtitleEntry[]): SubtitleEntry[] => {
+  const keyOf = (entry: SubtitleEntry) =>
+    `${timeStampToMs(entry.startTime)}-${timeStampToMs(entry.endTime)}-${entry.text}`
+  const previous = new Map(previousEntries.map((entry) => [keyOf(entry), entry]))
+  return nextEntries.map((entry) => {
+    const old = previous.get(keyOf(entry))
+    return {
+      ...entry,
+      needsCorrection: old?.needsCorrection,
+      correctionSuggestion: old?.correctionSuggestion,
+      reviewStatus: old?.reviewStatus,
+      reviewNote: old?.reviewNote,
+    }
+  })
+}
+
 export const useSubtitleStore = defineStore('subtitle', () => {
   const tabManager = useTabManagerStore()
 
@@ -96,6 +127,29 @@ export const useSubtitleStore = defineStore('subtitle', () => {
   const canRedo = computed(() => historyIndex.value < history.value.length - 1)
 
   const currentFilePath = computed(() => tabManager.activeTab?.subtitle.filePath || null)
+  const currentAssHeader = computed(() => tabManager.activeTab?.subtitle.assHeader ?? null)
+  const currentVttHeader = computed(() => tabManager.activeTab?.subtitle.vttHeader ?? null)
+  const currentTtmlHeader = computed(() => tabManager.activeTab?.subtitle.ttmlHeader ?? null)
+  const currentLrcHeader = computed(() => tabManager.activeTab?.subtitle.lrcHeader ?? null)
+  const currentEbuStl = computed(() => tabManager.activeTab?.subtitle.ebuStl ?? null)
+  const currentFcpxml = computed(() => tabManager.activeTab?.subtitle.fcpxml ?? null)
+  const currentFrameRate = computed(() => tabManager.activeTab?.subtitle.frameRate ?? null)
+
+  // 标签页保存时沿用的原文件信息（编码、BOM、文件头、帧率）
+  const fileFormatOf = (tab: EditorTab | null): SubtitleFileFormat => ({
+    encoding: tab?.subtitle.encoding ?? null,
+    withBom: tab?.subtitle.hasBom ?? false,
+    assHeader: tab?.subtitle.assHeader ?? null,
+    vttHeader: tab?.subtitle.vttHeader ?? null,
+    ttmlHeader: tab?.subtitle.ttmlHeader ?? null,
+    lrcHeader: tab?.subtitle.lrcHeader ?? null,
+    ebuStl: tab?.subtitle.ebuStl ?? null,
+    fcpxml: tab?.subtitle.fcpxml ?? null,
+    frameRate: tab?.subtitle.frameRate ?? null,
+  })
+
+  // 保存时沿用的原文件信息（编码、BOM、文件头、帧率）
+  const currentFileFormat = computed<SubtitleFileFormat>(() => fileFormatOf(tabManager.activeTab))
   
   // 最后保存时间
   const lastSavedAt = computed(() => tabManager.activeTab?.subtitle.lastSavedAt || null)
@@ -146,6 +200,94 @@ export const useSubtitleStore = defineStore('subtitle', () => {
     return conflicts
   }
 
+  // 按显示宽度重新换行全部字幕，返回修改的条数
+  const breakLines = async (options: LineBreakOptions): Promise<number> => {
+    const { invoke } = await import('@tauri-apps/api/core')
+    const result = await invoke<SubtitleEntry[]>('break_subtitle_lines', { entries: entries.value, options })
+    let changed = 0
+    result.forEach((wrapped) => {
+      const entry = entries.value.find((e) => e.id === wrapped.id)
+      if (entry && entry.text !== wrapped.text) {
+        entry.text = wrapped.text
+        changed++
+      }
+    })
+
+    if (changed > 0) {
+      addHistory({
+        type: HistoryActionType.BATCH,
+        timestamp: Date.now(),
+        entryId: -1,
+        before: {},
+        after: {},
+        description: '批量自动换行',
+      })
+    }
+    return changed
+  }
+
+  // 分割长字幕（split）或合并碎片字幕（merge），ids 为空时处理全部，返回分割的条数或合并的次数
+  const resegmentEntries = async (mode: 'split' | 'merge', options: SegmentOptions, ids?: number[]): Promise<number> => {
+    const { invoke } = await import('@tauri-apps/api/core')
+    const result = await invoke<{ entries: SubtitleEntry[]; changed: number }>(
+      mode === 'split' ? 'split_long_subtitles' : 'merge_subtitle_fragments',
+      { entries: entries.value, ids: ids ?? null, options },
+    )
+    if (result.changed === 0) return 0
+
+    // 后端已重新编号
+    entries.value.splice(0, entries.value.length, ...restoreFrontendFields(entries.value, result.entries))
+    addHistory({
+      type: HistoryActionType.BATCH,
+      timestamp: Date.now(),
+      entryId: -1,
+      before: {},
+      after: {},
+      description: mode === 'split' ? `分割长字幕 (${result.changed} 条)` : `合并碎片字幕 (${result.changed} 处)`,
+    })
+    detectTimeConflicts()
+    assignSubtitleToTracks()
+    return result.changed
+  }
+
+  // 按交付规范检查字幕（时长、间隔、阅读速度、行长、行数、重叠）
+  const lintSubtitles = async (profile?: LintProfile): Promise<LintReport> => {
+    const { invoke } = await import('@tauri-apps/api/core')
+    return await invoke<LintReport>('lint_subtitles', { entries: entries.value, profile: profile ?? null })
+  }
+
+  // 计算时间轴自动修复结果（不修改当前字幕，确认后用 applyTimingFixes 应用）
+  const previewTimingFixes = async (options: AutoFixOptions, audioPath?: string): Promise<AutoFixResult> => {
+    const { invoke } = await import('@tauri-apps/api/core')
+    return await invoke<AutoFixResult>('auto_fix_subtitle_timing', {
+      entries: entries.value,
+      options,
+      audioPath: audioPath ?? null,
+    })
+  }
+
+  // 应用自动修复结果中被修改的字幕时间
+  const applyTimingFixes = (result: AutoFixResult) => {
+    const fixedIds = new Set(result.fixes.map(f => f.entryId))
+    for (const fixed of result.entries) {
+      if (!fixedIds.has(fixed.id)) continue
+      const entry = entries.value.find(e => e.id === fixed.id)
+      if (!entry) continue
+      entry.startTime = { ...fixed.startTime }
+      entry.endTime = { ...fixed.endTime }
+    }
+
+    addHistory({
+      type: HistoryActionType.BATCH,
+      timestamp: Date.now(),
+      entryId: -1,
+      before: {},
+      after: {},
+      description: `自动修复时间轴 (${fixedIds.size} 条)`,
+    })
+    detectTimeConflicts()
+  }
+
   // 分配字幕到轨道 (支持最多 2 个轨道)（可传入 entries 数组以避免重复获取 computed）
   const assignSubtitleToTracks = (targetEntries?: SubtitleEntry[]) => {
     const currentEntries = targetEntries ?? entries.value
@@ -187,6 +329,9 @@ export const useSubtitleStore = defineStore('subtitle', () => {
   }
 
 
+  // 磁盘上内容的快照（外部修改时作为三方合并的基准），与编辑中的条目互不影响
+  const snapshotOf = (list: SubtitleEntry[]): SubtitleEntry[] => JSON.parse(JSON.stringify(list))
+
   // 加载 SRT 文件（现在由 tabManager 处理创建 tab）
   const loadSRTFile = (file: SRTFile) => {
     // 检查文件是否已经打开
@@ -199,6 +344,16 @@ export const useSubtitleStore = defineStore('subtitle', () => {
 
     // 创建新 tab
     const newTab = tabManager.createTab(file.path, file.entries)
+    newTab.subtitle.encoding = file.encoding ?? null
+    newTab.subtitle.hasBom = file.has_bom ?? false
+    newTab.subtitle.assHeader = file.ass_header ?? null
+    newTab.subtitle.vttHeader = file.vtt_header ?? null
+    newTab.subtitle.ttmlHeader = file.ttml_header ?? null
+    newTab.subtitle.lrcHeader = file.lrc_header ?? null
+    newTab.subtitle.ebuStl = file.ebu_stl ?? null
+    newTab.subtitle.fcpxml = file.fcpxml ?? null
+    newTab.subtitle.frameRate = file.frame_rate ?? null
+    newTab.subtitle.savedEntries = snapshotOf(file.entries)
     
     // 直接使用新 tab 的 entries 进行检测和分配，避免通过 computed 属性访问
     // 这样可以避免响应式追踪的开销
@@ -206,9 +361,199 @@ export const useSubtitleStore = defineStore('subtitle', () => {
     detectTimeConflicts(tabEntries)
     assignSubtitleToTracks(tabEntries)
     
+    if (file.warnings && file.warnings.length > 0) {
+      const skipped = file.warnings.filter((w) => w.auto_fix === null).length
+      logger.warn('SRT 文件解析存在问题', { path: file.path, warnings: file.warnings })
+      ElMessage.warning(
+        skipped > 0
+          ? `文件存在 ${file.warnings.length} 处格式问题，已跳过 ${skipped} 处无法解析的内容`
+          : `文件存在 ${file.warnings.length} 处格式问题，已自动修复`
+      )
+    }
+
     logger.info('SRT 文件加载完成', { path: file.path, entries: file.entries.length })
   }
 
+  // 用磁盘上的新版本替换标签页内容（文件在外部被修改后重新加载），清空撤销历史
+  const reloadTabFromFile = (tabId: string, file: SRTFile) => {
+    const tab = tabManager.tabs.find((t) => t.id === tabId)
+    if (!tab) return
+    Object.assign(tab.subtitle, {
+      entries: file.entries,
+      encoding: file.encoding ?? null,
+      hasBom: file.has_bom ?? false,
+      assHeader: file.ass_header ?? null,
+      vttHeader: file.vtt_header ?? null,
+      ttmlHeader: file.ttml_header ?? null,
+      lrcHeader: file.lrc_header ?? null,
+      ebuStl: file.ebu_stl ?? null,
+      fcpxml: file.fcpxml ?? null,
+      frameRate: file.frame_rate ?? null,
+      savedEntries: snapshotOf(file.entries),
+      history: [],
+      historyIndex: -1,
+      savedHistoryIndex: -1,
+      editingEntryId: null,
+    })
+    if (!file.entries.some((e) => e.id === tab.subtitle.currentEntryId)) {
+      tab.subtitle.currentEntryId = file.entries[0]?.id ?? null
+    }
+    detectTimeConflicts(tab.subtitle.entries)
+    assignSubtitleToTracks(tab.subtitle.entries)
+    logger.info('重新加载外部修改的字幕文件', { path: file.path, entries: file.entries.length })
+  }
+
+  // 把磁盘上的新版本与未保存的修改三方合并（基准为最近一次读取或保存的内容），返回合并结果
+  // 冲突处暂用这里的版本，并标记为需修改，备注中记下外部版本
+  const mergeExternalChanges = async (tabId: string, file: SRTFile): Promise<MergeResult | null> => {
+    const tab = tabManager.tabs.find((t) => t.id === tabId)
+    if (!tab?.subtitle.savedEntries) return null
+
+    const { invoke } = await import('@tauri-apps/api/core')
+    const result = await invoke<MergeResult>('merge_subtitle_versions', {
+      base: tab.subtitle.savedEntries,
+      ours: tab.subtitle.entries,
+      theirs: file.entries,
+    })
+
+    const conflictById = new Map(result.conflicts.map((conflict) => [conflict.entryId, conflict]))
+    const mergedEntries = restoreFrontendFields(tab.subtitle.entries, result.entries).map((merged) => {
+      const conflict = conflictById.get(merged.id)
+      if (conflict) {
+        merged.reviewStatus = 'needsWork'
+        merged.reviewNote = !conflict.theirs
+          ? '合并冲突：外部版本已删除此字幕'
+          : !conflict.ours
+            ? `合并冲突：这里已删除此字幕，外部版本为「${conflict.theirs.text}」`
+            : `合并冲突：外部版本为「${conflict.theirs.text}」`
+      }
+      return merged
+    })
+
+    tab.subtitle.entries.splice(0, tab.subtitle.entries.length, ...mergedEntries)
+    tab.subtitle.savedEntries = snapshotOf(file.entries)
+    tab.subtitle.editingEntryId = null
+    if (!mergedEntries.some((e) => e.id === tab.subtitle.currentEntryId)) {
+      tab.subtitle.currentEntryId = mergedEntries[0]?.id ?? null
+    }
+    addHistory({
+      type: HistoryActionType.BATCH,
+      timestamp: Date.now(),
+      entryId: -1,
+      before: {},
+      after: {},
+      description: `合并外部修改 (${result.applied} 处，冲突 ${result.conflicts.length} 处)`,
+    }, tab)
+    detectTimeConflicts(tab.subtitle.entries)
+    assignSubtitleToTracks(tab.subtitle.entries)
+    logger.info('合并外部修改的字幕文件', {
+      path: file.path,
+      applied: result.applied,
+      conflicts: result.conflicts.length,
+    })
+    return result
+  }
+
+  // 所有未保存标签页的自动保存日志
+  const buildAutosaveJournal = (): AutosaveJournal => ({
+    tabs: tabManager.tabs
+      .filter((tab) => tab.subtitle.filePath && tab.subtitle.historyIndex !== tab.subtitle.savedHistoryIndex)
+      .map((tab) => ({
+        filePath: tab.subtitle.filePath as string,
+        entries: tab.subtitle.entries,
+        format: fileFormatOf(tab),
+        audioPath: tab.audio.filePath,
+      })),
+    savedAt: Date.now(),
+  })
+
+  // 由条目和保存时沿用的原文件信息组成 SRTFile（从自动保存日志或项目恢复时使用）
+  const srtFileFrom = (filePath: string, fileEntries: SubtitleEntry[], format: SubtitleFileFormat): SRTFile => ({
+    name: filePath.split('/').pop() || filePath.split('\\').pop() || 'Untitled',
+    path: filePath,
+    entries: fileEntries,
+    encoding: format.encoding ?? undefined,
+    has_bom: format.withBom,
+    ass_header: format.assHeader ?? undefined,
+    vtt_header: format.vttHeader ?? undefined,
+    ttml_header: format.ttmlHeader ?? undefined,
+    lrc_header: format.lrcHeader ?? undefined,
+    ebu_stl: format.ebuStl ?? undefined,
+    fcpxml: format.fcpxml ?? undefined,
+    frame_rate: format.frameRate ?? undefined,
+  })
+
+  // 从自动保存日志恢复标签页，恢复后的标签页标记为未保存
+  const restoreFromJournal = (journalTab: JournalTab) => {
+    const { filePath, entries: journalEntries, format } = journalTab
+    loadSRTFile(srtFileFrom(filePath, journalEntries, format))
+    // 日志中是未保存的内容，不能作为合并基准
+    if (tabManager.activeTab) tabManager.activeTab.subtitle.savedEntries = null
+    addHistory({
+      type: HistoryActionType.BATCH,
+      timestamp: Date.now(),
+      entryId: -1,
+      before: {},
+      after: {},
+      description: '从自动保存恢复',
+    })
+    logger.info('从自动保存恢复标签页', { path: filePath, entries: journalEntries.length })
+  }
+
+  // 标签页转换为 VoSub 项目轨道：待确认的校正建议和审校信息取自条目，已处理的校正取自标签页
+  const buildProjectTrack = (tab: EditorTab): ProjectTrack => {
+    const tabEntries = tab.subtitle.entries
+    const pending: CorrectionDecision[] = tabEntries
+      .filter((entry) => entry.needsCorrection)
+      .map((entry): CorrectionDecision => ({ entry: correctionEntryOf(entry), status: 'pending' }))
+    return {
+      name: tab.fileName,
+      path: tab.subtitle.filePath,
+      relativePath: null,
+      entries: tabEntries,
+      format: fileFormatOf(tab),
+      corrections: [...tab.subtitle.corrections, ...pending],
+      reviews: tabEntries
+        .filter((entry) => (entry.reviewStatus && entry.reviewStatus !== 'unreviewed') || entry.reviewNote)
+        .map((entry) => ({ entryId: entry.id, status: entry.reviewStatus ?? 'unreviewed', note: entry.reviewNote ?? '' })),
+    }
+  }
+
+  // 从 VoSub 项目轨道恢复标签页（含校正建议和审校信息），返回对应的标签页
+  // 该字幕文件已在其他标签页打开时切换到该标签页，不覆盖其中的内容
+  const restoreProjectTrack = (track: ProjectTrack, projectPath: string): EditorTab | null => {
+    const filePath = track.path ?? projectPath.replace(/[^\\/]*$/, track.name)
+    const pendingById = new Map(
+      track.corrections.filter((c) => c.status === 'pending').map((c) => [c.entry.id, c.entry])
+    )
+    const reviewById = new Map(track.reviews.map((review) => [review.entryId, review]))
+    const trackEntries = track.entries.map((entry) => {
+      const pending = pendingById.get(entry.id)
+      const review = reviewById.get(entry.id)
+      return {
+        ...entry,
+        needsCorrection: pending ? true : undefined,
+        correctionSuggestion: pending?.has_diff ? pending.corrected : undefined,
+        reviewStatus: review?.status,
+        reviewNote: review?.note || undefined,
+      }
+    })
+
+    const alreadyOpen = tabManager.findTabByFilePath(filePath) !== undefined
+    loadSRTFile(srtFileFrom(filePath, trackEntries, track.format))
+    const tab = tabManager.activeTab
+    if (!tab) return null
+    tab.subtitle.projectPath = projectPath
+    if (alreadyOpen) {
+      logger.warn('项目中的字幕文件已打开，保留标签页中的内容', { path: filePath })
+    } else {
+      tab.subtitle.corrections = track.corrections.filter((c) => c.status !== 'pending')
+      // 项目中的内容不一定与字幕文件一致，不能作为合并基准
+      tab.subtitle.savedEntries = null
+    }
+    return tab
+  }
+
   // 根据播放时间获取当前字幕
   const getCurrentEntryByTime = (currentTime: number) => {
     const currentMs = currentTime * 1000
@@ -996,10 +1341,9 @@ export const useSubtitleStore = defineStore('subtitle', () => {
   }
 
   // 添加历史记录
-  const addHistory = (action: HistoryAction) => {
-    if (!tabManager.activeTab) return
-    
-    const tab = tabManager.activeTab
+  const addHistory = (action: HistoryAction, tab: EditorTab | null = tabManager.activeTab) => {
+    if (!tab) return
+
     // 清除当前位置之后的历史
     tab.subtitle.history = tab.subtitle.history.slice(0, tab.subtitle.historyIndex + 1)
     tab.subtitle.history.push(action)
@@ -1280,6 +1624,25 @@ export const useSubtitleStore = defineStore('subtitle', () => {
     return `${pad(time.hours, 2)}:${pad(time.minutes, 2)}:${pad(time.seconds, 2)},${pad(time.milliseconds, 3)}`
   }
 
+  // 标签页属于 VoSub 项目时同时更新项目文件；项目保存失败不影响字幕文件的保存结果
+  const saveLinkedProject = async (tab: EditorTab) => {
+    const projectPath = tab.subtitle.projectPath
+    if (!projectPath) return
+    try {
+      const { saveProject } = await import('@/utils/projectFile')
+      await saveProject(projectPath)
+    } catch (error) {
+      logger.error('更新项目文件失败', { path: projectPath, error: String(error) })
+      ElMessage.warning(`字幕已保存，但更新项目文件失败：${String(error)}`)
+    }
+  }
+
+  // 保存后立即更新自动保存日志，避免 15 秒内退出时下次启动用旧内容“恢复”已保存的文件
+  const writeJournalNow = async () => {
+    const { writeAutosaveJournal } = await import('@/utils/autosaveJournal')
+    await writeAutosaveJournal()
+  }
+
   // 保存到文件
   const saveToFile = async () => {
     const filePath = currentFilePath.value
@@ -1288,17 +1651,24 @@ export const useSubtitleStore = defineStore('subtitle', () => {
     }
 
     const { invoke } = await import('@tauri-apps/api/core')
+    const configStore = useConfigStore()
 
     try {
       await invoke('write_srt', {
         filePath,
         entries: entries.value,
+        format: currentFileFormat.value,
+        srtOptions: configStore.srtWriteOptions,
+        backupCount: configStore.backupCount,
       })
 
       if (tabManager.activeTab) {
         tabManager.activeTab.subtitle.savedHistoryIndex = tabManager.activeTab.subtitle.historyIndex
+        tabManager.activeTab.subtitle.savedEntries = snapshotOf(entries.value)
         tabManager.activeTab.subtitle.lastSavedAt = Date.now()
+        await saveLinkedProject(tabManager.activeTab)
       }
+      await writeJournalNow()
       logger.info('文件保存成功', { path: filePath, entries: entries.value.length })
     } catch (error) {
       logger.error('文件保存失败', { path: filePath, error: String(error) })
@@ -1313,20 +1683,30 @@ export const useSubtitleStore = defineStore('subtitle', () => {
     }
 
     const { invoke } = await import('@tauri-apps/api/core')
+    const configStore = useConfigStore()
 
     try {
       await invoke('write_srt', {
         filePath: newFilePath,
         entries: entries.value,
+        format: currentFileFormat.value,
+        srtOptions: configStore.srtWriteOptions,
+        backupCount: configStore.backupCount,
       })
 
-      // 更新当前 tab 的文件路径
+      // 更新当前 tab 的文件路径，改为监视新文件
+      const oldFilePath = tabManager.activeTab.subtitle.filePath
       tabManager.activeTab.subtitle.filePath = newFilePath
+      invoke('watch_file', { filePath: newFilePath }).catch(() => {})
+      tabManager.releaseFiles([oldFilePath])
       tabManager.activeTab.subtitle.savedHistoryIndex = tabManager.activeTab.subtitle.historyIndex
+      tabManager.activeTab.subtitle.savedEntries = snapshotOf(entries.value)
       
       // 更新 tab 标题
       const fileName = newFilePath.split('/').pop() || newFilePath.split('\\').pop() || 'Untitled'
       tabManager.activeTab.fileName = fileName
+      await saveLinkedProject(tabManager.activeTab)
+      await writeJournalNow()
 
       logger.info('文件另存为成功', { path: newFilePath, entries: entries.value.length })
     } catch (error) {
@@ -1401,6 +1781,36 @@ export const useSubtitleStore = defineStore('subtitle', () => {
     return entries.value.filter(e => e.needsCorrection)
   }
 
+  // 条目当前的校正建议（没有建议时为仅标记）转换为 FireRed 校正结果
+  const correctionEntryOf = (entry: SubtitleEntry): CorrectionEntry => ({
+    id: entry.id,
+    start_time: entry.startTime,
+    end_time: entry.endTime,
+    original: entry.text,
+    corrected: entry.correctionSuggestion ?? entry.text,
+    has_diff: !!entry.correctionSuggestion && entry.correctionSuggestion !== entry.text,
+  })
+
+  // 记录已处理的校正结果（保存在 VoSub 项目中），同一条字幕只保留最近一次处理
+  const recordCorrectionDecision = (decision: CorrectionDecision) => {
+    const tab = tabManager.activeTab
+    if (!tab) return
+    tab.subtitle.corrections = [
+      ...tab.subtitle.corrections.filter((c) => c.entry.id !== decision.entry.id),
+      decision,
+    ]
+  }
+
+  // 记录校正对比对话框中的选择
+  const recordCorrectionDecisions = (results: CorrectionEntryWithChoice[]) => {
+    results
+      .filter((result) => result.has_diff)
+      .forEach(({ choice, finalText, ...entry }) => {
+        const accepted = finalText !== undefined || choice === 'corrected'
+        recordCorrectionDecision({ entry, status: accepted ? 'accepted' : 'rejected', finalText })
+      })
+  }
+
   // 设置校正建议（同时标记为需要校正）
   const setCorrectionSuggestion = (entryId: number, suggestion: string) => {
     const entry = entries.value.find((e) => e.id === entryId)
@@ -1410,12 +1820,18 @@ export const useSubtitleStore = defineStore('subtitle', () => {
     }
   }
 
-  // 应用校正建议
-  const applyCorrectionSuggestion = (entryId: number) => {
+  // 应用校正建议（text 为逐字选择后的最终文本，默认采用整条建议）
+  const applyCorrectionSuggestion = (entryId: number, text?: string) => {
     const entry = entries.value.find((e) => e.id === entryId)
     if (entry && entry.correctionSuggestion) {
       const oldText = entry.text
-      entry.text = entry.correctionSuggestion
+      const newText = text ?? entry.correctionSuggestion
+      recordCorrectionDecision({
+        entry: correctionEntryOf(entry),
+        status: 'accepted',
+        finalText: newText !== entry.correctionSuggestion ? newText : undefined,
+      })
+      entry.text = newText
       entry.correctionSuggestion = undefined
       entry.needsCorrection = false
       
@@ -1436,12 +1852,26 @@ export const useSubtitleStore = defineStore('subtitle', () => {
   const dismissCorrectionSuggestion = (entryId: number) => {
     const entry = entries.value.find((e) => e.id === entryId)
     if (entry) {
+      if (entry.correctionSuggestion) {
+        recordCorrectionDecision({ entry: correctionEntryOf(entry), status: 'rejected' })
+      }
       entry.correctionSuggestion = undefined
       entry.needsCorrection = false
       logger.info('忽略校正建议', { entryId })
     }
   }
 
+  // ============ 审校状态 ============
+
+  // 设置单条字幕的审校状态和备注（保存在 VoSub 项目中）
+  const setEntryReview = (entryId: number, status: ReviewStatus, note: string) => {
+    const entry = entries.value.find((e) => e.id === entryId)
+    if (entry) {
+      entry.reviewStatus = status
+      entry.reviewNote = note || undefined
+    }
+  }
+
   // 需要校正的字幕数量
   const needsCorrectionCount = computed(() => {
     return entries.value.filter(e => e.needsCorrection).length
@@ -1465,10 +1895,24 @@ export const useSubtitleStore = defineStore('subtitle', () => {
     canUndo,
     canRedo,
     currentFilePath,
+    currentAssHeader,
+    currentVttHeader,
+    currentTtmlHeader,
+    currentLrcHeader,
+    currentEbuStl,
+    currentFcpxml,
+    currentFrameRate,
+    currentFileFormat,
     needsCorrectionCount,
 
     // 方法
     loadSRTFile,
+    reloadTabFromFile,
+    mergeExternalChanges,
+    buildAutosaveJournal,
+    restoreFromJournal,
+    buildProjectTrack,
+    restoreProjectTrack,
     getCurrentEntryByTime,
     updateEntryText,
     updateEntryTime,
@@ -1492,6 +1936,11 @@ export const useSubtitleStore = defineStore('subtitle', () => {
     convertEntryToLowerCase,
     convertEntryToCapitalize,
     detectTimeConflicts,
+    breakLines,
+    resegmentEntries,
+    lintSubtitles,
+    previewTimingFixes,
+    applyTimingFixes,
     assignSubtitleToTracks,
     undo,
     redo,
@@ -1511,5 +1960,7 @@ export const useSubtitleStore = defineStore('subtitle', () => {
     setCorrectionSuggestion,
     applyCorrectionSuggestion,
     dismissCorrectionSuggestion,
+    recordCorrectionDecisions,
+    setEntryReview,
   }
 })
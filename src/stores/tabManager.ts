@@ -30,6 +30,18 @@ export const useTabManagerStore = defineStore('tabManager', () => {
     return filePath.split('/').pop() || filePath.split('\\').pop() || 'Untitled'
   }
 
+  // 停止监视不再被任何标签页使用的文件
+  const releaseFiles = async (paths: (string | null)[]) => {
+    const { invoke } = await import('@tauri-apps/api/core')
+    for (const path of paths) {
+      if (!path) continue
+      const inUse = tabs.value.some(tab => tab.subtitle.filePath === path || tab.audio.filePath === path)
+      if (!inUse) {
+        invoke('unwatch_file', { filePath: path }).catch(() => {})
+      }
+    }
+  }
+
   // 创建新标签页
   const createTab = (subtitleFilePath: string, entries: any[]): EditorTab => {
     const id = generateTabId()
@@ -65,6 +77,14 @@ export const useTabManagerStore = defineStore('tabManager', () => {
     
     logger.info('关闭标签页', { id: tabId, fileName: closedTab?.fileName })
 
+    // 其他标签页不再使用的文件停止监视
+    if (closedTab) {
+      releaseFiles([closedTab.subtitle.filePath, closedTab.audio.filePath])
+    }
+
+    // 立即从自动保存日志中去掉已关闭的标签页
+    import('@/utils/autosaveJournal').then(({ writeAutosaveJournal }) => writeAutosaveJournal())
+
     // 如果关闭的是当前激活的标签页，切换到相邻的标签页
     if (activeTabId.value === tabId) {
       if (tabs.value.length > 0) {
@@ -143,6 +163,7 @@ export const useTabManagerStore = defineStore('tabManager', () => {
     updateSubtitleState,
     updateAudioState,
     findTabByFilePath,
+    releaseFiles,
     clearAllTabs,
   }
 })
@@ -0,0 +1,91 @@
+/**
+ * VoSub 项目文件（.vosub）相关类型定义（对应 Rust project 模块）
+ */
+
+import type { SubtitleEntry, SubtitleFileFormat } from './subtitle'
+import type { CorrectionEntry } from './correction'
+
+/**
+ * 校正建议的处理情况
+ */
+export type CorrectionStatus = 'pending' | 'accepted' | 'rejected'
+
+/**
+ * 一条 FireRed 校正结果及用户的处理
+ */
+export interface CorrectionDecision {
+  entry: CorrectionEntry
+  status: CorrectionStatus
+  finalText?: string // 用户逐字选择后的最终文本
+}
+
+/**
+ * 审校状态
+ */
+export type ReviewStatus = 'unreviewed' | 'approved' | 'needsWork'
+
+/**
+ * 单条字幕的审校状态和备注
+ */
+export interface EntryReview {
+  entryId: number
+  status: ReviewStatus
+  note: string
+}
+
+/**
+ * 项目中的字幕轨道
+ */
+export interface ProjectTrack {
+  name: string
+  path: string | null // 字幕文件绝对路径（打开时已解析为实际位置）
+  relativePath: string | null // 相对项目文件的路径（保存时由后端填写）
+  entries: SubtitleEntry[]
+  format: SubtitleFileFormat
+  corrections: CorrectionDecision[]
+  reviews: EntryReview[]
+}
+
+/**
+ * 项目关联的音频
+ */
+export interface ProjectAudio {
+  path: string
+  relativePath: string | null
+  fingerprint: { size: number; hash: string } | null // 保存时由后端计算
+}
+
+/**
+ * 编辑器视图状态
+ */
+export interface EditorViewState {
+  activeTrack: number
+  currentEntryId: number | null
+  currentTime: number // 播放位置（秒）
+  volume: number
+  playbackRate: number
+}
+
+/**
+ * 项目文件内容
+ */
+export interface VosubProject {
+  version: number
+  savedAt: number
+  tracks: ProjectTrack[]
+  audio: ProjectAudio | null
+  view: EditorViewState
+}
+
+/**
+ * 打开项目时音频文件的情况
+ */
+export type AudioLinkStatus = 'found' | 'changed' | 'missing'
+
+/**
+ * 打开的项目（read_vosub_project 返回值）
+ */
+export interface LoadedProject {
+  project: VosubProject
+  audioStatus: AudioLinkStatus | null
+}
@@ -2,6 +2,8 @@
  * VoSub 核心类型定义
  */
 
+import type { ReviewStatus } from './project'
+
 /**
  * 时间戳格式 (SRT 标准: HH:MM:SS,mmm)
  */
@@ -25,6 +27,203 @@ export interface SubtitleEntry {
   trackNumber?: number // 轨道号 (0=默认轨道, 1=冲突轨道)
   needsCorrection?: boolean // 是否需要二次校正（有 AI 校正建议待确认）
   correctionSuggestion?: string // AI 校正建议文本
+  reviewStatus?: ReviewStatus // 审校状态（保存在 VoSub 项目中）
+  reviewNote?: string // 审校备注（保存在 VoSub 项目中）
+  assEvent?: AssEventMeta // ASS/SSA 专有属性（仅从 ASS 文件加载时存在）
+  vttCue?: VttCueMeta // WebVTT 专有属性（仅从 VTT 文件加载时存在）
+  ttmlCue?: TtmlCueMeta // TTML 专有属性（仅从 TTML 文件加载时存在）
+  lrcWords?: LrcWord[] // 增强 LRC 逐字时间（仅从带逐字时间的 LRC 文件加载时存在）
+}
+
+/**
+ * TTML 样式或区域定义
+ */
+export interface TtmlDefinition {
+  id: string // xml:id
+  attributes: [string, string][] // tts:* 属性（不含前缀）
+}
+
+/**
+ * 增强 LRC 的逐字时间片段
+ */
+export interface LrcWord {
+  time: TimeStamp
+  text: string // 片段文本（保留词后的空格）
+}
+
+/**
+ * LRC 元数据标签（[ar:]、[ti:] 等，[offset:] 已在读取时应用）
+ */
+export interface LrcHeader {
+  tags: [string, string][]
+}
+
+/**
+ * EBU STL 导出选项 / GSI 信息
+ */
+export interface EbuStlOptions {
+  frameRate: number // 25 或 30 (29.97)
+  codePage?: string // GSI 代码页，如 "850"
+  displayStandard: string // "0" 开放字幕，"1" / "2" 图文电视
+  characterTable: string // "00" 拉丁 ~ "04" 希伯来
+  languageCode?: string
+  maxRowLength: number // 每行最多字符数
+  maxRows?: number
+  programmeTitle?: string
+  episodeTitle?: string
+  countryOfOrigin?: string
+  publisher?: string
+}
+
+/**
+ * FCPXML 导出选项
+ */
+export interface FcpxmlExportOptions {
+  fps: number // 23.976 / 29.97 / 59.94 使用 1001 时基
+  dropFrame: boolean // 丢帧时间码（仅 29.97 / 59.94）
+  width: number
+  height: number
+  version?: string // FCPXML 版本，默认 1.10
+  positionX: number
+  positionY: number
+  style?: FcpxmlTitleStyle
+  mode?: 'titles' | 'captions' // titles 为图形标题，captions 为 FCP 辅助字幕
+  captionFormat?: 'ITT' | 'CEA608' | 'SRT' // 字幕格式（仅 captions）
+  language?: string // 字幕语言，如 en、zh-Hans（仅 captions）
+}
+
+/**
+ * FCPXML 标题样式（颜色为 #RRGGBB 或 #RRGGBBAA）
+ */
+export interface FcpxmlTitleStyle {
+  name: string // 预设名称
+  font: string
+  fontFace: string // 字重，如 Regular / Semibold / W6
+  fontSize: number
+  fontColor: string
+  bold: boolean
+  italic: boolean
+  strokeColor: string
+  strokeWidth: number // 负值为外描边，0 为不描边
+  shadowColor: string
+  shadowDistance: number
+  shadowAngle: number
+  kerning: number
+  lineSpacing: number
+  alignment: 'left' | 'center' | 'right'
+  effectUid: string // 标题效果 UID
+  effectName: string
+}
+
+/**
+ * EDL / xmeml 标记导出选项
+ */
+export interface MarkerExportOptions {
+  fps: number
+  startTimecode: string // 时间线起始时间码，如 01:00:00:00
+  title?: string // EDL 标题 / 序列名称
+  color?: string // Resolve 标记颜色，如 Blue
+  width?: number
+  height?: number
+}
+
+/**
+ * 违反 CEA-608 限制的字幕（SCC 导出前检查）
+ */
+export interface CaptionViolation {
+  entryId: number
+  issue: string
+}
+
+/**
+ * TTML <p> 专有属性
+ */
+export interface TtmlCueMeta {
+  region: string | null
+  style: string | null
+  attributes: [string, string][] // 行内 tts:* 属性
+}
+
+/**
+ * TTML 文件头
+ */
+export interface TtmlHeader {
+  language: string // xml:lang
+  frameRate: number | null
+  tickRate: number | null
+  styles: TtmlDefinition[]
+  regions: TtmlDefinition[]
+}
+
+/**
+ * WebVTT cue 专有属性
+ */
+export interface VttCueMeta {
+  identifier: string | null // cue 标识符
+  settings: string // cue 设置原文，如 line:0 position:50% align:start
+  notesBefore: string[] // 该 cue 之前的 NOTE 块
+}
+
+/**
+ * WebVTT 文件头
+ */
+export interface VttHeader {
+  headerText: string // WEBVTT 之后的文本
+  blocks: string[] // 第一个 cue 之前的 STYLE / REGION / NOTE 块
+  trailingBlocks: string[] // 最后一个 cue 之后的 NOTE 块
+}
+
+/**
+ * ASS 样式（对应 [V4+ Styles] 中的 Style 行）
+ */
+export interface AssStyle {
+  name: string
+  fontname: string
+  fontsize: number
+  primaryColour: string // &HAABBGGRR
+  secondaryColour: string
+  outlineColour: string
+  backColour: string
+  bold: boolean
+  italic: boolean
+  underline: boolean
+  strikeOut: boolean
+  scaleX: number
+  scaleY: number
+  spacing: number
+  angle: number
+  borderStyle: number
+  outline: number
+  shadow: number
+  alignment: number // 小键盘布局 (1-9)
+  marginL: number
+  marginR: number
+  marginV: number
+  encoding: number
+}
+
+/**
+ * ASS Dialogue 专有属性
+ */
+export interface AssEventMeta {
+  layer: number
+  style: string
+  name: string // 说话人
+  marginL: number
+  marginR: number
+  marginV: number
+  effect: string
+  overrideTags: string // 行首覆盖标签，如 {\an8}
+  comment: boolean // Comment 事件（播放器不显示，保存时原样写回）
+}
+
+/**
+ * ASS 文件头
+ */
+export interface AssHeader {
+  scriptInfo: [string, string][]
+  styles: AssStyle[]
+  extraSections: string
 }
 
 /**
@@ -46,7 +245,125 @@ export interface SRTFile {
   name: string // 文件名
   path: string // 文件路径
   entries: SubtitleEntry[] // 字幕条目列表
-  encoding?: string // 文件编码
+  encoding?: string // 文件编码（自动检测，如 UTF-8 / GBK / Big5 / Shift_JIS / UTF-16LE）
+  has_bom?: boolean // 原文件是否带 BOM
+  ass_header?: AssHeader // ASS/SSA 文件头（仅从 ASS 文件加载时存在）
+  vtt_header?: VttHeader // WebVTT 文件头（仅从 VTT 文件加载时存在）
+  ttml_header?: TtmlHeader // TTML 文件头（仅从 TTML 文件加载时存在）
+  lrc_header?: LrcHeader // LRC 元数据标签（仅从 LRC 文件加载时存在）
+  ebu_stl?: EbuStlOptions // EBU STL 的 GSI 信息（仅从 EBU STL 文件加载时存在）
+  fcpxml?: FcpxmlExportOptions // FCPXML 的时间线设置和标题样式（仅从 FCPXML 文件加载时存在）
+  frame_rate?: number // 帧率（仅从 MicroDVD / Spruce STL 文件加载时存在）
+  warnings?: ParseDiagnostic[] // 解析时发现并已处理的问题
+}
+
+/**
+ * 保存字幕文件时沿用的原文件信息（对应 Rust SubtitleFileFormat）
+ */
+export interface SubtitleFileFormat {
+  encoding: string | null
+  withBom: boolean
+  assHeader: AssHeader | null
+  vttHeader: VttHeader | null
+  ttmlHeader: TtmlHeader | null
+  lrcHeader: LrcHeader | null
+  ebuStl: EbuStlOptions | null
+  fcpxml: FcpxmlExportOptions | null
+  frameRate: number | null
+}
+
+/**
+ * SRT 写入选项（对应 Rust SrtWriteOptions）
+ */
+export interface SrtWriteOptions {
+  renumber: boolean // 从 1 开始重新编号，否则保留原字幕 id
+  lineEnding: 'lf' | 'crlf'
+  withBom: boolean | null // null 时沿用原文件
+  finalNewline: boolean // 最后一条字幕后以换行结尾
+}
+
+/**
+ * 已打开文件在外部发生的变化（后端 file-changed 事件）
+ */
+export interface FileChange {
+  path: string
+  kind: 'modified' | 'deleted' | 'renamed'
+  newPath: string | null // 重命名后的路径
+}
+
+/**
+ * 文字差异片段（按词比较，CJK 逐字）
+ */
+export interface TextSegment {
+  op: 'equal' | 'delete' | 'insert'
+  text: string
+}
+
+/**
+ * 一条字幕在两个版本之间的变化（按时间和文本对齐，而不是按序号）
+ */
+export interface CueChange {
+  kind: 'added' | 'removed' | 'changed'
+  oldIndex: number | null // 在旧版本中的位置
+  newIndex: number | null // 在新版本中的位置
+  retimed: boolean
+  retexted: boolean
+  textDiff: TextSegment[] // 文字差异（仅 retexted 时）
+}
+
+/**
+ * 两个版本的比较结果（diff_subtitle_versions 返回值）
+ */
+export interface SubtitleDiff {
+  changes: CueChange[]
+  unchanged: number
+}
+
+/**
+ * 三方合并冲突：两方对同一条字幕做了不同的修改
+ */
+export interface MergeConflict {
+  base: SubtitleEntry | null // 两方在同一位置各自新增字幕时为空
+  ours: SubtitleEntry | null // 这里已删除时为空
+  theirs: SubtitleEntry | null // 外部已删除时为空
+  entryId: number | null // 合并结果中暂用的字幕编号
+}
+
+/**
+ * 三方合并结果（merge_subtitle_versions 返回值）
+ */
+export interface MergeResult {
+  entries: SubtitleEntry[]
+  conflicts: MergeConflict[]
+  applied: number // 自动合并的外部修改数
+}
+
+/**
+ * 自动保存日志中的未保存标签页
+ */
+export interface JournalTab {
+  filePath: string
+  entries: SubtitleEntry[]
+  format: SubtitleFileFormat // 保存时沿用的原文件信息
+  audioPath: string | null
+}
+
+/**
+ * 自动保存日志（定期写入，下次启动时用于恢复未保存的标签页）
+ */
+export interface AutosaveJournal {
+  tabs: JournalTab[]
+  savedAt: number // 写入时间（毫秒时间戳）
+}
+
+/**
+ * 字幕解析诊断信息
+ */
+export interface ParseDiagnostic {
+  line: number // 行号（从 1 开始）
+  block: number // 字幕块序号（0 表示不属于任何字幕块）
+  issue: string // 问题描述
+  auto_fix: string | null // 已自动应用的修复（null 表示该内容被跳过）
 }
 
 /**
@@ -133,6 +450,95 @@ export interface TimeConflict {
   overlapDuration: number // 重叠时长(毫秒)
 }
 
+/**
+ * 自动换行选项
+ */
+export interface LineBreakOptions {
+  maxWidth: number // 每行最大显示宽度（CJK 字符按 2 计）
+  maxLines: number
+}
+
+/**
+ * 长字幕分割 / 碎片合并选项
+ */
+export interface SegmentOptions {
+  maxWidth: number // 每条字幕最大显示宽度（CJK 按 2 计），0 表示只按句末标点分割
+  minChars: number // 有效字符少于该值视为碎片
+  maxGapMs: number // 与相邻字幕间隔不超过该值才合并
+}
+
+/**
+ * 字幕质量检查规则
+ */
+export type LintRule =
+  | 'overlap'
+  | 'invalidDuration'
+  | 'minDuration'
+  | 'maxDuration'
+  | 'minGap'
+  | 'readingSpeed'
+  | 'lineLength'
+  | 'lineCount'
+
+/**
+ * 质量检查结果
+ */
+export interface LintFinding {
+  entryId: number
+  rule: LintRule
+  severity: 'error' | 'warning'
+  message: string
+  relatedId: number | null // 相关的另一条字幕（重叠、间隔过短）
+}
+
+/**
+ * 质量检查配置（阈值为 0 表示不检查）
+ */
+export interface LintProfile {
+  name: string
+  minDurationMs: number
+  maxDurationMs: number
+  minGapMs: number
+  maxCps: number // 每秒最多字符数
+  maxCjkCps: number
+  maxCharsPerLine: number
+  maxCjkCharsPerLine: number // 含 CJK 的行，半角字符按半个字计
+  maxLines: number
+}
+
+export interface LintReport {
+  findings: LintFinding[]
+  conflicts: TimeConflict[] // 时间重叠
+}
+
+/**
+ * 时间轴自动修复选项（阈值为 0 表示不处理）
+ */
+export interface AutoFixOptions {
+  fixOverlaps: boolean
+  overlapStrategy: 'trimPrevious' | 'split' // 截短前一条 / 在重叠区间内分开
+  minGapMs: number
+  minDurationMs: number
+  maxDurationMs: number
+}
+
+/**
+ * 一次时间调整
+ */
+export interface TimingFix {
+  entryId: number
+  kind: 'overlap' | 'minGap' | 'minDuration' | 'maxDuration'
+  oldStart: TimeStamp
+  oldEnd: TimeStamp
+  newStart: TimeStamp
+  newEnd: TimeStamp
+}
+
+export interface AutoFixResult {
+  entries: SubtitleEntry[]
+  fixes: TimingFix[]
+}
+
 /**
  * 查找结果
  */
@@ -1,4 +1,5 @@
-import type { SubtitleEntry, HistoryAction } from './subtitle'
+import type { SubtitleEntry, HistoryAction, AssHeader, VttHeader, TtmlHeader, LrcHeader, EbuStlOptions, FcpxmlExportOptions } from './subtitle'
+import type { CorrectionDecision } from './project'
 
 // 单个标签页的字幕状态
 export interface TabSubtitleState {
@@ -12,7 +13,19 @@ export interface TabSubtitleState {
   searchResults: number[]
   currentSearchIndex: number
   filePath: string | null
+  encoding: string | null // 原文件编码（保存时按原编码写回）
+  hasBom: boolean // 原文件是否带 BOM
+  assHeader: AssHeader | null // ASS 文件头（保存 ASS 文件时保留样式）
+  vttHeader: VttHeader | null // WebVTT 文件头（保存 VTT 文件时保留 STYLE / REGION / NOTE）
+  ttmlHeader: TtmlHeader | null // TTML 文件头（保存 TTML 文件时保留样式和区域）
+  lrcHeader: LrcHeader | null // LRC 元数据标签（保存 LRC 文件时保留歌手、标题等）
+  ebuStl: EbuStlOptions | null // EBU STL 的 GSI 信息（保存 .stl 时写为 EBU STL 而不是 Spruce STL）
+  fcpxml: FcpxmlExportOptions | null // FCPXML 的时间线设置和标题样式（保存和导出 FCPXML 时沿用）
+  frameRate: number | null // 帧率（保存 MicroDVD / Spruce STL 文件时使用）
   lastSavedAt: number | null // 最后保存时间戳
+  projectPath: string | null // 所属 VoSub 项目文件（保存时同时更新项目）
+  corrections: CorrectionDecision[] // 已处理的 FireRed 校正结果（待确认的保存在条目上）
+  savedEntries: SubtitleEntry[] | null // 最近一次读取或保存时磁盘上的内容（外部修改时作为三方合并的基准）
 }
 
 // 单个标签页的音频状态
@@ -49,7 +62,19 @@ export function createDefaultSubtitleState(): TabSubtitleState {
     searchResults: [],
     currentSearchIndex: 0,
     filePath: null,
+    encoding: null,
+    hasBom: false,
+    assHeader: null,
+    vttHeader: null,
+    ttmlHeader: null,
+    lrcHeader: null,
+    ebuStl: null,
+    fcpxml: null,
+    frameRate: null,
     lastSavedAt: null,
+    projectPath: null,
+    corrections: [],
+    savedEntries: null,
   }
 }
 
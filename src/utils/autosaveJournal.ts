@@ -0,0 +1,115 @@
+import { invoke } from '@tauri-apps/api/core'
+import { getCurrentWindow } from '@tauri-apps/api/window'
+import { ElMessageBox } from 'element-plus'
+import type { Router } from 'vue-router'
+import type { AutosaveJournal } from '@/types/subtitle'
+import { useSubtitleStore } from '@/stores/subtitle'
+import { useAudioStore } from '@/stores/audio'
+import { useTabManagerStore } from '@/stores/tabManager'
+import logger from '@/utils/logger'
+
+// 自动保存日志的写入间隔
+const JOURNAL_INTERVAL_MS = 15_000
+
+let journalTimer: ReturnType<typeof setInterval> | null = null
+let unlistenCloseRequested: (() => void) | null = null
+// 启动后第一次总是写入，恢复或放弃的旧日志随即被覆盖
+let lastSignature: string | null = null
+
+// 标签页未保存状态的摘要，没有变化时不重写日志
+const journalSignature = (): string => {
+  const tabManager = useTabManagerStore()
+  return tabManager.tabs
+    .filter((tab) => tab.subtitle.historyIndex !== tab.subtitle.savedHistoryIndex)
+    .map((tab) => {
+      const last = tab.subtitle.history[tab.subtitle.historyIndex]
+      return `${tab.id}:${tab.subtitle.filePath}:${tab.subtitle.historyIndex}:${last?.timestamp ?? 0}`
+    })
+    .join('|')
+}
+
+/**
+ * 立即写入自动保存日志（所有标签页都已保存时清除日志）
+ */
+export async function writeAutosaveJournal(): Promise<void> {
+  const signature = journalSignature()
+  if (signature === lastSignature) return
+  try {
+    const journal = useSubtitleStore().buildAutosaveJournal()
+    await invoke('write_autosave_journal', { journal })
+    lastSignature = signature
+  } catch (error) {
+    logger.error('写入自动保存日志失败', { error: String(error) })
+  }
+}
+
+/**
+ * 开始定期写入自动保存日志，关闭窗口前再写入一次
+ */
+export function startAutosaveJournal(): void {
+  if (journalTimer) return
+  journalTimer = setInterval(writeAutosaveJournal, JOURNAL_INTERVAL_MS)
+  getCurrentWindow()
+    .onCloseRequested(() => writeAutosaveJournal())
+    .then((unlisten) => {
+      unlistenCloseRequested = unlisten
+    })
+    .catch((error) => logger.error('监听窗口关闭失败', { error: String(error) }))
+}
+
+/**
+ * 停止定期写入自动保存日志
+ */
+export function stopAutosaveJournal(): void {
+  if (journalTimer) {
+    clearInterval(journalTimer)
+    journalTimer = null
+  }
+  unlistenCloseRequested?.()
+  unlistenCloseRequested = null
+}
+
+/**
+ * 启动时检查上次退出前未保存的标签页，询问用户是否恢复
+ * 用户放弃时清除日志；恢复后跳转到编辑器
+ */
+export async function recoverAutosaveJournal(router: Router): Promise<void> {
+  let journal: AutosaveJournal | null
+  try {
+    journal = await invoke<AutosaveJournal | null>('read_autosave_journal')
+  } catch (error) {
+    logger.error('读取自动保存日志失败', { error: String(error) })
+    return
+  }
+  if (!journal || journal.tabs.length === 0) return
+
+  const names = journal.tabs.map((tab) => tab.filePath.split(/[\\/]/).pop()).join('、')
+  const savedAt = new Date(journal.savedAt).toLocaleString()
+  try {
+    await ElMessageBox.confirm(
+      `上次退出时有 ${journal.tabs.length} 个标签页未保存（${names}），最后自动保存于 ${savedAt}。是否恢复？`,
+      '恢复未保存的字幕',
+      { confirmButtonText: '恢复', cancelButtonText: '放弃', type: 'warning' }
+    )
+  } catch {
+    logger.info('用户放弃恢复未保存的标签页', { tabs: journal.tabs.length })
+    await invoke('write_autosave_journal', { journal: { tabs: [], savedAt: Date.now() } }).catch(() => {})
+    return
+  }
+
+  const subtitleStore = useSubtitleStore()
+  const audioStore = useAudioStore()
+  for (const tab of journal.tabs) {
+    subtitleStore.restoreFromJournal(tab)
+    if (tab.audioPath && (await invoke<boolean>('check_file_exists', { filePath: tab.audioPath }))) {
+      const fileName = tab.audioPath.split(/[\\/]/).pop() || 'audio'
+      const format = tab.audioPath.split('.').pop()?.toLowerCase() || 'mp3'
+      await audioStore
+        .loadAudio({ name: fileName, path: tab.audioPath, duration: 0, format })
+        .catch((error) => logger.warn('恢复音频失败', { path: tab.audioPath, error: String(error) }))
+    }
+  }
+  if (router.currentRoute.value.path !== '/editor') {
+    router.push('/editor')
+  }
+}
@@ -0,0 +1,156 @@
+import { invoke } from '@tauri-apps/api/core'
+import { ElMessage, ElMessageBox } from 'element-plus'
+import type { EditorTab } from '@/types/tab'
+import type { FileChange, SubtitleDiff } from '@/types/subtitle'
+import { useSubtitleStore } from '@/stores/subtitle'
+import { useAudioStore } from '@/stores/audio'
+import { useTabManagerStore } from '@/stores/tabManager'
+import { readSubtitleFile } from '@/utils/subtitleFile'
+import logger from '@/utils/logger'
+
+const fileNameOf = (path: string) => path.split(/[\\/]/).pop() || path
+
+// 正在询问是否重新加载的文件，避免同一文件连续修改时重复弹窗
+const promptingPaths = new Set<string>()
+
+// 重新读取文件并替换标签页内容
+const reloadSubtitleTab = async (tab: EditorTab, path: string) => {
+  const file = await readSubtitleFile(path)
+  if (!file) return
+  useSubtitleStore().reloadTabFromFile(tab.id, file)
+}
+
+// 外部修改概要，如「新增 1 条、修改 3 条」
+const describeDiff = (diff: SubtitleDiff) => {
+  const count = (kind: string) => diff.changes.filter((change) => change.kind === kind).length
+  return [
+    ['新增', count('added')],
+    ['删除', count('removed')],
+    ['修改', count('changed')],
+  ]
+    .filter(([, n]) => n)
+    .map(([label, n]) => `${label} ${n} 条`)
+    .join('、')
+}
+
+// 有上次读取或保存的内容作为基准时，询问合并、重新加载还是保留这里的修改
+const promptMerge = async (tab: EditorTab, path: string) => {
+  const file = await readSubtitleFile(path)
+  if (!file || !tab.subtitle.savedEntries) return
+
+  const diff = await invoke<SubtitleDiff>('diff_subtitle_versions', {
+    oldEntries: tab.subtitle.savedEntries,
+    newEntries: file.entries,
+  })
+  if (diff.changes.length === 0) {
+    logger.info('外部修改未改变字幕内容，忽略', { path })
+    return
+  }
+
+  try {
+    await ElMessageBox.confirm(
+      `${fileNameOf(path)} 已被其他程序修改（${describeDiff(diff)}），而这里还有未保存的修改。\n\n合并将保留两边互不冲突的修改，冲突的字幕暂用这里的版本并标记为需修改；重新加载将丢弃这里的修改；关闭则保留这里的修改，下次保存时覆盖外部的修改。`,
+      '文件已在外部修改',
+      {
+        confirmButtonText: '合并',
+        cancelButtonText: '重新加载',
+        type: 'warning',
+        distinguishCancelAndClose: true,
+      }
+    )
+  } catch (action) {
+    if (action === 'cancel') {
+      useSubtitleStore().reloadTabFromFile(tab.id, file)
+    } else {
+      logger.info('保留本地修改，忽略外部修改', { path })
+    }
+    return
+  }
+
+  const result = await useSubtitleStore().mergeExternalChanges(tab.id, file)
+  if (!result) return
+  if (result.conflicts.length > 0) {
+    ElMessage.warning(`已合并 ${result.applied} 处外部修改，${result.conflicts.length} 处冲突已标记为需修改`)
+  } else {
+    ElMessage.success(`已合并 ${result.applied} 处外部修改`)
+  }
+}
+
+// 字幕文件在外部被修改：没有未保存的修改时直接重新加载，否则询问用户
+const handleSubtitleModified = async (tab: EditorTab, path: string) => {
+  const hasUnsavedChanges = tab.subtitle.historyIndex !== tab.subtitle.savedHistoryIndex
+  if (!hasUnsavedChanges) {
+    await reloadSubtitleTab(tab, path)
+    ElMessage.info(`${fileNameOf(path)} 已在外部修改，已重新加载`)
+    return
+  }
+
+  if (promptingPaths.has(path)) return
+  promptingPaths.add(path)
+  try {
+    if (tab.subtitle.savedEntries) {
+      await promptMerge(tab, path)
+      return
+    }
+    await ElMessageBox.confirm(
+      `${fileNameOf(path)} 已被其他程序修改，而这里还有未保存的修改。\n\n重新加载将丢弃这里的修改；保留则下次保存时覆盖外部的修改。`,
+      '文件已在外部修改',
+      {
+        confirmButtonText: '重新加载',
+        cancelButtonText: '保留我的修改',
+        type: 'warning',
+        distinguishCancelAndClose: true,
+      }
+    )
+    await reloadSubtitleTab(tab, path)
+  } catch {
+    logger.info('保留本地修改，忽略外部修改', { path })
+  } finally {
+    promptingPaths.delete(path)
+  }
+}
+
+/**
+ * 处理已打开文件在外部的变化（后端 file-changed 事件）
+ */
+export async function handleFileChange(change: FileChange): Promise<void> {
+  const tabManager = useTabManagerStore()
+  const { path, kind, newPath } = change
+  logger.info('文件在外部发生变化', { path, kind, newPath })
+
+  try {
+    for (const tab of tabManager.tabs.filter((t) => t.subtitle.filePath === path)) {
+      if (kind === 'modified') {
+        await handleSubtitleModified(tab, path)
+      } else if (kind === 'renamed' && newPath) {
+        tab.subtitle.filePath = newPath
+        tab.fileName = fileNameOf(newPath)
+        ElMessage.info(`${fileNameOf(path)} 已重命名为 ${fileNameOf(newPath)}`)
+      } else {
+        ElMessage.warning(`${fileNameOf(path)} 已在外部被删除或移走，保存时将重新创建`)
+      }
+    }
+
+    const audioTabs = tabManager.tabs.filter((t) => t.audio.filePath === path)
+    if (audioTabs.length > 0) {
+      if (kind === 'modified') {
+        await useAudioStore().reloadChangedAudio(path)
+      } else if (kind === 'renamed' && newPath) {
+        audioTabs.forEach((tab) => {
+          tab.audio.filePath = newPath
+          tab.audio.fileName = fileNameOf(newPath)
+        })
+      } else {
+        ElMessage.warning(`音频文件 ${fileNameOf(path)} 已在外部被删除或移走`)
+      }
+    }
+
+    // 重命名后改为监视新路径
+    if (kind === 'renamed' && newPath) {
+      await invoke('watch_file', { filePath: newPath })
+      tabManager.releaseFiles([path])
+    }
+  } catch (error) {
+    logger.error('处理文件变化失败', { path, kind, error: String(error) })
+  }
+}
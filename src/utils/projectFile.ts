@@ -0,0 +1,151 @@
+import { invoke } from '@tauri-apps/api/core'
+import { save } from '@tauri-apps/plugin-dialog'
+import { ElMessage } from 'element-plus'
+import { nextTick } from 'vue'
+import type { Router } from 'vue-router'
+import type { LoadedProject, ProjectAudio, VosubProject } from '@/types/project'
+import { useSubtitleStore } from '@/stores/subtitle'
+import { useAudioStore } from '@/stores/audio'
+import { useTabManagerStore } from '@/stores/tabManager'
+import { useConfigStore } from '@/stores/config'
+import logger from '@/utils/logger'
+
+// 当前支持的项目文件版本（对应 Rust PROJECT_VERSION）
+const PROJECT_VERSION = 1
+
+const fileNameOf = (path: string) => path.split(/[\\/]/).pop() || path
+const audioFormatOf = (path: string) => path.split('.').pop()?.toLowerCase() || 'mp3'
+
+// 打开项目时记录的音频指纹：音频暂时找不到时保存项目不会丢失指纹
+const knownAudio = new Map<string, ProjectAudio>()
+
+/**
+ * 是否为 VoSub 项目文件
+ */
+export function isProjectFile(path: string): boolean {
+  return path.toLowerCase().endsWith('.vosub')
+}
+
+/**
+ * 保存 VoSub 项目：包含属于该项目的所有标签页，音频和视图状态取自当前标签页
+ */
+export async function saveProject(projectPath: string): Promise<void> {
+  const tabManager = useTabManagerStore()
+  const subtitleStore = useSubtitleStore()
+  const audioStore = useAudioStore()
+  const active = tabManager.activeTab
+  if (!active) throw new Error('No active tab')
+
+  active.subtitle.projectPath = projectPath
+  const projectTabs = tabManager.tabs.filter((tab) => tab.subtitle.projectPath === projectPath)
+  const audioPath = active.audio.filePath ?? projectTabs.find((tab) => tab.audio.filePath)?.audio.filePath ?? null
+  const known = knownAudio.get(projectPath)
+
+  const project: VosubProject = {
+    version: PROJECT_VERSION,
+    savedAt: Date.now(),
+    tracks: projectTabs.map((tab) => subtitleStore.buildProjectTrack(tab)),
+    audio: audioPath
+      ? { path: audioPath, relativePath: null, fingerprint: known?.path === audioPath ? known.fingerprint : null }
+      : null,
+    view: {
+      activeTrack: projectTabs.indexOf(active),
+      currentEntryId: active.subtitle.currentEntryId,
+      currentTime: active.audio.filePath ? audioStore.playerState.currentTime : 0,
+      volume: active.audio.volume,
+      playbackRate: active.audio.playbackRate,
+    },
+  }
+  await invoke('write_vosub_project', { projectPath, project })
+  logger.info('保存项目', { path: projectPath, tracks: project.tracks.length })
+}
+
+/**
+ * 把当前标签页保存为新项目（同时保存字幕文件），返回项目路径，用户取消时返回 null
+ */
+export async function saveProjectAs(): Promise<string | null> {
+  const tab = useTabManagerStore().activeTab
+  if (!tab) return null
+
+  const basePath = tab.subtitle.filePath ?? tab.fileName
+  const selected = await save({
+    defaultPath: tab.subtitle.projectPath ?? basePath.replace(/\.[^.\\/]*$/, '') + '.vosub',
+    filters: [{ name: 'VoSub 项目', extensions: ['vosub'] }],
+  })
+  if (!selected) return null
+
+  tab.subtitle.projectPath = selected
+  await useSubtitleStore().saveToFile()
+  useConfigStore().addRecentFile(selected)
+  return selected
+}
+
+/**
+ * 打开 VoSub 项目：每条字幕轨道打开为一个标签页，并恢复音频、校正结果、审校信息和视图状态
+ */
+export async function openProjectFile(projectPath: string, router: Router): Promise<void> {
+  const { project, audioStatus } = await invoke<LoadedProject>('read_vosub_project', { projectPath })
+  if (project.tracks.length === 0) {
+    ElMessage.warning('项目中没有字幕轨道')
+    return
+  }
+
+  const subtitleStore = useSubtitleStore()
+  const audioStore = useAudioStore()
+  const tabManager = useTabManagerStore()
+  const tabs = project.tracks.map((track) => subtitleStore.restoreProjectTrack(track, projectPath))
+  const activeTab = tabs[project.view.activeTrack] ?? tabs[0]
+  const audio = audioStatus !== 'missing' ? project.audio : null
+  if (project.audio) knownAudio.set(projectPath, project.audio)
+
+  // 其他轨道的音频在切换到对应标签页时再加载
+  if (audio) {
+    for (const tab of tabs) {
+      if (tab && tab !== activeTab && !tab.audio.filePath) {
+        Object.assign(tab.audio, {
+          filePath: audio.path,
+          fileName: fileNameOf(audio.path),
+          format: audioFormatOf(audio.path),
+          volume: project.view.volume,
+          playbackRate: project.view.playbackRate,
+        })
+      }
+    }
+  }
+
+  if (activeTab) {
+    tabManager.setActiveTab(activeTab.id)
+    // 等标签页切换处理完再加载音频，避免重复创建播放器
+    await nextTick()
+    if (audio && activeTab.audio.filePath !== audio.path) {
+      await audioStore.loadAudio({
+        name: fileNameOf(audio.path),
+        path: audio.path,
+        duration: 0,
+        format: audioFormatOf(audio.path),
+      })
+    }
+    const { currentEntryId, currentTime, volume, playbackRate } = project.view
+    if (activeTab.subtitle.entries.some((entry) => entry.id === currentEntryId)) {
+      activeTab.subtitle.currentEntryId = currentEntryId
+    }
+    if (activeTab.audio.filePath) {
+      audioStore.setVolume(volume)
+      audioStore.setPlaybackRate(playbackRate)
+      if (currentTime > 0) audioStore.seek(currentTime)
+    }
+  }
+
+  if (audioStatus === 'missing' && project.audio) {
+    ElMessage.warning(`找不到项目关联的音频文件 ${fileNameOf(project.audio.path)}，请重新加载音频`)
+  } else if (audioStatus === 'changed') {
+    ElMessage.warning('项目关联的音频文件与保存项目时不同，字幕时间可能需要重新核对')
+  }
+
+  logger.info('打开项目', { path: projectPath, tracks: project.tracks.length, audioStatus })
+  useConfigStore().addRecentFile(projectPath)
+  if ((window as any).__updateRecentFilesMenu) await (window as any).__updateRecentFilesMenu()
+  if (router.currentRoute.value.path !== '/editor') {
+    router.push('/editor')
+  }
+}
@@ -0,0 +1,45 @@
+import { invoke } from '@tauri-apps/api/core'
+import { ElMessageBox } from 'element-plus'
+import type { SRTFile } from '@/types/subtitle'
+import logger from '@/utils/logger'
+
+// 后端在以帧计时的文件（MicroDVD / Spruce STL）缺少帧率时返回的错误前缀
+const FPS_REQUIRED_ERROR = 'FPS_REQUIRED'
+
+/**
+ * 询问用户帧率，取消时返回 null
+ */
+export async function promptFrameRate(title: string, defaultFps = 25): Promise<number | null> {
+  try {
+    const { value } = await ElMessageBox.prompt('请输入帧率（如 23.976、25、29.97）', title, {
+      confirmButtonText: '确定',
+      cancelButtonText: '取消',
+      inputValue: String(defaultFps),
+      inputPattern: /^\d+(\.\d+)?$/,
+      inputErrorMessage: '请输入有效的帧率',
+    })
+    return parseFloat(value)
+  } catch {
+    return null
+  }
+}
+
+/**
+ * 读取字幕文件；文件以帧计时且未声明帧率时询问用户帧率后重试
+ * 用户取消时返回 null
+ */
+export async function readSubtitleFile(filePath: string): Promise<SRTFile | null> {
+  try {
+    return await invoke<SRTFile>('read_srt', { filePath })
+  } catch (error) {
+    if (!String(error).startsWith(FPS_REQUIRED_ERROR)) {
+      throw error
+    }
+    const fps = await promptFrameRate('该字幕文件未声明帧率')
+    if (fps === null) {
+      logger.warn('用户取消输入帧率', { path: filePath })
+      return null
+    }
+    return await invoke<SRTFile>('read_srt', { filePath, fps })
+  }
+}
@@ -3,27 +3,8 @@
  * 用于将字幕时间对齐到音频波形中的语音区域
  */
 
-import type { TimeStamp } from '@/types/subtitle'
-
-/**
- * 将时间戳转换为毫秒
- */
-export function timestampToMs(ts: TimeStamp): number {
-  return ts.hours * 3600000 + ts.minutes * 60000 + ts.seconds * 1000 + ts.milliseconds
-}
-
-/**
- * 将毫秒转换为时间戳
- */
-export function msToTimestamp(ms: number): TimeStamp {
-  // 确保输入是整数，避免浮点数导致 Rust 后端解析失败
-  ms = Math.round(ms)
-  const hours = Math.floor(ms / 3600000)
-  const minutes = Math.floor((ms % 3600000) / 60000)
-  const seconds = Math.floor((ms % 60000) / 1000)
-  const milliseconds = ms % 1000
-  return { hours, minutes, seconds, milliseconds }
-}
+// 时间换算统一使用 time.ts，这里保留原有导出名
+export { timeStampToMs as timestampToMs, msToTimeStamp as msToTimestamp } from './time'
 
 /**
  * 查找语音区域的起始和结束点